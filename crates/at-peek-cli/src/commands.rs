@@ -0,0 +1,360 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Subcommand implementations
+
+use anyhow::{bail, Context};
+use atproto_client::{
+    create_session, extract_self_labels, resolve_did, resolve_did_document,
+    resolve_labeler_endpoint, AbortHandle, AnalysisEvent, BulkAnalyzer, CheckStatus, Connection,
+    Did, Label, LabelerClient, PostClient, Subject,
+};
+
+use crate::output::{self, Format, Resolution};
+
+/// Sign in with an app password and return the access token
+pub async fn authenticate(login: &str, password: &str) -> anyhow::Result<String> {
+    let session = create_session(login, password)
+        .await
+        .context("Authentication failed")?;
+
+    log::info!("Signed in as {} ({})", session.handle, session.did);
+
+    Ok(session.access_jwt)
+}
+
+/// `check`: labels on a single account or record
+pub async fn check(
+    input: &str,
+    auth_token: Option<String>,
+    format: Format,
+) -> anyhow::Result<bool> {
+    let (subject, did) = input
+        .parse::<Subject>()?
+        .resolve()
+        .await
+        .with_context(|| format!("Failed to resolve {}", input))?;
+
+    let mut labels = labeler_client(auth_token)
+        .query_labels(std::slice::from_ref(&subject))
+        .await
+        .with_context(|| format!("Failed to query labels for {}", subject))?
+        .labels;
+    labels.extend(fetch_self_labels(&subject, &did).await);
+
+    output::print_labels(&subject, &labels, format)?;
+
+    Ok(!labels.is_empty())
+}
+
+/// `bulk`: labels on an account and its most recent posts
+pub async fn bulk(input: &str, analyzer: BulkAnalyzer, format: Format) -> anyhow::Result<bool> {
+    let did = resolve_account(input).await?;
+
+    // Ctrl-C stops the analysis but still prints what was checked so far
+    let abort = AbortHandle::new();
+    let on_interrupt = abort.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            on_interrupt.abort();
+        }
+    });
+
+    let analysis = analyzer
+        .with_abort(abort)
+        .analyze_account(&did, &mut log_progress)
+        .await
+        .with_context(|| format!("Failed to analyze {}", did))?;
+
+    let found = !analysis.stats.account_labels.is_empty()
+        || analysis
+            .labeled_posts
+            .iter()
+            .any(|post| !post.labels.is_empty());
+
+    output::print_bulk(&did, &analysis, format)?;
+
+    if let Some(reason) = &analysis.interruption {
+        eprintln!(
+            "warning: analysis incomplete ({}), {} posts not checked",
+            reason, analysis.pending_posts
+        );
+    }
+
+    Ok(found)
+}
+
+/// `sweep`: every label under an account, flagging labels on deleted records
+pub async fn sweep(input: &str, analyzer: BulkAnalyzer, format: Format) -> anyhow::Result<bool> {
+    let did = resolve_account(input).await?;
+
+    let sweep = analyzer
+        .sweep_account(&did, &mut log_progress)
+        .await
+        .with_context(|| format!("Failed to sweep {}", did))?;
+
+    output::print_sweep(&sweep, format)?;
+
+    if sweep.truncated {
+        eprintln!(
+            "warning: stopped after {} labels, older labels were not read",
+            atproto_client::analysis::MAX_SWEEP_LABELS
+        );
+    }
+
+    Ok(!sweep.labels.is_empty())
+}
+
+/// `batch`: labels on every subject listed in a file
+pub async fn batch(path: &str, analyzer: BulkAnalyzer, format: Format) -> anyhow::Result<bool> {
+    let text = if path == "-" {
+        std::io::read_to_string(std::io::stdin()).context("Failed to read stdin")?
+    } else {
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?
+    };
+    let inputs = Subject::split_list(&text);
+    if inputs.is_empty() {
+        bail!("no handles, DIDs or links found in {}", path);
+    }
+
+    let checks = analyzer.check_subjects(&inputs, &mut log_progress).await;
+    output::print_checks(&checks, format)?;
+
+    let failed = checks
+        .iter()
+        .filter(|check| check.status() == CheckStatus::Failed)
+        .count();
+    if failed == checks.len() {
+        bail!("none of the {} subject(s) could be checked", failed);
+    }
+    if failed > 0 {
+        eprintln!(
+            "warning: {} of {} subject(s) could not be checked",
+            failed,
+            checks.len()
+        );
+    }
+
+    Ok(checks
+        .iter()
+        .any(|check| check.status() == CheckStatus::Labeled))
+}
+
+/// `network`: account labels across an account's follows and followers
+pub async fn network(
+    input: &str,
+    analyzer: BulkAnalyzer,
+    connections: &[Connection],
+    max_accounts: usize,
+    format: Format,
+) -> anyhow::Result<bool> {
+    let did = resolve_account(input).await?;
+
+    let scan = analyzer
+        .scan_network(&did, connections, max_accounts, &mut log_progress)
+        .await
+        .with_context(|| format!("Failed to scan the network of {}", did))?;
+
+    output::print_network(&scan, format)?;
+
+    if scan.truncated {
+        eprintln!(
+            "warning: stopped after {} accounts per list, the rest were not checked",
+            max_accounts
+        );
+    }
+    if let Some(reason) = &scan.interruption {
+        eprintln!("warning: scan stopped early: {}", reason);
+    }
+    if scan.unchecked > 0 {
+        eprintln!(
+            "warning: {} account(s) could not be checked",
+            scan.unchecked
+        );
+    }
+
+    let found = scan.labeled().next().is_some();
+    Ok(found)
+}
+
+/// `thread`: labels on every post and author in a post's thread
+pub async fn thread(input: &str, analyzer: BulkAnalyzer, format: Format) -> anyhow::Result<bool> {
+    let subject: Subject = input.parse()?;
+    if !subject.is_post() {
+        bail!("{} is not a post", input);
+    }
+    let (uri, _) = subject
+        .resolve()
+        .await
+        .with_context(|| format!("Failed to resolve {}", subject.actor))?;
+
+    let thread = analyzer
+        .analyze_thread(&uri, &mut log_progress)
+        .await
+        .with_context(|| format!("Failed to analyze the thread of {}", uri))?;
+
+    output::print_thread(&thread, format)?;
+
+    if let Some(reason) = &thread.interruption {
+        eprintln!("warning: analysis stopped early: {}", reason);
+    }
+    if thread.unchecked > 0 {
+        eprintln!(
+            "warning: {} post(s) or author(s) could not be checked",
+            thread.unchecked
+        );
+    }
+
+    let found = thread.labeled_posts().next().is_some();
+    Ok(found)
+}
+
+/// `list`: labels on a list or starter pack and on every member
+pub async fn list(
+    input: &str,
+    analyzer: BulkAnalyzer,
+    max_members: usize,
+    format: Format,
+) -> anyhow::Result<bool> {
+    let subject: Subject = input.parse()?;
+    if !subject.is_list() {
+        bail!("{} is not a list or starter pack", input);
+    }
+    let (uri, _) = subject
+        .resolve()
+        .await
+        .with_context(|| format!("Failed to resolve {}", subject.actor))?;
+
+    let inspection = analyzer
+        .inspect_list(&uri, max_members, &mut log_progress)
+        .await
+        .with_context(|| format!("Failed to inspect {}", uri))?;
+
+    output::print_list(&inspection, format)?;
+
+    if inspection.truncated {
+        eprintln!(
+            "warning: stopped after {} members, the rest were not checked",
+            max_members
+        );
+    }
+    if let Some(reason) = &inspection.interruption {
+        eprintln!("warning: inspection stopped early: {}", reason);
+    }
+    if inspection.unchecked > 0 {
+        eprintln!(
+            "warning: {} member(s) could not be checked",
+            inspection.unchecked
+        );
+    }
+
+    let found = !inspection.labels.is_empty() || inspection.labeled_members().next().is_some();
+    Ok(found)
+}
+
+/// `resolve`: handle → DID → PDS
+pub async fn resolve(input: &str, format: Format) -> anyhow::Result<()> {
+    let did = resolve_account(input).await?;
+
+    let did_doc = resolve_did_document(&did)
+        .await
+        .context("Failed to fetch DID document")?;
+    let handle = did_doc
+        .get("alsoKnownAs")
+        .and_then(|aka| aka.as_array())
+        .and_then(|aka| {
+            aka.iter()
+                .filter_map(|v| v.as_str())
+                .find_map(|v| v.strip_prefix("at://"))
+        })
+        .map(str::to_string);
+
+    let pds = resolve_did(&did).await.context("Failed to resolve PDS")?;
+
+    output::print_resolution(
+        &Resolution {
+            did: did.as_str().to_string(),
+            handle,
+            pds,
+        },
+        format,
+    )
+}
+
+/// `labels`: everything a labeler has emitted for the given patterns
+pub async fn labels(
+    labeler: &str,
+    subjects: &[String],
+    limit: usize,
+    auth_token: Option<String>,
+    format: Format,
+) -> anyhow::Result<bool> {
+    let labeler_did = Did::new(labeler.to_string());
+    if !labeler_did.validate() {
+        bail!("Invalid labeler DID: {}", labeler);
+    }
+
+    let endpoint = resolve_labeler_endpoint(&labeler_did)
+        .await
+        .context("Failed to resolve labeler service")?;
+
+    let mut client = LabelerClient::with_url(endpoint);
+    if let Some(token) = auth_token {
+        client = client.with_auth(token);
+    }
+
+    let labels = client
+        .query_all_labels(subjects, limit)
+        .await
+        .with_context(|| format!("Failed to query labels from {}", labeler))?
+        .labels;
+
+    output::print_labels(&subjects.join(" "), &labels, format)?;
+
+    Ok(!labels.is_empty())
+}
+
+fn labeler_client(auth_token: Option<String>) -> LabelerClient {
+    match auth_token {
+        Some(token) => LabelerClient::new_authenticated(token),
+        None => LabelerClient::new(),
+    }
+}
+
+/// Log an analysis's progress messages
+fn log_progress(event: AnalysisEvent) {
+    if let AnalysisEvent::Progress { message, percent } = event {
+        log::info!("[{:>3}%] {}", percent, message);
+    }
+}
+
+/// Resolve a handle, DID or link to the account's DID (records give their author)
+async fn resolve_account(input: &str) -> anyhow::Result<Did> {
+    let subject: Subject = input.parse()?;
+    let (_, did) = subject
+        .resolve()
+        .await
+        .with_context(|| format!("Failed to resolve {}", subject.actor))?;
+    if subject.actor != did.as_str() {
+        log::info!("Resolved {} to {}", subject.actor, did);
+    }
+
+    Ok(did)
+}
+
+/// Self-labels declared by the author on a profile (for DIDs) or record (for at-URIs)
+async fn fetch_self_labels(subject: &str, did: &Did) -> Vec<Label> {
+    let (collection, rkey) = match subject.strip_prefix("at://") {
+        Some(rest) => match rest.splitn(3, '/').collect::<Vec<_>>()[..] {
+            [_, collection, rkey] => (collection, rkey),
+            _ => return Vec::new(),
+        },
+        None => ("app.bsky.actor.profile", "self"),
+    };
+
+    match PostClient::new().get_record(did, collection, rkey).await {
+        Ok(record) => extract_self_labels(&record),
+        Err(e) => {
+            log::debug!("No self-labels for {}: {}", subject, e);
+            Vec::new()
+        }
+    }
+}
@@ -0,0 +1,404 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! `at-peek` command-line interface for querying ATproto moderation labels
+
+#![forbid(unsafe_code)]
+
+mod commands;
+mod output;
+
+use std::process::ExitCode;
+
+use atproto_client::{AnalysisScope, BulkAnalyzer, Connection, ScopeCollection};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Args, Parser, Subcommand};
+
+use crate::output::Format;
+
+/// Exit status when labels were found (or a non-label command succeeded)
+const EXIT_FOUND: u8 = 0;
+
+/// Exit status when the query succeeded but returned no labels
+const EXIT_NOT_FOUND: u8 = 1;
+
+/// Exit status for resolution, network, or authentication failures
+const EXIT_ERROR: u8 = 2;
+
+/// Inspect moderation labels on Bluesky accounts and posts
+#[derive(Parser)]
+#[command(
+    name = "at-peek",
+    version,
+    after_help = "Exit status: 0 if labels were found, 1 if none were found, 2 on error."
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(flatten)]
+    auth: AuthArgs,
+
+    /// Log progress to stderr (repeat for debug output)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show labels on a handle, DID, AT-URI or Bluesky link
+    Check {
+        /// Handle (alice.bsky.social), DID, at:// URI, or bsky.app / client URL
+        subject: String,
+    },
+
+    /// Check an account's recent posts (or other records) for labels
+    Bulk {
+        /// Handle, DID or profile link of the account
+        account: String,
+
+        /// Maximum number of records to check, most recent first
+        #[arg(long, default_value_t = atproto_client::analysis::DEFAULT_MAX_POSTS)]
+        limit: usize,
+
+        /// Check every record in scope, however many there are
+        #[arg(long, conflicts_with = "limit")]
+        all: bool,
+
+        /// Only records created on or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long, value_parser = parse_date)]
+        since: Option<DateTime<Utc>>,
+
+        /// Only records created before this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long, value_parser = parse_date)]
+        until: Option<DateTime<Utc>>,
+
+        /// Collections to check: posts, reposts, likes, lists, profile
+        #[arg(long = "collection", value_delimiter = ',', default_value = "posts")]
+        collections: Vec<ScopeCollection>,
+
+        /// Skip replies and check only top-level posts
+        #[arg(long)]
+        no_replies: bool,
+
+        /// Also count labels whose expiry has passed
+        #[arg(long)]
+        include_expired: bool,
+
+        /// Also fetch likers and reposters of labeled posts
+        #[arg(long)]
+        engagement: bool,
+
+        /// Also query account labels on every liker and reposter (implies --engagement)
+        #[arg(long)]
+        engagement_labels: bool,
+
+        /// Likers, and reposters, to list per post (each 100 cost one request per post)
+        #[arg(long, default_value_t = atproto_client::analysis::DEFAULT_ENGAGEMENT_ACCOUNTS)]
+        engagement_accounts: usize,
+
+        /// Maximum number of requests in flight at once
+        #[arg(long, default_value_t = atproto_client::analysis::DEFAULT_CONCURRENCY)]
+        concurrency: usize,
+    },
+
+    /// Find every label under an account, including labels on deleted records
+    Sweep {
+        /// Handle, DID or profile link of the account
+        account: String,
+
+        /// Maximum number of requests in flight at once
+        #[arg(long, default_value_t = atproto_client::analysis::DEFAULT_CONCURRENCY)]
+        concurrency: usize,
+    },
+
+    /// Check many handles, DIDs or links at once, read from a text or CSV file
+    Batch {
+        /// File with subjects separated by newlines or commas (`-` reads stdin)
+        file: String,
+
+        /// Also count labels whose expiry has passed
+        #[arg(long)]
+        include_expired: bool,
+
+        /// Maximum number of requests in flight at once
+        #[arg(long, default_value_t = atproto_client::analysis::DEFAULT_CONCURRENCY)]
+        concurrency: usize,
+    },
+
+    /// Find labeled accounts among an account's follows and followers
+    Network {
+        /// Handle, DID or profile link of the account
+        account: String,
+
+        /// Only scan the accounts it follows
+        #[arg(long, conflicts_with = "followers_only")]
+        follows_only: bool,
+
+        /// Only scan the accounts following it
+        #[arg(long)]
+        followers_only: bool,
+
+        /// Maximum number of follows, and of followers, to read
+        #[arg(long, default_value_t = atproto_client::analysis::DEFAULT_MAX_CONNECTIONS)]
+        limit: usize,
+
+        /// Also count labels whose expiry has passed
+        #[arg(long)]
+        include_expired: bool,
+
+        /// Maximum number of requests in flight at once
+        #[arg(long, default_value_t = atproto_client::analysis::DEFAULT_CONCURRENCY)]
+        concurrency: usize,
+    },
+
+    /// Check every post and author in a post's thread for labels
+    Thread {
+        /// AT-URI or Bluesky link of any post in the thread
+        post: String,
+
+        /// Also count labels whose expiry has passed
+        #[arg(long)]
+        include_expired: bool,
+
+        /// Maximum number of requests in flight at once
+        #[arg(long, default_value_t = atproto_client::analysis::DEFAULT_CONCURRENCY)]
+        concurrency: usize,
+    },
+
+    /// Check a moderation list, curation list or starter pack and every member for labels
+    List {
+        /// AT-URI or Bluesky link of the list or starter pack
+        list: String,
+
+        /// Maximum number of members to read
+        #[arg(long, default_value_t = atproto_client::analysis::DEFAULT_MAX_LIST_MEMBERS)]
+        limit: usize,
+
+        /// Also count labels whose expiry has passed
+        #[arg(long)]
+        include_expired: bool,
+
+        /// Maximum number of requests in flight at once
+        #[arg(long, default_value_t = atproto_client::analysis::DEFAULT_CONCURRENCY)]
+        concurrency: usize,
+    },
+
+    /// Resolve a handle to its DID, or a DID to its PDS
+    Resolve {
+        /// Handle or DID
+        subject: String,
+    },
+
+    /// List labels emitted by a labeler service
+    Labels {
+        /// DID of the labeler service
+        #[arg(long)]
+        labeler: String,
+
+        /// Subject patterns to query; a trailing `*` matches prefixes
+        #[arg(long = "subject", default_value = "*")]
+        subjects: Vec<String>,
+
+        /// Maximum number of labels to fetch
+        #[arg(long, default_value_t = 1000)]
+        limit: usize,
+    },
+}
+
+#[derive(Args)]
+struct OutputArgs {
+    /// Print JSON instead of text
+    #[arg(long, global = true, conflicts_with = "csv")]
+    json: bool,
+
+    /// Print one CSV row per label instead of text
+    #[arg(long, global = true)]
+    csv: bool,
+}
+
+#[derive(Args)]
+struct AuthArgs {
+    /// Handle to sign in with (enables restricted labels such as !takedown)
+    #[arg(long, global = true, env = "AT_PEEK_HANDLE", requires = "app_password")]
+    login: Option<String>,
+
+    /// App password for --login
+    #[arg(
+        long,
+        global = true,
+        env = "AT_PEEK_APP_PASSWORD",
+        hide_env_values = true,
+        requires = "login"
+    )]
+    app_password: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let level = match cli.verbose {
+        0 => log::LevelFilter::Warn,
+        1 => log::LevelFilter::Info,
+        _ => log::LevelFilter::Debug,
+    };
+    env_logger::Builder::new()
+        .filter_level(level)
+        .parse_default_env()
+        .init();
+
+    let format = if cli.output.json {
+        Format::Json
+    } else if cli.output.csv {
+        Format::Csv
+    } else {
+        Format::Text
+    };
+
+    match run(cli.command, &cli.auth, format).await {
+        Ok(true) => ExitCode::from(EXIT_FOUND),
+        Ok(false) => ExitCode::from(EXIT_NOT_FOUND),
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+/// Run a subcommand, returning whether any labels were found
+async fn run(command: Command, auth: &AuthArgs, format: Format) -> anyhow::Result<bool> {
+    let auth_token = match (&auth.login, &auth.app_password) {
+        (Some(login), Some(password)) => Some(commands::authenticate(login, password).await?),
+        _ => None,
+    };
+
+    match command {
+        Command::Check { subject } => commands::check(&subject, auth_token, format).await,
+        Command::Bulk {
+            account,
+            limit,
+            all,
+            since,
+            until,
+            collections,
+            no_replies,
+            include_expired,
+            engagement,
+            engagement_labels,
+            engagement_accounts,
+            concurrency,
+        } => {
+            let scope = AnalysisScope {
+                max_records: (!all).then_some(limit),
+                since,
+                until,
+                collections,
+                include_replies: !no_replies,
+                include_expired,
+            };
+            let analyzer = BulkAnalyzer::new(auth_token)
+                .with_scope(scope)
+                .with_engagement(engagement || engagement_labels)
+                .with_engagement_accounts(engagement_accounts)
+                .with_engagement_labels(engagement_labels)
+                .with_concurrency(concurrency);
+            commands::bulk(&account, analyzer, format).await
+        }
+        Command::Sweep {
+            account,
+            concurrency,
+        } => {
+            let analyzer = BulkAnalyzer::new(auth_token).with_concurrency(concurrency);
+            commands::sweep(&account, analyzer, format).await
+        }
+        Command::Batch {
+            file,
+            include_expired,
+            concurrency,
+        } => {
+            let scope = AnalysisScope {
+                include_expired,
+                ..AnalysisScope::default()
+            };
+            let analyzer = BulkAnalyzer::new(auth_token)
+                .with_scope(scope)
+                .with_concurrency(concurrency);
+            commands::batch(&file, analyzer, format).await
+        }
+        Command::Network {
+            account,
+            follows_only,
+            followers_only,
+            limit,
+            include_expired,
+            concurrency,
+        } => {
+            let connections = match (follows_only, followers_only) {
+                (true, _) => vec![Connection::Follows],
+                (_, true) => vec![Connection::Followers],
+                _ => vec![Connection::Follows, Connection::Followers],
+            };
+            let scope = AnalysisScope {
+                include_expired,
+                ..AnalysisScope::default()
+            };
+            let analyzer = BulkAnalyzer::new(auth_token)
+                .with_scope(scope)
+                .with_concurrency(concurrency);
+            commands::network(&account, analyzer, &connections, limit, format).await
+        }
+        Command::Thread {
+            post,
+            include_expired,
+            concurrency,
+        } => {
+            let scope = AnalysisScope {
+                include_expired,
+                ..AnalysisScope::default()
+            };
+            let analyzer = BulkAnalyzer::new(auth_token)
+                .with_scope(scope)
+                .with_concurrency(concurrency);
+            commands::thread(&post, analyzer, format).await
+        }
+        Command::List {
+            list,
+            limit,
+            include_expired,
+            concurrency,
+        } => {
+            let scope = AnalysisScope {
+                include_expired,
+                ..AnalysisScope::default()
+            };
+            let analyzer = BulkAnalyzer::new(auth_token)
+                .with_scope(scope)
+                .with_concurrency(concurrency);
+            commands::list(&list, analyzer, limit, format).await
+        }
+        Command::Resolve { subject } => commands::resolve(&subject, format).await.map(|()| true),
+        Command::Labels {
+            labeler,
+            subjects,
+            limit,
+        } => commands::labels(&labeler, &subjects, limit, auth_token, format).await,
+    }
+}
+
+/// Parse a `--since`/`--until` value; bare dates mean midnight UTC
+fn parse_date(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(Default::default()).and_utc());
+    }
+
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| {
+            format!(
+                "expected YYYY-MM-DD or an RFC 3339 timestamp, got '{}'",
+                value
+            )
+        })
+}
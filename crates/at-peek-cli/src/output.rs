@@ -0,0 +1,456 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Text, JSON and CSV rendering of command results
+
+use atproto_client::{
+    export, AccountSweep, BulkAnalysis, ConnectedAccount, Did, Label, LabelCategory,
+    ListInspection, NetworkScan, PostThread, RecordStatus, SubjectCheck,
+};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Serialize)]
+pub struct Resolution {
+    pub did: String,
+    pub handle: Option<String>,
+    pub pds: String,
+}
+
+pub fn print_labels(subject: &str, labels: &[Label], format: Format) -> anyhow::Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(labels)?),
+        Format::Csv => print!("{}", export::labels_to_csv(labels)),
+        Format::Text => {
+            if labels.is_empty() {
+                println!("No labels on {}", subject);
+            } else {
+                println!("{} label(s) on {}", labels.len(), subject);
+                for label in labels {
+                    print_text_label(label, true);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn print_bulk(did: &Did, analysis: &BulkAnalysis, format: Format) -> anyhow::Result<()> {
+    let stats = &analysis.stats;
+
+    match format {
+        Format::Json => {
+            #[derive(Serialize)]
+            struct BulkReport<'a> {
+                did: &'a str,
+                #[serde(flatten)]
+                analysis: &'a BulkAnalysis,
+            }
+
+            let report = BulkReport {
+                did: did.as_str(),
+                analysis,
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        Format::Csv => print!("{}", export::to_csv(did.as_str(), analysis)),
+        Format::Text => {
+            println!("Account {}", did);
+            if stats.account_labels.is_empty() {
+                println!("  No account labels");
+            }
+            for label in &stats.account_labels {
+                print_text_label(label, false);
+            }
+
+            println!();
+            println!(
+                "{} of {} posts labeled by labelers, {} self-labeled",
+                stats.posts_with_labels, stats.total_posts, stats.self_labeled_posts
+            );
+            if stats.expired_labels > 0 {
+                println!(
+                    "  {} expired label(s) not counted (--include-expired to count them)",
+                    stats.expired_labels
+                );
+            }
+            for (value, count) in &stats.top_label_values {
+                println!("  {:>5}  {}", count, value);
+            }
+
+            for post in &analysis.labeled_posts {
+                println!();
+                let via = match post.collection.as_str() {
+                    "app.bsky.feed.like" => " [liked]",
+                    "app.bsky.feed.repost" => " [reposted]",
+                    _ => "",
+                };
+                println!("{}{} ({})", post.uri, via, post.created_at);
+                if !post.text.is_empty() {
+                    println!("  \"{}\"", post.text.replace('\n', " "));
+                }
+                if post.like_count > 0 || post.repost_count > 0 {
+                    println!(
+                        "  {} like(s), {} repost(s)",
+                        post.like_count, post.repost_count
+                    );
+                }
+                if let Some(error) = &post.engagement_error {
+                    println!("  engagement incomplete: {}", error);
+                }
+                if post.engagement_labels.is_some() {
+                    let engagers = post.labeled_engagers();
+                    let spam = engagers
+                        .iter()
+                        .filter(|(_, labels)| {
+                            labels
+                                .iter()
+                                .any(|label| label.category() == LabelCategory::Spam)
+                        })
+                        .count();
+                    println!(
+                        "  {} labeled liker/reposter account(s), {} spam-labeled",
+                        engagers.len(),
+                        spam
+                    );
+                    for (user, labels) in engagers {
+                        let values: Vec<&str> =
+                            labels.iter().map(|label| label.val.as_str()).collect();
+                        println!("      @{}: {}", user.handle, values.join(", "));
+                    }
+                }
+                for label in &post.labels {
+                    print_text_label(label, false);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn print_sweep(sweep: &AccountSweep, format: Format) -> anyhow::Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(sweep)?),
+        Format::Csv => {
+            println!("{},record_status", export::CSV_HEADER);
+            for swept in &sweep.labels {
+                let status = swept.record_status.name().to_lowercase();
+                println!("{},{}", export::label_csv_row(&swept.label), status);
+            }
+        }
+        Format::Text => {
+            let deleted = sweep.on_deleted_records().count();
+            println!(
+                "{} label(s) under {}, {} on deleted records",
+                sweep.labels.len(),
+                sweep.did,
+                deleted
+            );
+            for swept in &sweep.labels {
+                print_text_label(&swept.label, true);
+                match swept.record_status {
+                    RecordStatus::Deleted => println!("      record deleted"),
+                    RecordStatus::Unknown => println!("      record status unknown"),
+                    RecordStatus::Account | RecordStatus::Present => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn print_checks(checks: &[SubjectCheck], format: Format) -> anyhow::Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(checks)?),
+        Format::Csv => print!("{}", export::checks_to_csv(checks)),
+        Format::Text => {
+            for check in checks {
+                match &check.subject {
+                    Some(subject) if *subject != check.input => {
+                        println!("{} ({}): {}", check.input, subject, check.status().name())
+                    }
+                    _ => println!("{}: {}", check.input, check.status().name()),
+                }
+                for label in &check.labels {
+                    print_text_label(label, false);
+                }
+                if check.expired_labels > 0 {
+                    println!("  {} expired label(s) not counted", check.expired_labels);
+                }
+                if let Some(error) = &check.error {
+                    println!("  error: {}", error);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn print_network(scan: &NetworkScan, format: Format) -> anyhow::Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(scan)?),
+        Format::Csv => {
+            println!("{},handle,connection", export::CSV_HEADER);
+            for account in scan.labeled() {
+                for label in &account.labels {
+                    println!(
+                        "{},{},{}",
+                        export::label_csv_row(label),
+                        export::csv_field(account.handle.as_deref().unwrap_or_default()),
+                        connection(account)
+                    );
+                }
+            }
+        }
+        Format::Text => {
+            let labeled = scan.labeled().count();
+            println!(
+                "{} follows and {} followers of {}: {} labeled account(s)",
+                scan.follows, scan.followers, scan.did, labeled
+            );
+            for (val, count) in scan.label_counts() {
+                println!("  {:>5}  {}", count, val);
+            }
+            for account in scan.labeled() {
+                println!(
+                    "{} ({}) [{}]",
+                    account.handle.as_deref().unwrap_or("no handle"),
+                    account.did,
+                    connection(account)
+                );
+                for label in &account.labels {
+                    print_text_label(label, false);
+                }
+            }
+            if scan.expired_labels > 0 {
+                println!("{} expired label(s) not counted", scan.expired_labels);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn print_thread(thread: &PostThread, format: Format) -> anyhow::Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(thread)?),
+        Format::Csv => {
+            println!("{},handle", export::CSV_HEADER);
+            for post in &thread.posts {
+                for label in &post.labels {
+                    println!(
+                        "{},{}",
+                        export::label_csv_row(label),
+                        export::csv_field(post.author_name())
+                    );
+                }
+            }
+            for did in thread.authors() {
+                let handle = thread
+                    .posts
+                    .iter()
+                    .find(|post| post.did == did)
+                    .map_or(did.as_str(), |post| post.author_name());
+                for label in thread.author_labels.get(&did).into_iter().flatten() {
+                    println!(
+                        "{},{}",
+                        export::label_csv_row(label),
+                        export::csv_field(handle)
+                    );
+                }
+            }
+        }
+        Format::Text => {
+            let (replies, from_labeled) = thread.labeled_replies();
+            println!(
+                "{} posts in the thread of {}: {} labeled or by labeled accounts",
+                thread.posts.len(),
+                thread.uri,
+                thread.labeled_posts().count()
+            );
+            println!(
+                "{} of {} replies are from labeled accounts",
+                from_labeled, replies
+            );
+            for (val, count) in thread.label_counts() {
+                println!("  {:>5}  {}", count, val);
+            }
+            for post in &thread.posts {
+                let indent = "  ".repeat(post.depth);
+                let marker = if post.uri == thread.uri { "▶ " } else { "" };
+                match &post.unavailable {
+                    Some(reason) => println!("{}{}[{}] {}", indent, marker, reason, post.uri),
+                    None => println!(
+                        "{}{}@{}: {}",
+                        indent,
+                        marker,
+                        post.author_name(),
+                        snippet(&post.text)
+                    ),
+                }
+                for label in &post.labels {
+                    println!(
+                        "{}    {} {} (post)",
+                        indent,
+                        label.category().icon(),
+                        label.val
+                    );
+                }
+                for label in thread.labels_on_author(post) {
+                    println!(
+                        "{}    {} {} (account)",
+                        indent,
+                        label.category().icon(),
+                        label.val
+                    );
+                }
+            }
+            if thread.expired_labels > 0 {
+                println!("{} expired label(s) not counted", thread.expired_labels);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn print_list(inspection: &ListInspection, format: Format) -> anyhow::Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(inspection)?),
+        Format::Csv => {
+            println!("{},handle", export::CSV_HEADER);
+            for label in &inspection.labels {
+                println!("{},", export::label_csv_row(label));
+            }
+            for member in inspection.labeled_members() {
+                for label in &member.labels {
+                    println!(
+                        "{},{}",
+                        export::label_csv_row(label),
+                        export::csv_field(member.handle.as_deref().unwrap_or_default())
+                    );
+                }
+            }
+        }
+        Format::Text => {
+            println!(
+                "{} \"{}\" ({}): {} members, {} labeled",
+                inspection.kind.name(),
+                inspection.name,
+                inspection.uri,
+                inspection.members.len(),
+                inspection.labeled_members().count()
+            );
+            if inspection.labels.is_empty() {
+                println!(
+                    "No labels on the {} itself",
+                    inspection.kind.name().to_lowercase()
+                );
+            } else {
+                println!(
+                    "Labels on the {} itself:",
+                    inspection.kind.name().to_lowercase()
+                );
+                for label in &inspection.labels {
+                    print_text_label(label, inspection.list_uri != inspection.uri);
+                }
+            }
+            for (val, count) in inspection.label_counts() {
+                println!("  {:>5}  {}", count, val);
+            }
+            for member in inspection.labeled_members() {
+                println!(
+                    "{} ({})",
+                    member.handle.as_deref().unwrap_or("no handle"),
+                    member.did
+                );
+                for label in &member.labels {
+                    print_text_label(label, false);
+                }
+            }
+            if inspection.expired_labels > 0 {
+                println!("{} expired label(s) not counted", inspection.expired_labels);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn print_resolution(resolution: &Resolution, format: Format) -> anyhow::Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(resolution)?),
+        Format::Csv => {
+            println!("did,handle,pds");
+            println!(
+                "{},{},{}",
+                export::csv_field(&resolution.did),
+                export::csv_field(resolution.handle.as_deref().unwrap_or_default()),
+                export::csv_field(&resolution.pds)
+            );
+        }
+        Format::Text => {
+            println!("DID:    {}", resolution.did);
+            if let Some(handle) = &resolution.handle {
+                println!("Handle: {}", handle);
+            }
+            println!("PDS:    {}", resolution.pds);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_text_label(label: &Label, show_subject: bool) {
+    let category = label.category();
+    let source = if label.self_applied {
+        "self-label by author".to_string()
+    } else {
+        format!("by {}", label.src)
+    };
+
+    println!(
+        "  {} {} [{}] {}, {}",
+        category.icon(),
+        label.val,
+        category.name(),
+        source,
+        label.cts
+    );
+    if show_subject {
+        println!("      on {}", label.uri);
+    }
+    if let Some(exp) = &label.exp {
+        if label.is_expired() {
+            println!("      expired {}", exp);
+        } else {
+            println!("      expires {}", exp);
+        }
+    }
+}
+
+fn connection(account: &ConnectedAccount) -> &'static str {
+    match (account.followed, account.follower) {
+        (true, true) => "mutual",
+        (true, false) => "follows",
+        _ => "follower",
+    }
+}
+
+/// First line of a post's text, shortened to fit a terminal line
+fn snippet(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > 80 {
+        format!("{}…", line.chars().take(79).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
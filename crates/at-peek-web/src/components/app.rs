@@ -4,8 +4,12 @@ use leptos::*;
 
 #[allow(unused_imports)]
 use leptos_meta::*;
+use leptos_router::{use_location, Redirect, Route, Router, Routes, A};
 
-use super::{BulkAnalysis, Header, InputPanel, LabelViewer};
+use super::{
+    BatchCheck, BulkAnalysis, Header, ListInspectionView, NetworkScanView, SingleCheck, ThreadView,
+    WatchAlerts, WatchlistView,
+};
 use crate::state::AppState;
 
 #[component]
@@ -13,10 +17,12 @@ pub fn App() -> impl IntoView {
     provide_meta_context();
 
     let state = AppState::new();
-    let mode = create_rw_signal("single"); // "single" or "bulk"
 
     provide_context(state);
 
+    // Watched subjects are re-checked whichever page is open
+    crate::watchlist::start_background_checks(state);
+
     view! {
         <Stylesheet id="leptos" href="/pkg/at-peek-web.css"/>
         <Link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/tailwindcss@2.2.19/dist/tailwind.min.css"/>
@@ -24,64 +30,106 @@ pub fn App() -> impl IntoView {
         <Title text="at-peek - ATproto Label Inspector"/>
         <Meta name="description" content="Inspect content moderation labels on ATproto users and posts"/>
 
-        <div class="min-h-screen bg-gray-50 dark:bg-gray-900 text-gray-900 dark:text-gray-100">
-            <Header />
-
-            <main class="container mx-auto px-4 py-8 max-w-4xl">
-                <div class="mb-6 flex gap-2">
-                    <button
-                        class=move || format!(
-                            "px-4 py-2 rounded-lg font-semibold transition-colors {}",
-                            if mode.get() == "single" {
-                                "bg-blue-600 text-white"
-                            } else {
-                                "bg-gray-200 dark:bg-gray-700 text-gray-700 dark:text-gray-300 hover:bg-gray-300 dark:hover:bg-gray-600"
-                            }
-                        )
-                        on:click=move |_| mode.set("single")
-                    >
-                        "🔍 Single Check"
-                    </button>
-                    <button
-                        class=move || format!(
-                            "px-4 py-2 rounded-lg font-semibold transition-colors {}",
-                            if mode.get() == "bulk" {
-                                "bg-blue-600 text-white"
-                            } else {
-                                "bg-gray-200 dark:bg-gray-700 text-gray-700 dark:text-gray-300 hover:bg-gray-300 dark:hover:bg-gray-600"
-                            }
-                        )
-                        on:click=move |_| mode.set("bulk")
-                    >
-                        "📊 Bulk Analysis"
-                    </button>
-                </div>
-
-                <Show
-                    when=move || mode.get() == "single"
-                    fallback=|| view! { <BulkAnalysis /> }
-                >
-                    <InputPanel />
-                    <LabelViewer />
-                </Show>
-            </main>
-
-            <footer class="mt-16 py-8 border-t border-gray-200 dark:border-gray-700">
-                <div class="container mx-auto px-4 text-center text-sm text-gray-600 dark:text-gray-400">
-                    <p>
-                        "at-peek v0.1.0 is open source • "
-                        <a href="https://github.com/bskynz/at-peek" target="_blank" class="text-blue-600 dark:text-blue-400 hover:underline">
-                            "GitHub"
-                        </a>
-                    </p>
-                    <p class="mt-2 text-xs">
-                        "🔒 All processing happens locally in your browser. No data leaves your device."
-                    </p>
-                    <p class="mt-1 text-xs text-gray-500 dark:text-gray-500">
-                        "Note: This site is hosted on Cloudflare Pages. Cloudflare terminates TLS and can technically access data in transit, including authentication credentials. Use app passwords, not your main account password."
-                    </p>
-                </div>
-            </footer>
-        </div>
+        <Router>
+            <div class="min-h-screen bg-gray-50 dark:bg-gray-900 text-gray-900 dark:text-gray-100">
+                <Header />
+
+                <main class="container mx-auto px-4 py-8 max-w-4xl">
+                    <div class="mb-6 flex flex-wrap gap-2">
+                        <ModeLink href="/check" active_prefixes=&["/check", "/post"]>
+                            "🔍 Single Check"
+                        </ModeLink>
+                        <ModeLink href="/batch" active_prefixes=&["/batch"]>
+                            "📋 Batch Check"
+                        </ModeLink>
+                        <ModeLink href="/bulk" active_prefixes=&["/bulk"]>
+                            "📊 Bulk Analysis"
+                        </ModeLink>
+                        <ModeLink href="/network" active_prefixes=&["/network"]>
+                            "🕸 Network"
+                        </ModeLink>
+                        <ModeLink href="/thread" active_prefixes=&["/thread"]>
+                            "🧵 Thread"
+                        </ModeLink>
+                        <ModeLink href="/list" active_prefixes=&["/list", "/starter-pack"]>
+                            "📃 Lists"
+                        </ModeLink>
+                        <ModeLink href="/watchlist" active_prefixes=&["/watchlist"]>
+                            "👁 Watchlist"
+                        </ModeLink>
+                    </div>
+
+                    <WatchAlerts />
+
+                    <Routes>
+                        <Route path="/check" view=SingleCheck />
+                        <Route path="/check/:subject" view=SingleCheck />
+                        <Route path="/post/:did/:rkey" view=SingleCheck />
+                        <Route path="/batch" view=BatchCheck />
+                        <Route path="/bulk" view=BulkAnalysis />
+                        <Route path="/bulk/:handle" view=BulkAnalysis />
+                        <Route path="/network" view=NetworkScanView />
+                        <Route path="/network/:handle" view=NetworkScanView />
+                        <Route path="/thread" view=ThreadView />
+                        <Route path="/thread/:did/:rkey" view=ThreadView />
+                        <Route path="/list" view=ListInspectionView />
+                        <Route path="/list/:did/:rkey" view=ListInspectionView />
+                        <Route path="/starter-pack/:did/:rkey" view=ListInspectionView />
+                        <Route path="/watchlist" view=WatchlistView />
+                        <Route path="/*any" view=|| view! { <Redirect path="/check" /> } />
+                    </Routes>
+                </main>
+
+                <footer class="mt-16 py-8 border-t border-gray-200 dark:border-gray-700">
+                    <div class="container mx-auto px-4 text-center text-sm text-gray-600 dark:text-gray-400">
+                        <p>
+                            "at-peek v0.1.0 is open source • "
+                            <a href="https://github.com/bskynz/at-peek" target="_blank" class="text-blue-600 dark:text-blue-400 hover:underline">
+                                "GitHub"
+                            </a>
+                        </p>
+                        <p class="mt-2 text-xs">
+                            "🔒 All processing happens locally in your browser. No data leaves your device."
+                        </p>
+                        <p class="mt-1 text-xs text-gray-500 dark:text-gray-500">
+                            "Note: This site is hosted on Cloudflare Pages. Cloudflare terminates TLS and can technically access data in transit, including authentication credentials. Use app passwords, not your main account password."
+                        </p>
+                    </div>
+                </footer>
+            </div>
+        </Router>
+    }
+}
+
+/// Mode toggle, highlighted while the current route starts with one of `active_prefixes`
+#[component]
+fn ModeLink(
+    href: &'static str,
+    active_prefixes: &'static [&'static str],
+    children: Children,
+) -> impl IntoView {
+    let location = use_location();
+    let is_active = move || {
+        location.pathname.with(|path| {
+            active_prefixes
+                .iter()
+                .any(|prefix| path.starts_with(prefix))
+        })
+    };
+
+    view! {
+        <A
+            href=href
+            class=move || format!(
+                "px-4 py-2 rounded-lg font-semibold transition-colors {}",
+                if is_active() {
+                    "bg-blue-600 text-white"
+                } else {
+                    "bg-gray-200 dark:bg-gray-700 text-gray-700 dark:text-gray-300 hover:bg-gray-300 dark:hover:bg-gray-600"
+                }
+            )
+        >
+            {children()}
+        </A>
     }
 }
@@ -0,0 +1,358 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use leptos::*;
+use leptos_router::A;
+use wasm_bindgen_futures::spawn_local;
+
+use crate::state::AppState;
+use crate::utils;
+use atproto_client::{AbortHandle, AnalysisEvent, CheckStatus, Subject, SubjectCheck};
+
+/// Column the results table is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    /// Input order
+    Input,
+    Status,
+    Labels,
+}
+
+/// Batch mode for `/batch`: check a pasted list or text/CSV file of subjects at once
+#[component]
+pub fn BatchCheck() -> impl IntoView {
+    let state = expect_context::<AppState>();
+    let text = create_rw_signal(String::new());
+    let include_expired = create_rw_signal(false);
+    let checks = create_rw_signal::<Vec<SubjectCheck>>(Vec::new());
+    let is_checking = create_rw_signal(false);
+    let progress = create_rw_signal::<Option<String>>(None);
+    let progress_percent = create_rw_signal(0);
+    let abort = store_value(AbortHandle::new());
+    let sort = create_rw_signal((SortColumn::Input, true));
+    state.error.set(None);
+
+    let subjects = create_memo(move |_| text.with(|text| Subject::split_list(text)));
+
+    let run_check = move || {
+        let inputs = subjects.get_untracked();
+        if inputs.is_empty() {
+            state.error.set(Some(
+                "Paste handles, DIDs or AT-URIs, one per line or separated by commas".to_string(),
+            ));
+            return;
+        }
+
+        state.error.set(None);
+        checks.set(Vec::new());
+        is_checking.set(true);
+        progress.set(Some(format!("Checking {} subjects...", inputs.len())));
+        progress_percent.set(0);
+
+        abort.with_value(AbortHandle::abort);
+        let handle = AbortHandle::new();
+        abort.set_value(handle.clone());
+
+        spawn_local(async move {
+            let on_event = |event: AnalysisEvent| {
+                if let AnalysisEvent::Progress { message, percent } = event {
+                    progress.set(Some(message));
+                    progress_percent.set(percent);
+                }
+            };
+            let results = utils::check_subjects(
+                &inputs,
+                state.auth_token.get_untracked(),
+                include_expired.get_untracked(),
+                handle,
+                on_event,
+            )
+            .await;
+            // The view may have been left while the check ran
+            if checks.try_set(results).is_none() {
+                progress.set(None);
+                is_checking.set(false);
+            }
+        });
+    };
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        run_check();
+    };
+
+    let on_file_selected = move |file: Option<web_sys::File>| {
+        let Some(file) = file else {
+            return;
+        };
+        spawn_local(async move {
+            match utils::read_file_text(file).await {
+                Ok(contents) => text.set(contents),
+                Err(e) => state.error.set(Some(e)),
+            }
+        });
+    };
+
+    on_cleanup(move || {
+        abort.try_with_value(AbortHandle::abort);
+    });
+
+    let on_export = move |_| {
+        checks.with_untracked(|checks| {
+            if let Err(e) = utils::export_checks(checks) {
+                state.error.set(Some(e));
+            }
+        });
+    };
+
+    let sorted = move || {
+        let (column, ascending) = sort.get();
+        let mut rows: Vec<(usize, SubjectCheck)> = checks.get().into_iter().enumerate().collect();
+        // Stable, so ties stay in input order
+        rows.sort_by(|(a_index, a), (b_index, b)| {
+            let order = match column {
+                SortColumn::Input => a_index.cmp(b_index),
+                SortColumn::Status => a.status().cmp(&b.status()),
+                SortColumn::Labels => b.labels.len().cmp(&a.labels.len()),
+            };
+            if ascending {
+                order
+            } else {
+                order.reverse()
+            }
+        });
+        rows
+    };
+
+    let counts = move || {
+        checks.with(|checks| {
+            [
+                CheckStatus::Labeled,
+                CheckStatus::Clean,
+                CheckStatus::Failed,
+            ]
+            .map(|status| {
+                (
+                    status,
+                    checks
+                        .iter()
+                        .filter(|check| check.status() == status)
+                        .count(),
+                )
+            })
+        })
+    };
+
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6 mb-6">
+            <h2 class="text-xl font-bold mb-1">"📋 Batch Check"</h2>
+            <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                "Check many accounts or records at once. Paste handles, DIDs, AT-URIs or links separated by newlines or commas, or load a text/CSV file; extra CSV columns are ignored."
+            </p>
+
+            <form on:submit=on_submit>
+                <textarea
+                    rows="8"
+                    placeholder="alice.bsky.social\ndid:plc:...\nhttps://bsky.app/profile/bob.bsky.social/post/..."
+                    class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent bg-white dark:bg-gray-700 font-mono text-sm"
+                    prop:value=move || text.get()
+                    on:input=move |ev| text.set(event_target_value(&ev))
+                />
+
+                <div class="mt-2 flex flex-wrap items-center gap-4 text-sm">
+                    <label class="px-3 py-1 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-lg cursor-pointer transition-colors">
+                        "📄 Load .txt / .csv"
+                        <input
+                            type="file"
+                            accept=".txt,.csv,text/plain,text/csv"
+                            class="hidden"
+                            on:change=move |ev| {
+                                let input = event_target::<web_sys::HtmlInputElement>(&ev);
+                                on_file_selected(input.files().and_then(|files| files.get(0)));
+                                // Allow re-selecting the same file
+                                input.set_value("");
+                            }
+                        />
+                    </label>
+                    <label class="flex items-center gap-2 cursor-pointer">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || include_expired.get()
+                            on:change=move |ev| include_expired.set(event_target_checked(&ev))
+                        />
+                        "Count expired labels"
+                    </label>
+                    <span class="ml-auto text-gray-500 dark:text-gray-400">
+                        {move || match subjects.with(Vec::len) {
+                            1 => "1 subject".to_string(),
+                            n => format!("{} subjects", n),
+                        }}
+                    </span>
+                </div>
+
+                <button
+                    type="submit"
+                    disabled=move || is_checking.get()
+                    class="mt-4 w-full bg-blue-600 hover:bg-blue-700 disabled:bg-gray-400 text-white font-semibold py-2 px-4 rounded-lg transition-colors"
+                >
+                    {move || if is_checking.get() { "🔄 Checking..." } else { "📋 Check All" }}
+                </button>
+            </form>
+
+            {move || state.error.get().map(|err| view! {
+                <div class="mt-4 p-4 bg-red-100 dark:bg-red-900 border border-red-300 dark:border-red-700 rounded-lg">
+                    <p class="text-red-800 dark:text-red-200 text-sm">{err}</p>
+                </div>
+            })}
+
+            {move || progress.get().map(|msg| view! {
+                <div class="mt-4 p-4 bg-blue-50 dark:bg-blue-900 rounded-lg border border-blue-200 dark:border-blue-700">
+                    <div class="flex items-center justify-between mb-2">
+                        <p class="text-sm font-semibold text-blue-800 dark:text-blue-200">{msg}</p>
+                        <span class="text-sm font-bold text-blue-600 dark:text-blue-400">
+                            {move || progress_percent.get()}"%"
+                        </span>
+                    </div>
+                    <div class="w-full bg-blue-200 dark:bg-blue-800 rounded-full h-2.5 overflow-hidden">
+                        <div
+                            class="bg-blue-600 dark:bg-blue-400 h-2.5 rounded-full transition-all duration-300 ease-out"
+                            style=move || format!("width: {}%", progress_percent.get())
+                        />
+                    </div>
+                    <button
+                        type="button"
+                        on:click=move |_| abort.with_value(AbortHandle::abort)
+                        class="mt-3 px-3 py-1 text-sm font-semibold bg-white dark:bg-gray-700 border border-blue-300 dark:border-blue-600 text-blue-800 dark:text-blue-200 rounded-lg hover:bg-blue-100 dark:hover:bg-blue-800 transition-colors"
+                    >
+                        "⏹ Cancel"
+                    </button>
+                </div>
+            })}
+        </div>
+
+        <Show when=move || checks.with(|checks| !checks.is_empty())>
+            <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6">
+                <div class="flex flex-wrap items-center gap-4 mb-4 text-sm">
+                    {move || counts().into_iter().map(|(status, count)| view! {
+                        <span class=format!("px-2 py-1 rounded font-semibold {}", status_color(status))>
+                            {format!("{} {}", count, status.name())}
+                        </span>
+                    }).collect_view()}
+                    <button
+                        type="button"
+                        on:click=on_export
+                        class="ml-auto px-3 py-1 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-lg transition-colors"
+                    >
+                        "⬇ CSV"
+                    </button>
+                </div>
+
+                <div class="overflow-x-auto">
+                    <table class="w-full text-sm">
+                        <thead>
+                            <tr class="text-left border-b border-gray-200 dark:border-gray-700">
+                                <SortHeader sort=sort column=SortColumn::Input title="Subject" />
+                                <SortHeader sort=sort column=SortColumn::Status title="Status" />
+                                <SortHeader sort=sort column=SortColumn::Labels title="Labels" />
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {move || sorted().into_iter().map(|(_, check)| view! {
+                                <CheckRow check=check />
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+/// Column header that sorts by its column, toggling the direction on a second click
+#[component]
+fn SortHeader(
+    sort: RwSignal<(SortColumn, bool)>,
+    column: SortColumn,
+    title: &'static str,
+) -> impl IntoView {
+    let on_click = move |_| {
+        sort.update(|(current, ascending)| {
+            if *current == column {
+                *ascending = !*ascending;
+            } else {
+                *current = column;
+                *ascending = true;
+            }
+        })
+    };
+
+    view! {
+        <th class="py-2 pr-4">
+            <button type="button" class="font-semibold hover:underline" on:click=on_click>
+                {title}
+                {move || match sort.get() {
+                    (current, true) if current == column => " ▲",
+                    (current, false) if current == column => " ▼",
+                    _ => "",
+                }}
+            </button>
+        </th>
+    }
+}
+
+#[component]
+fn CheckRow(check: SubjectCheck) -> impl IntoView {
+    let status = check.status();
+    let link = utils::check_path(check.subject.as_deref().unwrap_or(&check.input));
+    let resolved = check
+        .subject
+        .clone()
+        .filter(|subject| *subject != check.input);
+
+    view! {
+        <tr class="border-b border-gray-100 dark:border-gray-700 align-top">
+            <td class="py-2 pr-4 break-all">
+                <A href=link class="text-blue-600 dark:text-blue-400 hover:underline">
+                    {check.input.clone()}
+                </A>
+                {resolved.map(|subject| view! {
+                    <div class="text-xs text-gray-500 dark:text-gray-400 font-mono">{subject}</div>
+                })}
+            </td>
+            <td class="py-2 pr-4">
+                <span class=format!("px-2 py-1 rounded text-xs font-semibold {}", status_color(status))>
+                    {status.name()}
+                </span>
+            </td>
+            <td class="py-2">
+                <div class="flex flex-wrap gap-1">
+                    {check.labels.iter().map(|label| view! {
+                        <span
+                            class="px-2 py-1 rounded bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200 text-xs font-mono"
+                            title=format!("by {}", label.src)
+                        >
+                            {label.category().icon()} " " {label.val.clone()}
+                        </span>
+                    }).collect_view()}
+                    {(check.expired_labels > 0).then(|| view! {
+                        <span class="text-xs text-gray-500 dark:text-gray-400">
+                            {format!("+{} expired", check.expired_labels)}
+                        </span>
+                    })}
+                </div>
+                {check.error.map(|error| view! {
+                    <p class="text-xs text-red-700 dark:text-red-300">{error}</p>
+                })}
+            </td>
+        </tr>
+    }
+}
+
+fn status_color(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Labeled => "bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200",
+        CheckStatus::Clean => "bg-green-100 dark:bg-green-900 text-green-800 dark:text-green-200",
+        CheckStatus::Failed => {
+            "bg-yellow-100 dark:bg-yellow-900 text-yellow-800 dark:text-yellow-200"
+        }
+    }
+}
@@ -1,42 +1,33 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use leptos::*;
-use std::collections::HashMap;
+use leptos_router::{use_navigate, use_params_map};
 use wasm_bindgen_futures::spawn_local;
 
+use super::SnapshotHistory;
+use crate::snapshots;
 use crate::state::AppState;
-use atproto_client::LabelCategory;
-
-#[derive(Clone, Debug)]
-pub struct BulkAnalysisStats {
-    pub total_posts: usize,
-    pub posts_with_labels: usize,
-    pub labels_by_category: HashMap<LabelCategory, usize>,
-    pub top_label_values: Vec<(String, usize)>,
-    pub account_labels: Vec<atproto_client::Label>,
-}
+use crate::utils::ExportFormat;
+use atproto_client::analysis::sort_labeled_posts;
+use atproto_client::{
+    AbortHandle, AccountSweep, AnalysisEvent, AnalysisScope, BulkAnalysis as BulkAnalysisReport,
+    BulkAnalysisStats, LabelCategory, PostWithLabels, RecordStatus, ScopeCollection, Snapshot,
+    SnapshotKind,
+};
 
-#[derive(Clone, Debug)]
-pub struct PostWithLabels {
-    pub uri: String,
-    pub text: String,
-    pub labels: Vec<atproto_client::Label>,
-    pub created_at: String,
-    pub has_media: bool,
-    pub image_urls: Vec<String>,
-    pub video_url: Option<String>,
-    pub like_count: usize,
-    pub repost_count: usize,
-    pub likers: Vec<UserInfo>,
-    pub reposters: Vec<UserInfo>,
-}
+/// Post limits offered in the scope options; `None` is "all"
+const LIMIT_CHOICES: [Option<usize>; 5] = [Some(100), Some(500), Some(1000), Some(5000), None];
 
-#[derive(Clone, Debug)]
-pub struct UserInfo {
-    #[allow(dead_code)]
-    pub did: String,
-    pub handle: String,
-    pub display_name: Option<String>,
+/// Why a run stopped early: (reason, posts not checked yet, account DID)
+type Interruption = (String, usize, String);
+
+/// Where the posts for an analysis run come from
+#[derive(Clone)]
+enum AnalysisSource {
+    /// Handle or DID, fetched from the account's PDS
+    Subject(String),
+    /// Repository export (`.car`) supplied by the user
+    CarFile(web_sys::File),
 }
 
 #[component]
@@ -48,37 +39,108 @@ pub fn BulkAnalysis() -> impl IntoView {
     let is_analyzing = create_rw_signal(false);
     let progress = create_rw_signal::<Option<String>>(None);
     let progress_percent = create_rw_signal(0);
+    let interruption = create_rw_signal::<Option<Interruption>>(None);
+    let abort = store_value(AbortHandle::new());
+    let last_source = store_value::<Option<AnalysisSource>>(None);
+    let scope = create_rw_signal(AnalysisScope::default());
+    let report = create_rw_signal::<Option<BulkAnalysisReport>>(None);
+    let sweep = create_rw_signal::<Option<AccountSweep>>(None);
+    // DID of the last analyzed account, whose saved snapshots are listed
+    let snapshot_did = create_rw_signal::<Option<String>>(None);
+    // Bumped per run so a run superseded by navigation stops updating the view
+    let run_id = store_value(0u64);
+    let navigate = use_navigate();
+    let params = use_params_map();
+    let routed_handle = create_memo(move |_| {
+        params.with(|p| {
+            p.get("handle")
+                .map(|handle| handle.trim().to_string())
+                .filter(|handle| !handle.is_empty())
+        })
+    });
 
-    let on_analyze = move |ev: leptos::ev::SubmitEvent| {
-        ev.prevent_default();
-
-        let input = state.subject_input.get();
-
-        if input.trim().is_empty() {
-            state
-                .error
-                .set(Some("Please enter a Bluesky handle".to_string()));
-            return;
-        }
-
+    // Only the Resume button picks up a saved checkpoint; any other run starts fresh
+    let run_analysis = move |source: AnalysisSource, resume: bool| {
         state.error.set(None);
         stats.set(None);
         labeled_posts.set(Vec::new());
+        report.set(None);
+        sweep.set(None);
+        snapshot_did.set(None);
+        interruption.set(None);
         is_analyzing.set(true);
         progress.set(Some("Starting analysis...".to_string()));
         progress_percent.set(0);
 
+        abort.with_value(AbortHandle::abort);
+        let handle = AbortHandle::new();
+        abort.set_value(handle.clone());
+        run_id.update_value(|id| *id += 1);
+        let id = run_id.get_value();
+        let is_current = move || run_id.try_get_value() == Some(id);
+        last_source.set_value(Some(source.clone()));
+
+        let scope = scope.get_untracked();
+
         spawn_local(async move {
             let auth_token = state.auth_token.get();
-            match crate::utils::analyze_user_posts(&input, auth_token, |msg, percent| {
-                progress.set(Some(msg));
-                progress_percent.set(percent);
-            })
-            .await
-            {
-                Ok((analysis_stats, posts)) => {
-                    stats.set(Some(analysis_stats));
-                    labeled_posts.set(posts);
+            // Stats and posts fill in live as label batches and engagement lookups finish
+            let on_event = |event: AnalysisEvent| match event {
+                _ if !is_current() => {}
+                AnalysisEvent::Progress { message, percent } => {
+                    progress.set(Some(message));
+                    progress_percent.set(percent);
+                }
+                AnalysisEvent::Stats(running) => stats.set(Some(running)),
+                AnalysisEvent::PostFound(post) => labeled_posts.update(|posts| {
+                    posts.push(post);
+                    sort_labeled_posts(posts);
+                }),
+                AnalysisEvent::PostEnriched(post) => labeled_posts.update(|posts| {
+                    if let Some(existing) = posts.iter_mut().find(|p| p.uri == post.uri) {
+                        *existing = post;
+                    }
+                }),
+            };
+            let result = match source {
+                AnalysisSource::Subject(input) => {
+                    crate::utils::analyze_user_posts(
+                        &input, auth_token, scope, resume, handle, on_event,
+                    )
+                    .await
+                }
+                AnalysisSource::CarFile(file) => match crate::utils::read_file_bytes(file).await {
+                    Ok(bytes) => {
+                        crate::utils::analyze_car_export(
+                            &bytes, auth_token, scope, resume, handle, on_event,
+                        )
+                        .await
+                    }
+                    Err(e) => Err(e),
+                },
+            };
+            if !is_current() {
+                return;
+            }
+            match result {
+                Ok(analysis) => {
+                    // Partial runs would show every unchecked post as deleted in a diff
+                    if analysis.complete {
+                        snapshots::keep(
+                            state,
+                            Snapshot::from_analysis(&analysis, chrono::Utc::now()),
+                        );
+                    }
+                    snapshot_did.set(Some(analysis.checkpoint.did.clone()));
+                    report.set(Some(analysis.clone()));
+                    // Partial results are still shown; the checkpoint lets the next run resume
+                    interruption.set(
+                        analysis.interruption.map(|reason| {
+                            (reason, analysis.pending_posts, analysis.checkpoint.did)
+                        }),
+                    );
+                    stats.set(Some(analysis.stats));
+                    labeled_posts.set(analysis.labeled_posts);
                     state.error.set(None);
                     progress.set(None);
                 }
@@ -93,6 +155,142 @@ pub fn BulkAnalysis() -> impl IntoView {
         });
     };
 
+    // Wildcard sweep: every label under the account, including ones on deleted records
+    let run_sweep = move |input: String| {
+        state.error.set(None);
+        stats.set(None);
+        labeled_posts.set(Vec::new());
+        report.set(None);
+        sweep.set(None);
+        snapshot_did.set(None);
+        interruption.set(None);
+        is_analyzing.set(true);
+        progress.set(Some("Starting sweep...".to_string()));
+        progress_percent.set(0);
+
+        abort.with_value(AbortHandle::abort);
+        let handle = AbortHandle::new();
+        abort.set_value(handle.clone());
+        run_id.update_value(|id| *id += 1);
+        let id = run_id.get_value();
+        let is_current = move || run_id.try_get_value() == Some(id);
+
+        spawn_local(async move {
+            let auth_token = state.auth_token.get_untracked();
+            let on_event = |event: AnalysisEvent| {
+                if let AnalysisEvent::Progress { message, percent } = event {
+                    if is_current() {
+                        progress.set(Some(message));
+                        progress_percent.set(percent);
+                    }
+                }
+            };
+            let result = crate::utils::sweep_account(&input, auth_token, handle, on_event).await;
+            if !is_current() {
+                return;
+            }
+            match result {
+                Ok(found) => sweep.set(Some(found)),
+                Err(e) => state.error.set(Some(format!("Error: {}", e))),
+            }
+            progress.set(None);
+            is_analyzing.set(false);
+        });
+    };
+
+    let on_sweep = move |_: leptos::ev::MouseEvent| {
+        let input = state.subject_input.get_untracked();
+        if input.trim().is_empty() {
+            state
+                .error
+                .set(Some("Please enter a Bluesky handle".to_string()));
+            return;
+        }
+        run_sweep(input);
+    };
+
+    let on_analyze = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+
+        let input = state.subject_input.get();
+
+        if input.trim().is_empty() {
+            state
+                .error
+                .set(Some("Please enter a Bluesky handle".to_string()));
+            return;
+        }
+
+        if scope.with_untracked(|s| s.collections.is_empty()) {
+            state.error.set(Some(
+                "Select at least one collection to analyze".to_string(),
+            ));
+            return;
+        }
+
+        // The route runs the analysis, so the URL can be shared and revisited
+        let path = crate::utils::bulk_path(&input);
+        let current = routed_handle.get_untracked();
+        if current.is_some_and(|handle| crate::utils::bulk_path(&handle) == path) {
+            run_analysis(AnalysisSource::Subject(input), false);
+        } else {
+            navigate(&path, Default::default());
+        }
+    };
+
+    create_effect(move |_| {
+        if let Some(handle) = routed_handle.get() {
+            state.subject_input.set(handle.clone());
+            run_analysis(AnalysisSource::Subject(handle), false);
+        }
+    });
+
+    let on_car_selected = move |file: Option<web_sys::File>| {
+        if is_analyzing.get_untracked() {
+            return;
+        }
+        if let Some(file) = file {
+            log::info!(
+                "Importing repository export {} ({} bytes)",
+                file.name(),
+                file.size()
+            );
+            run_analysis(AnalysisSource::CarFile(file), false);
+        }
+    };
+
+    // Leaving the view stops a running analysis, which saves its checkpoint
+    on_cleanup(move || {
+        abort.try_with_value(AbortHandle::abort);
+    });
+
+    let on_cancel = move |_: leptos::ev::MouseEvent| abort.with_value(AbortHandle::abort);
+
+    let on_export = move |format: ExportFormat| {
+        report.with_untracked(|analysis| {
+            if let Some(analysis) = analysis {
+                if let Err(e) = crate::utils::export_analysis(analysis, format) {
+                    state.error.set(Some(e));
+                }
+            }
+        });
+    };
+
+    let on_resume = move |_: leptos::ev::MouseEvent| {
+        if let Some(source) = last_source.get_value() {
+            run_analysis(source, true);
+        }
+    };
+
+    let on_start_over = move |_: leptos::ev::MouseEvent| {
+        if let Some((_, _, did)) = interruption.get_untracked() {
+            crate::utils::clear_checkpoint(&did);
+        }
+        if let Some(source) = last_source.get_value() {
+            run_analysis(source, false);
+        }
+    };
+
     view! {
         <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6 mb-6">
             <h2 class="text-xl font-bold mb-4">
@@ -106,7 +304,7 @@ pub fn BulkAnalysis() -> impl IntoView {
                 <div class="mb-4">
                     <input
                         type="text"
-                        placeholder="Enter Bluesky handle (e.g., alice.bsky.social)"
+                        placeholder="Enter Bluesky handle or profile link (e.g., alice.bsky.social)"
                         class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent bg-white dark:bg-gray-700"
                         prop:value=move || state.subject_input.get()
                         on:input=move |ev| {
@@ -115,19 +313,71 @@ pub fn BulkAnalysis() -> impl IntoView {
                     />
                 </div>
 
+                <ScopeOptions scope=scope />
+
                 <button
                     type="submit"
                     disabled=move || is_analyzing.get()
                     class="w-full bg-blue-600 hover:bg-blue-700 disabled:bg-gray-400 text-white font-semibold py-2 px-4 rounded-lg transition-colors"
                 >
                     {move || if is_analyzing.get() {
-                        "🔄 Analyzing..."
+                        "🔄 Analyzing...".to_string()
                     } else {
-                        "📊 Analyze Last 1000 Posts"
+                        scope.with(|s| {
+                            let noun = if s.collections == [ScopeCollection::Posts] {
+                                "Posts"
+                            } else {
+                                "Records"
+                            };
+                            match s.max_records {
+                                Some(max) => format!("📊 Analyze Last {} {}", max, noun),
+                                None => format!("📊 Analyze All {}", noun),
+                            }
+                        })
                     }}
                 </button>
+                <button
+                    type="button"
+                    on:click=on_sweep
+                    disabled=move || is_analyzing.get()
+                    class="mt-2 w-full bg-white dark:bg-gray-700 border border-blue-600 text-blue-700 dark:text-blue-300 hover:bg-blue-50 dark:hover:bg-gray-600 disabled:opacity-50 font-semibold py-2 px-4 rounded-lg transition-colors"
+                    title="Queries at://<did>/* so labels on deleted records and other collections are found too"
+                >
+                    "🗂 Find All Labels Under Account (incl. deleted records)"
+                </button>
             </form>
 
+            <label
+                class="mt-4 block p-4 border-2 border-dashed border-gray-300 dark:border-gray-600 rounded-lg text-center cursor-pointer hover:border-blue-500 dark:hover:border-blue-400 transition-colors"
+                on:dragover=|ev: leptos::ev::DragEvent| ev.prevent_default()
+                on:drop=move |ev: leptos::ev::DragEvent| {
+                    ev.prevent_default();
+                    on_car_selected(
+                        ev.data_transfer()
+                            .and_then(|dt| dt.files())
+                            .and_then(|files| files.get(0)),
+                    );
+                }
+            >
+                <input
+                    type="file"
+                    accept=".car,application/vnd.ipld.car"
+                    class="hidden"
+                    on:change=move |ev| {
+                        let input = event_target::<web_sys::HtmlInputElement>(&ev);
+                        on_car_selected(input.files().and_then(|files| files.get(0)));
+                        // Allow re-selecting the same file
+                        input.set_value("");
+                    }
+                />
+                <p class="text-sm font-semibold text-gray-700 dark:text-gray-300">
+                    "📦 Or drop a repository export (.car) here"
+                </p>
+                <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                    "Exported from Bluesky under Settings → Account → Export my data. The file is parsed locally; only label lookups go to labelers."
+                </p>
+            </label>
+
             {move || {
                 if state.auth_token.get().is_none() {
                     Some(view! {
@@ -174,16 +424,81 @@ pub fn BulkAnalysis() -> impl IntoView {
                                 style=move || format!("width: {}%", progress_percent.get())
                             />
                         </div>
+                        {move || is_analyzing.get().then(|| view! {
+                            <button
+                                type="button"
+                                on:click=on_cancel
+                                class="mt-3 px-3 py-1 text-sm font-semibold bg-white dark:bg-gray-700 border border-blue-300 dark:border-blue-600 text-blue-800 dark:text-blue-200 rounded-lg hover:bg-blue-100 dark:hover:bg-blue-800 transition-colors"
+                            >
+                                "⏹ Cancel"
+                            </button>
+                        })}
                     </div>
                 }
             })}
 
+            {move || interruption.get().map(|(reason, pending, _)| view! {
+                <div class="mt-4 p-4 bg-yellow-100 dark:bg-yellow-900 border-l-4 border-yellow-500 rounded-r-lg">
+                    <p class="text-sm font-semibold text-yellow-900 dark:text-yellow-100 mb-1">
+                        "⏸ Partial results"
+                    </p>
+                    <p class="text-sm text-yellow-800 dark:text-yellow-200">
+                        {format!(
+                            "The analysis stopped early ({}); {} posts have not been checked yet. Progress is saved, so resuming skips the posts already checked.",
+                            reason,
+                            pending,
+                        )}
+                    </p>
+                    <div class="flex gap-2 mt-3">
+                        <button
+                            type="button"
+                            on:click=on_resume
+                            class="px-3 py-1 text-sm font-semibold bg-yellow-600 hover:bg-yellow-700 text-white rounded-lg transition-colors"
+                        >
+                            "▶ Resume"
+                        </button>
+                        <button
+                            type="button"
+                            on:click=on_start_over
+                            class="px-3 py-1 text-sm font-semibold bg-white dark:bg-gray-700 border border-yellow-500 text-yellow-900 dark:text-yellow-100 rounded-lg hover:bg-yellow-50 dark:hover:bg-gray-600 transition-colors"
+                        >
+                            "Start over"
+                        </button>
+                    </div>
+                </div>
+            })}
+
+            {move || report.with(Option::is_some).then(|| view! {
+                <div class="mt-6 flex flex-wrap items-center gap-2 text-sm">
+                    <span class="font-semibold text-gray-700 dark:text-gray-300">"Export:"</span>
+                    {[
+                        (ExportFormat::Json, "⬇ JSON"),
+                        (ExportFormat::Csv, "⬇ CSV"),
+                        (ExportFormat::Html, "⬇ HTML report"),
+                    ].into_iter().map(|(format, text)| view! {
+                        <button
+                            type="button"
+                            on:click=move |_| on_export(format)
+                            class="px-3 py-1 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-lg transition-colors"
+                        >
+                            {text}
+                        </button>
+                    }).collect_view()}
+                </div>
+            })}
+
             {move || stats.get().map(|s| view! {
                 <div class="mt-6">
                     <StatsDisplay stats=s />
                 </div>
             })}
 
+            {move || sweep.get().map(|found| view! {
+                <div class="mt-6">
+                    <SweepResults sweep=found />
+                </div>
+            })}
+
             {move || {
                 let posts = labeled_posts.get();
                 if !posts.is_empty() {
@@ -198,6 +513,8 @@ pub fn BulkAnalysis() -> impl IntoView {
                 }
             }}
 
+            <SnapshotHistory subject=snapshot_did kind=SnapshotKind::Bulk />
+
             {move || selected_post.get().map(|post| view! {
                 <PostDetailModal post=post on_close=move || selected_post.set(None) />
             })}
@@ -205,6 +522,202 @@ pub fn BulkAnalysis() -> impl IntoView {
     }
 }
 
+/// Labels found by a wildcard sweep, with labels on deleted records called out
+#[component]
+fn SweepResults(sweep: AccountSweep) -> impl IntoView {
+    let only_deleted = create_rw_signal(false);
+    let deleted = sweep.on_deleted_records().count();
+    let total = sweep.labels.len();
+    let truncated = sweep.truncated;
+    let labels = store_value(sweep.labels);
+
+    view! {
+        <div class="border border-gray-200 dark:border-gray-700 rounded-lg p-4">
+            <div class="flex flex-wrap items-center justify-between gap-2 mb-3">
+                <h3 class="text-lg font-bold">
+                    {format!("🗂 {} label(s) under this account • {} on deleted records", total, deleted)}
+                </h3>
+                <label class="text-sm flex items-center gap-2 cursor-pointer">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || only_deleted.get()
+                        on:change=move |ev| only_deleted.set(event_target_checked(&ev))
+                    />
+                    "Only deleted records"
+                </label>
+            </div>
+            {truncated.then(|| view! {
+                <p class="text-sm text-yellow-800 dark:text-yellow-200 mb-3">
+                    {format!(
+                        "⚠️ Stopped after {} labels; older labels were not read.",
+                        atproto_client::analysis::MAX_SWEEP_LABELS
+                    )}
+                </p>
+            })}
+            {(total == 0).then(|| view! {
+                <p class="text-sm text-gray-500 dark:text-gray-400">"No labels found under this account."</p>
+            })}
+            <ul class="space-y-2">
+                {move || labels.with_value(|labels| {
+                    labels
+                        .iter()
+                        .filter(|swept| !only_deleted.get() || swept.record_status == RecordStatus::Deleted)
+                        .map(|swept| {
+                            let label = &swept.label;
+                            let category = label.category();
+                            let (badge_class, badge) = match swept.record_status {
+                                RecordStatus::Account => ("bg-blue-100 dark:bg-blue-900 text-blue-800 dark:text-blue-200", "👤 Account"),
+                                RecordStatus::Present => ("bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300", "📄 Record exists"),
+                                RecordStatus::Deleted => ("bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200", "🗑 Record deleted"),
+                                RecordStatus::Unknown => ("bg-yellow-100 dark:bg-yellow-900 text-yellow-800 dark:text-yellow-200", "❔ Record status unknown"),
+                            };
+                            view! {
+                                <li class="p-3 bg-gray-50 dark:bg-gray-750 rounded-lg text-sm">
+                                    <div class="flex flex-wrap items-center gap-2">
+                                        <span>{category.icon()}</span>
+                                        <span class="font-semibold">{label.val.clone()}</span>
+                                        <span class=format!("px-2 py-0.5 rounded text-xs font-semibold {}", badge_class)>{badge}</span>
+                                        <span class="text-xs text-gray-500 dark:text-gray-400">
+                                            {format!(
+                                                "by {} • {}",
+                                                crate::utils::shorten_did(&label.src),
+                                                crate::utils::format_timestamp(&label.cts)
+                                            )}
+                                        </span>
+                                        {label.is_expired().then(|| view! {
+                                            <span class="px-2 py-0.5 rounded text-xs bg-gray-200 dark:bg-gray-600">"⌛ Expired"</span>
+                                        })}
+                                    </div>
+                                    <div class="text-xs text-gray-500 dark:text-gray-400 break-all mt-1">{label.uri.clone()}</div>
+                                </li>
+                            }
+                        })
+                        .collect_view()
+                })}
+            </ul>
+        </div>
+    }
+}
+
+/// Post limit, date window, collections and reply filter for the next run
+#[component]
+fn ScopeOptions(scope: RwSignal<AnalysisScope>) -> impl IntoView {
+    let date_value = |date: Option<chrono::DateTime<chrono::Utc>>| {
+        date.map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    };
+
+    view! {
+        <details class="mb-4 text-sm">
+            <summary class="cursor-pointer font-semibold text-gray-700 dark:text-gray-300">
+                "⚙️ Scope"
+            </summary>
+            <div class="mt-3 grid grid-cols-1 md:grid-cols-3 gap-4">
+                <label class="block">
+                    <span class="text-gray-600 dark:text-gray-400">"Most recent"</span>
+                    <select
+                        class="mt-1 w-full px-2 py-1 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700"
+                        on:change=move |ev| {
+                            let value = event_target_value(&ev);
+                            scope.update(|s| s.max_records = value.parse().ok());
+                        }
+                    >
+                        {LIMIT_CHOICES.into_iter().map(|choice| {
+                            let (value, text) = match choice {
+                                Some(max) => (max.to_string(), max.to_string()),
+                                None => ("all".to_string(), "All".to_string()),
+                            };
+                            view! {
+                                <option
+                                    value=value
+                                    selected=move || scope.with(|s| s.max_records == choice)
+                                >
+                                    {text}
+                                </option>
+                            }
+                        }).collect_view()}
+                    </select>
+                </label>
+
+                <label class="block">
+                    <span class="text-gray-600 dark:text-gray-400">"Created from"</span>
+                    <input
+                        type="date"
+                        class="mt-1 w-full px-2 py-1 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700"
+                        prop:value=move || scope.with(|s| date_value(s.since))
+                        on:change=move |ev| {
+                            let since = crate::utils::parse_date_input(&event_target_value(&ev));
+                            scope.update(|s| s.since = since);
+                        }
+                    />
+                </label>
+
+                <label class="block">
+                    <span class="text-gray-600 dark:text-gray-400">"Created up to (inclusive)"</span>
+                    <input
+                        type="date"
+                        class="mt-1 w-full px-2 py-1 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700"
+                        prop:value=move || {
+                            scope.with(|s| date_value(s.until.map(|d| d - chrono::Duration::days(1))))
+                        }
+                        on:change=move |ev| {
+                            // The scope's end is exclusive; include the whole selected day
+                            let until = crate::utils::parse_date_input(&event_target_value(&ev))
+                                .map(|d| d + chrono::Duration::days(1));
+                            scope.update(|s| s.until = until);
+                        }
+                    />
+                </label>
+            </div>
+
+            <div class="mt-3 flex flex-wrap gap-x-4 gap-y-2">
+                {ScopeCollection::ALL.into_iter().map(|collection| view! {
+                    <label class="flex items-center gap-1">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || scope.with(|s| s.collections.contains(&collection))
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                scope.update(|s| {
+                                    s.collections.retain(|c| *c != collection);
+                                    if checked {
+                                        s.collections.push(collection);
+                                    }
+                                });
+                            }
+                        />
+                        {collection.name()}
+                    </label>
+                }).collect_view()}
+            </div>
+
+            <label class="mt-3 flex items-center gap-1">
+                <input
+                    type="checkbox"
+                    prop:checked=move || scope.with(|s| s.include_replies)
+                    on:change=move |ev| {
+                        let checked = event_target_checked(&ev);
+                        scope.update(|s| s.include_replies = checked);
+                    }
+                />
+                "Include replies"
+            </label>
+
+            <label class="mt-2 flex items-center gap-1">
+                <input
+                    type="checkbox"
+                    prop:checked=move || scope.with(|s| s.include_expired)
+                    on:change=move |ev| {
+                        let checked = event_target_checked(&ev);
+                        scope.update(|s| s.include_expired = checked);
+                    }
+                />
+                "Count expired labels"
+            </label>
+        </details>
+    }
+}
+
 #[component]
 fn StatsDisplay(stats: BulkAnalysisStats) -> impl IntoView {
     let percentage_with_labels = if stats.total_posts > 0 {
@@ -242,7 +755,7 @@ fn StatsDisplay(stats: BulkAnalysisStats) -> impl IntoView {
                 </div>
             })}
 
-            <div class="grid grid-cols-1 md:grid-cols-3 gap-4">
+            <div class="grid grid-cols-1 md:grid-cols-4 gap-4">
                 <div class="p-4 bg-gray-50 dark:bg-gray-700 rounded-lg">
                     <div class="text-2xl font-bold text-blue-600 dark:text-blue-400">
                         {stats.total_posts}
@@ -257,7 +770,16 @@ fn StatsDisplay(stats: BulkAnalysisStats) -> impl IntoView {
                         {stats.posts_with_labels}
                     </div>
                     <div class="text-sm text-gray-600 dark:text-gray-400">
-                        "Posts with Labels"
+                        "Externally Labeled Posts"
+                    </div>
+                </div>
+
+                <div class="p-4 bg-gray-50 dark:bg-gray-700 rounded-lg">
+                    <div class="text-2xl font-bold text-purple-600 dark:text-purple-400">
+                        {stats.self_labeled_posts}
+                    </div>
+                    <div class="text-sm text-gray-600 dark:text-gray-400">
+                        "Self-Labeled Posts"
                     </div>
                 </div>
 
@@ -271,6 +793,16 @@ fn StatsDisplay(stats: BulkAnalysisStats) -> impl IntoView {
                 </div>
             </div>
 
+            {(stats.expired_labels > 0).then(|| view! {
+                <p class="text-sm text-gray-600 dark:text-gray-400">
+                    {format!(
+                        "⌛ {} expired label{} not counted (enable \"Count expired labels\" in the scope options to include them)",
+                        stats.expired_labels,
+                        if stats.expired_labels == 1 { "" } else { "s" }
+                    )}
+                </p>
+            })}
+
             {if !stats.labels_by_category.is_empty() {
                 view! {
                     <div>
@@ -403,7 +935,11 @@ fn LabeledPostsList(
                                     <div class="flex-1 min-w-0">
                                         <p class="text-sm text-gray-900 dark:text-gray-100 truncate mb-2">
                                             {if post.text.is_empty() {
-                                                "[No text]".to_string()
+                                                match post.collection.as_str() {
+                                                    "app.bsky.feed.like" => "❤️ Liked post".to_string(),
+                                                    "app.bsky.feed.repost" => "🔁 Reposted post".to_string(),
+                                                    _ => "[No text]".to_string(),
+                                                }
                                             } else if post.text.len() > 100 {
                                                 format!("{}...", &post.text[..100])
                                             } else {
@@ -421,8 +957,16 @@ fn LabeledPostsList(
                                                     LabelCategory::ModerationAction => "bg-pink-100 text-pink-800 dark:bg-pink-900 dark:text-pink-200",
                                                     _ => "bg-gray-100 text-gray-800 dark:bg-gray-700 dark:text-gray-200",
                                                 };
+                                                let (border, title) = if label.self_applied {
+                                                    ("border border-dashed border-current", "Self-label applied by the author")
+                                                } else if label.is_expired() {
+                                                    ("line-through opacity-60", "Expired")
+                                                } else {
+                                                    ("", "")
+                                                };
                                                 view! {
-                                                    <span class=format!("px-2 py-1 rounded text-xs font-medium {}", color)>
+                                                    <span class=format!("px-2 py-1 rounded text-xs font-medium {} {}", color, border) title=title>
+                                                        {if label.self_applied { "✍️ " } else { "" }}
                                                         {&label.val}
                                                     </span>
                                                 }
@@ -469,6 +1013,19 @@ where
 {
     let show_likers = create_rw_signal(false);
     let show_reposters = create_rw_signal(false);
+    // Ok(commit CID) once verified, Err(reason) if an on-demand proof check failed
+    let verification = create_rw_signal(post.verified_commit.clone().map(Ok::<_, String>));
+    let verifying = create_rw_signal(false);
+
+    let post_uri = post.uri.clone();
+    let on_verify = move |_| {
+        let uri = post_uri.clone();
+        verifying.set(true);
+        spawn_local(async move {
+            verification.set(Some(crate::utils::verify_post(&uri).await));
+            verifying.set(false);
+        });
+    };
 
     view! {
         <div
@@ -520,8 +1077,14 @@ where
                                                 </span>
                                             </div>
                                             <div class="text-xs text-gray-600 dark:text-gray-400 space-y-1">
-                                                <div>"Source: " <span class="font-mono">{crate::utils::shorten_did(&label.src)}</span></div>
+                                                <div>
+                                                    "Source: " <span class="font-mono">{crate::utils::shorten_did(&label.src)}</span>
+                                                    {label.self_applied.then(|| view! {
+                                                        <span class="ml-1 text-purple-600 dark:text-purple-400">"(self-label by author)"</span>
+                                                    })}
+                                                </div>
                                                 <div>"Applied: " {crate::utils::format_timestamp(&label.cts)}</div>
+                                                {crate::utils::expiry_note(label, chrono::Utc::now()).map(|note| view! { <div>{note}</div> })}
                                                 {if !post.created_at.is_empty() {
                                                     let duration = crate::utils::calculate_duration(&post.created_at, &label.cts);
                                                     let is_moderation = label.val.starts_with('!');
@@ -569,6 +1132,35 @@ where
                             </p>
                         </div>
 
+                        <div>
+                            <h4 class="text-sm font-semibold text-gray-700 dark:text-gray-300 mb-2">
+                                "Authenticity"
+                            </h4>
+                            {move || match verification.get() {
+                                Some(Ok(commit)) => view! {
+                                    <p class="text-sm text-green-700 dark:text-green-400">
+                                        "✅ Verified from signed repo (commit "
+                                        <span class="font-mono break-all">{commit}</span>
+                                        ")"
+                                    </p>
+                                }.into_view(),
+                                other => view! {
+                                    <div class="flex items-center gap-3">
+                                        <button
+                                            on:click=on_verify.clone()
+                                            disabled=move || verifying.get()
+                                            class="px-3 py-1 text-sm bg-gray-100 dark:bg-gray-700 rounded hover:bg-gray-200 dark:hover:bg-gray-600 disabled:opacity-50"
+                                        >
+                                            {move || if verifying.get() { "Verifying..." } else { "🔏 Verify against signed repo" }}
+                                        </button>
+                                        {other.and_then(Result::err).map(|e| view! {
+                                            <span class="text-xs text-red-600 dark:text-red-400">"❌ " {e}</span>
+                                        })}
+                                    </div>
+                                }.into_view(),
+                            }}
+                        </div>
+
                         // Display images
                         {if !post.image_urls.is_empty() {
                             view! {
@@ -736,9 +1328,138 @@ where
                                 </div>
                             }
                         }
+
+                        {post.engagement_truncated.then(|| view! {
+                            <p class="text-xs text-gray-500 dark:text-gray-400">
+                                {format!(
+                                    "Listed {} of {} likers and {} of {} reposters.",
+                                    post.likers.len(),
+                                    post.like_count,
+                                    post.reposters.len(),
+                                    post.repost_count
+                                )}
+                            </p>
+                        })}
+
+                        {post.engagement_error.clone().map(|error| view! {
+                            <p class="text-xs text-yellow-800 dark:text-yellow-200">
+                                {format!("Engagement is incomplete: {}", error)}
+                            </p>
+                        })}
+
+                        <EngagementLabels post=post.clone() />
                     </div>
                 </div>
             </div>
         </div>
     }
 }
+
+/// Labeled accounts among a post's likers and reposters, queried on request
+#[component]
+fn EngagementLabels(post: PostWithLabels) -> impl IntoView {
+    let state = expect_context::<AppState>();
+    let engagers = post.likers.len() + post.reposters.len();
+    let post = create_rw_signal(post);
+    let checking = create_rw_signal(false);
+    let error = create_rw_signal::<Option<String>>(None);
+
+    let on_check = move |e: leptos::ev::MouseEvent| {
+        e.stop_propagation();
+        checking.set(true);
+        error.set(None);
+        spawn_local(async move {
+            let snapshot = post.get_untracked();
+            let (labels, failure) =
+                crate::utils::engagement_labels(&snapshot, state.auth_token.get_untracked()).await;
+            post.update(|post| post.engagement_labels = Some(labels));
+            error.set(failure);
+            checking.set(false);
+        });
+    };
+
+    view! {
+        <div class="border-t border-gray-200 dark:border-gray-700 pt-3">
+            <h4 class="text-sm font-semibold text-gray-700 dark:text-gray-300 mb-2">
+                "🕵 Labeled Amplifiers"
+            </h4>
+            {move || post.with(|post| {
+                if post.engagement_labels.is_none() {
+                    return view! {
+                        <button
+                            type="button"
+                            on:click=on_check
+                            disabled=move || checking.get() || engagers == 0
+                            class="px-3 py-1 text-sm bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 disabled:opacity-50 rounded-lg transition-colors"
+                        >
+                            {move || if checking.get() {
+                                "🔄 Checking...".to_string()
+                            } else {
+                                format!("Check {} liker/reposter account(s) for labels", engagers)
+                            }}
+                        </button>
+                    }.into_view();
+                }
+
+                let labeled = post.labeled_engagers();
+                let spam = labeled
+                    .iter()
+                    .filter(|(_, labels)| labels.iter().any(|label| label.category() == LabelCategory::Spam))
+                    .count();
+                let accounts = post
+                    .likers
+                    .iter()
+                    .chain(&post.reposters)
+                    .map(|user| user.did.as_str())
+                    .collect::<std::collections::HashSet<_>>()
+                    .len();
+                let summary = format!(
+                    "{} of {} account(s) that liked or reposted carry account labels; {} spam-labeled",
+                    labeled.len(),
+                    accounts,
+                    spam
+                );
+                let rows = labeled
+                    .into_iter()
+                    .map(|(user, labels)| {
+                        let liked = post.likers.iter().any(|liker| liker.did == user.did);
+                        let reposted = post.reposters.iter().any(|reposter| reposter.did == user.did);
+                        let how = match (liked, reposted) {
+                            (true, true) => "❤️🔁",
+                            (true, false) => "❤️",
+                            _ => "🔁",
+                        };
+                        view! {
+                            <li class="flex flex-wrap items-center gap-2 text-sm">
+                                <span title="Liked / reposted">{how}</span>
+                                <a
+                                    href=format!("https://bsky.app/profile/{}", user.handle)
+                                    target="_blank"
+                                    class="text-blue-600 dark:text-blue-400 hover:underline"
+                                >
+                                    {format!("@{}", user.handle)}
+                                </a>
+                                {labels.into_iter().map(|label| view! {
+                                    <span
+                                        class="px-2 py-0.5 rounded bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200 text-xs font-mono"
+                                        title=format!("by {}", label.src)
+                                    >
+                                        {label.category().icon()} " " {label.val.clone()}
+                                    </span>
+                                }).collect_view()}
+                            </li>
+                        }
+                    })
+                    .collect_view();
+
+                view! {
+                    <p class="text-sm text-gray-700 dark:text-gray-300 mb-2">{summary}</p>
+                    <ul class="max-h-48 overflow-y-auto space-y-1">{rows}</ul>
+                }.into_view()
+            })}
+            {move || error.get().map(|e| view! {
+                <p class="mt-2 text-sm text-red-700 dark:text-red-300">{format!("⚠️ {}", e)}</p>
+            })}
+        </div>
+    }
+}
@@ -1,14 +1,17 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use leptos::*;
-use wasm_bindgen_futures::spawn_local;
+use leptos_router::{use_navigate, use_params_map};
 
+use super::single_check::{route_subject, run_check};
 use crate::state::AppState;
 use crate::utils;
 
 #[component]
 pub fn InputPanel() -> impl IntoView {
     let state = expect_context::<AppState>();
+    let navigate = use_navigate();
+    let params = use_params_map();
 
     let on_submit = move |ev: leptos::ev::SubmitEvent| {
         ev.prevent_default();
@@ -16,29 +19,20 @@ pub fn InputPanel() -> impl IntoView {
         let input = state.subject_input.get();
 
         if input.trim().is_empty() {
-            state
-                .error
-                .set(Some("Please enter a handle, DID, or AT-URI".to_string()));
+            state.error.set(Some(
+                "Please enter a handle, DID, AT-URI or Bluesky link".to_string(),
+            ));
             return;
         }
 
-        state.error.set(None);
-        state.is_loading.set(true);
-
-        spawn_local(async move {
-            let auth_token = state.auth_token.get();
-            match utils::fetch_labels(&input, auth_token).await {
-                Ok(collection) => {
-                    state.labels.set(Some(collection));
-                    state.error.set(None);
-                }
-                Err(e) => {
-                    state.error.set(Some(format!("Error: {}", e)));
-                    state.labels.set(None);
-                }
-            }
-            state.is_loading.set(false);
-        });
+        // The route runs the check, so the URL can be shared and revisited
+        let path = utils::check_path(&input);
+        let current = params.with_untracked(route_subject);
+        if current.is_some_and(|subject| utils::check_path(&subject) == path) {
+            run_check(state, input);
+        } else {
+            navigate(&path, Default::default());
+        }
     };
 
     view! {
@@ -49,13 +43,13 @@ pub fn InputPanel() -> impl IntoView {
                         for="subject-input"
                         class="block text-sm font-medium mb-2"
                     >
-                        "Enter Bluesky handle, DID, or post AT-URI"
+                        "Enter Bluesky handle, DID, AT-URI or bsky.app link"
                     </label>
 
                     <input
                         id="subject-input"
                         type="text"
-                        placeholder="alice.bsky.social or did:plc:... or at://..."
+                        placeholder="alice.bsky.social, did:plc:..., at://... or https://bsky.app/profile/..."
                         class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent bg-white dark:bg-gray-700"
                         prop:value=move || state.subject_input.get()
                         on:input=move |ev| {
@@ -64,7 +58,7 @@ pub fn InputPanel() -> impl IntoView {
                     />
 
                     <p class="mt-2 text-xs text-gray-500 dark:text-gray-400">
-                        "Examples: alice.bsky.social • did:plc:xyz123 • at://did:plc:xyz/app.bsky.feed.post/abc"
+                        "Examples: @alice.bsky.social • did:plc:xyz123 • at://did:plc:xyz/app.bsky.feed.post/abc • https://bsky.app/profile/alice.bsky.social/post/abc"
                     </p>
                 </div>
 
@@ -32,9 +32,26 @@ pub fn LabelBadge(label: Label) -> impl IntoView {
     let formatted_time = utils::format_timestamp(&label.cts);
     let shortened_did = utils::shorten_did(&label.src);
 
+    let now = create_rw_signal(chrono::Utc::now());
+    let expired = label.is_expired_at(now.get_untracked());
+    let countdown = utils::expires_soon(&label, now.get_untracked()).then(|| label.clone());
+    if countdown.is_some() {
+        // Keep the countdown ticking while the badge is on screen
+        if let Ok(handle) = set_interval_with_handle(
+            move || now.set(chrono::Utc::now()),
+            std::time::Duration::from_secs(1),
+        ) {
+            on_cleanup(move || handle.clear());
+        }
+    }
+
     view! {
         <div
-            class=format!("p-4 rounded-lg border-2 {} transition-all hover:shadow-md", color_class)
+            class=format!(
+                "p-4 rounded-lg border-2 {} transition-all hover:shadow-md {}",
+                color_class,
+                if expired { "opacity-60" } else { "" }
+            )
             title=label.description()
         >
             <div class="flex items-start justify-between">
@@ -42,6 +59,11 @@ pub fn LabelBadge(label: Label) -> impl IntoView {
                     <div class="flex items-center gap-2 mb-2">
                         <span class="text-2xl">{category.icon()}</span>
                         <span class="font-bold text-lg">{label.val.clone()}</span>
+                        {expired.then(|| view! {
+                            <span class="px-2 py-0.5 rounded bg-gray-200 dark:bg-gray-600 text-xs font-semibold">
+                                "⌛ Expired"
+                            </span>
+                        })}
                     </div>
 
                     <p class="text-sm opacity-75 mb-2">
@@ -52,20 +74,41 @@ pub fn LabelBadge(label: Label) -> impl IntoView {
                         <div>
                             <span class="font-semibold">"Source: "</span>
                             <span>{shortened_did}</span>
+                            {label.self_applied.then(|| view! {
+                                <span class="ml-1 px-1 rounded bg-purple-200 dark:bg-purple-800">
+                                    "✍️ Self-applied by author"
+                                </span>
+                            })}
                         </div>
                         <div>
                             <span class="font-semibold">"Created: "</span>
                             <span>{formatted_time}</span>
                         </div>
+                        {label.provenance.map(|provenance| view! {
+                            <div>
+                                <span class="font-semibold">"Via: "</span>
+                                <span class="break-all">{provenance.endpoint}</span>
+                                {provenance.authenticated.then(|| view! {
+                                    <span class="ml-1">"🔐 authenticated"</span>
+                                })}
+                            </div>
+                        })}
                         {label.exp.map(|exp| {
                             let formatted_exp = utils::format_timestamp(&exp);
                             view! {
                                 <div>
-                                    <span class="font-semibold">"Expires: "</span>
+                                    <span class="font-semibold">
+                                        {if expired { "Expired: " } else { "Expires: " }}
+                                    </span>
                                     <span>{formatted_exp}</span>
                                 </div>
                             }
                         })}
+                        {countdown.map(|label| view! {
+                            <div class="font-semibold text-yellow-800 dark:text-yellow-200">
+                                {move || utils::expiry_note(&label, now.get())}
+                            </div>
+                        })}
                     </div>
                 </div>
             </div>
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use atproto_client::{LabelChangeKind, LabelDiff};
+use leptos::*;
+
+use crate::utils;
+
+/// Labels added, negated, expired or removed between two snapshots
+#[component]
+pub fn LabelChanges(changes: LabelDiff) -> impl IntoView {
+    if changes.is_empty() {
+        return view! {
+            <p class="text-sm text-gray-500 dark:text-gray-400">"No label changes"</p>
+        }
+        .into_view();
+    }
+
+    view! {
+        <ul class="space-y-1 text-sm">
+            {changes.changes.into_iter().map(|change| {
+                let color = match change.kind {
+                    LabelChangeKind::Added => "text-red-700 dark:text-red-300",
+                    LabelChangeKind::Negated => "text-green-700 dark:text-green-300",
+                    LabelChangeKind::Expired | LabelChangeKind::Removed => "text-gray-600 dark:text-gray-400",
+                };
+                let label = change.label;
+                let source = if label.self_applied {
+                    "author (self-label)".to_string()
+                } else {
+                    utils::shorten_did(&label.src)
+                };
+                view! {
+                    <li class=format!("flex flex-wrap items-center gap-2 {}", color)>
+                        <span>{change.kind.icon()}</span>
+                        <span class="font-semibold">{change.kind.name()}</span>
+                        <code class="px-1 rounded bg-gray-100 dark:bg-gray-700">{label.val.clone()}</code>
+                        <span class="text-xs">{format!("by {}", source)}</span>
+                        {label.uri.starts_with("at://").then(|| view! {
+                            <span class="text-xs text-gray-500 dark:text-gray-400 break-all">{label.uri.clone()}</span>
+                        })}
+                    </li>
+                }
+            }).collect_view()}
+        </ul>
+    }
+    .into_view()
+}
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use atproto_client::{LabelSpan, LabelTimeline, TimelineEvent, TimelineEventKind};
+use leptos::*;
+
+use crate::state::AppState;
+use crate::utils;
+
+/// Every application, negation and expiry for the checked subject, oldest first
+#[component]
+pub fn LabelHistory() -> impl IntoView {
+    let state = expect_context::<AppState>();
+
+    let timeline = move || {
+        state.labels.with(|collection| {
+            collection
+                .as_ref()
+                .map(|collection| LabelTimeline::from_collection(collection, chrono::Utc::now()))
+        })
+    };
+
+    move || {
+        let timeline = timeline()?;
+        if timeline.events.is_empty() {
+            return None;
+        }
+
+        let active = timeline.active().count();
+        let summary = format!(
+            "{} event{} • {} label{} in effect • {}",
+            timeline.events.len(),
+            if timeline.events.len() == 1 { "" } else { "s" },
+            active,
+            if active == 1 { "" } else { "s" },
+            if timeline.has_reversals() {
+                "some labels were negated or expired"
+            } else {
+                "never reversed"
+            }
+        );
+
+        let entries = timeline
+            .events
+            .iter()
+            .map(|event| {
+                // Applications that started a span say how long it lasted
+                let span = (event.kind == TimelineEventKind::Applied)
+                    .then(|| timeline.spans.iter().find(|span| starts_span(span, event)))
+                    .flatten()
+                    .cloned();
+                view! { <HistoryEntry event=event.clone() span=span /> }
+            })
+            .collect_view();
+
+        Some(view! {
+            <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6 mt-6">
+                <h2 class="text-xl font-bold mb-1">"🕒 Label History"</h2>
+                <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">{summary}</p>
+                <ol class="relative border-l-2 border-gray-200 dark:border-gray-700 ml-2 space-y-4">
+                    {entries}
+                </ol>
+            </div>
+        })
+    }
+}
+
+#[component]
+fn HistoryEntry(event: TimelineEvent, span: Option<LabelSpan>) -> impl IntoView {
+    let dot_class = match event.kind {
+        TimelineEventKind::Applied => "bg-red-500",
+        TimelineEventKind::Negated => "bg-green-500",
+        TimelineEventKind::Expired => "bg-gray-400",
+    };
+    let at = utils::format_timestamp(&event.at.to_rfc3339());
+    let source = if event.label.self_applied {
+        "author (self-label)".to_string()
+    } else {
+        utils::shorten_did(&event.label.src)
+    };
+
+    let duration = span.map(|span| {
+        let start = span.applied_at.to_rfc3339();
+        match span.ended_at {
+            Some(ended) => view! {
+                <span class="text-gray-600 dark:text-gray-400">
+                    {format!(
+                        "In effect for {} (until {})",
+                        utils::calculate_duration(&start, &ended.to_rfc3339()),
+                        utils::format_timestamp(&ended.to_rfc3339())
+                    )}
+                </span>
+            }
+            .into_view(),
+            None => view! {
+                <span class="px-2 py-0.5 rounded bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200 font-semibold">
+                    {format!(
+                        "Active • {} so far",
+                        utils::calculate_duration(&start, &chrono::Utc::now().to_rfc3339())
+                    )}
+                </span>
+            }
+            .into_view(),
+        }
+    });
+
+    view! {
+        <li class="ml-4">
+            <span class=format!(
+                "absolute -left-2 mt-1.5 w-3 h-3 rounded-full border-2 border-white dark:border-gray-800 {}",
+                dot_class
+            )></span>
+            <div class="text-xs text-gray-500 dark:text-gray-400">{at}</div>
+            <div class="flex flex-wrap items-center gap-2 text-sm">
+                <span>{event.kind.icon()}</span>
+                <span class="font-semibold">{event.kind.name()}</span>
+                <code class="px-1 rounded bg-gray-100 dark:bg-gray-700">{event.label.val.clone()}</code>
+                <span class="text-gray-600 dark:text-gray-400">{format!("by {}", source)}</span>
+            </div>
+            {(event.label.uri.starts_with("at://")).then(|| view! {
+                <div class="text-xs text-gray-500 dark:text-gray-400 break-all">{event.label.uri.clone()}</div>
+            })}
+            <div class="text-xs mt-1">{duration}</div>
+        </li>
+    }
+}
+
+fn starts_span(span: &LabelSpan, event: &TimelineEvent) -> bool {
+    span.applied_at == event.at
+        && span.val == event.label.val
+        && span.src == event.label.src
+        && span.uri == event.label.uri
+}
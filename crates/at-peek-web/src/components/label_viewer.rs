@@ -1,28 +1,59 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use atproto_client::LabelCategory;
+use atproto_client::{LabelCategory, Subject};
 use leptos::*;
 
 use super::{EmptyState, LabelBadge};
 use crate::state::AppState;
+use crate::utils::CheckExportFormat;
 
 #[component]
 pub fn LabelViewer() -> impl IntoView {
     let state = expect_context::<AppState>();
+    let include_expired = create_rw_signal(false);
 
+    // Labels in effect by category, and expired labels kept apart
     let categorized_labels = move || {
         state.labels.get().map(|collection| {
+            let now = chrono::Utc::now();
             let mut categories: std::collections::HashMap<LabelCategory, Vec<_>> =
                 std::collections::HashMap::new();
+            let mut expired = Vec::new();
 
             for label in collection.labels {
-                categories.entry(label.category()).or_default().push(label);
+                if label.is_expired_at(now) {
+                    expired.push(label);
+                } else {
+                    categories.entry(label.category()).or_default().push(label);
+                }
             }
 
-            categories
+            (categories, expired)
         })
     };
 
+    let on_export = move |format: CheckExportFormat| {
+        let subject = state.subject_input.get_untracked();
+        state.labels.with_untracked(|collection| {
+            if let Some(collection) = collection {
+                if let Err(e) = crate::utils::export_labels(subject.trim(), collection, format) {
+                    state.error.set(Some(e));
+                }
+            }
+        });
+    };
+
+    let is_watched = move || {
+        let subject = Subject::parse(&state.subject_input.get()).ok()?.to_string();
+        Some(state.watchlist.with(|list| list.entry(&subject).is_some()))
+    };
+
+    let on_watch = move |_| {
+        if let Err(e) = crate::watchlist::watch(state, &state.subject_input.get_untracked()) {
+            state.error.set(Some(e));
+        }
+    };
+
     view! {
         <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6">
             <Show
@@ -34,12 +65,13 @@ pub fn LabelViewer() -> impl IntoView {
                 }
             >
                 {move || {
-                    let Some(categories) = categorized_labels() else {
+                    let Some((categories, expired)) = categorized_labels() else {
                         return view! { <div/> }.into_view();
                     };
+                    let expired_section = view! { <ExpiredLabels labels=expired include_expired=include_expired /> };
 
                     if categories.is_empty() {
-                        return view! { <EmptyState /> }.into_view();
+                        return view! { <EmptyState /> {expired_section} }.into_view();
                     }
 
                     view! {
@@ -61,12 +93,73 @@ pub fn LabelViewer() -> impl IntoView {
                             >
                                 <CategoryGroup category=item.0 labels=item.1 />
                             </For>
+                            {expired_section}
                         </div>
                     }.into_view()
                 }}
+                // Exported even when empty: "no labels, per these responses" is evidence too
+                <div class="mt-6 flex flex-wrap items-center gap-2 text-sm">
+                    <span class="font-semibold text-gray-700 dark:text-gray-300">"Export:"</span>
+                    {[
+                        (CheckExportFormat::Json, "⬇ JSON"),
+                        (CheckExportFormat::Markdown, "⬇ Markdown"),
+                    ].into_iter().map(|(format, text)| view! {
+                        <button
+                            type="button"
+                            on:click=move |_| on_export(format)
+                            class="px-3 py-1 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-lg transition-colors"
+                        >
+                            {text}
+                        </button>
+                    }).collect_view()}
+                    <button
+                        type="button"
+                        on:click=on_watch
+                        disabled=move || is_watched() != Some(false)
+                        class="ml-auto px-3 py-1 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 disabled:opacity-50 rounded-lg transition-colors"
+                    >
+                        {move || if is_watched() == Some(true) { "👁 Watching" } else { "👁 Add to Watchlist" }}
+                    </button>
+                </div>
+            </Show>
+        </div>
+    }
+}
+
+/// Labels whose expiry has passed, listed only when asked for
+#[component]
+fn ExpiredLabels(
+    labels: Vec<atproto_client::Label>,
+    include_expired: RwSignal<bool>,
+) -> impl IntoView {
+    if labels.is_empty() {
+        return ().into_view();
+    }
+    let count = labels.len();
+    let labels = store_value(labels);
+
+    view! {
+        <div class="mt-6 border-t border-gray-200 dark:border-gray-700 pt-4">
+            <label class="text-sm flex items-center gap-2 cursor-pointer text-gray-700 dark:text-gray-300">
+                <input
+                    type="checkbox"
+                    prop:checked=move || include_expired.get()
+                    on:change=move |ev| include_expired.set(event_target_checked(&ev))
+                />
+                {format!(
+                    "Include expired labels ({} no longer in effect)",
+                    count
+                )}
+            </label>
+            <Show when=move || include_expired.get()>
+                <div class="mt-3 space-y-3">
+                    <h3 class="font-semibold text-gray-600 dark:text-gray-400">"⌛ Expired Labels"</h3>
+                    {labels.get_value().into_iter().map(|label| view! { <LabelBadge label=label /> }).collect_view()}
+                </div>
             </Show>
         </div>
     }
+    .into_view()
 }
 
 #[component]
@@ -0,0 +1,421 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use leptos::*;
+use leptos_router::{use_location, use_navigate, use_params_map, A};
+use wasm_bindgen_futures::spawn_local;
+
+use super::LabelBadge;
+use crate::state::AppState;
+use crate::utils;
+use atproto_client::analysis::DEFAULT_MAX_LIST_MEMBERS;
+use atproto_client::{AbortHandle, AnalysisEvent, ListInspection, ListMember};
+
+/// Members read per list offered in the options
+const LIMIT_CHOICES: [usize; 4] = [1000, 5000, DEFAULT_MAX_LIST_MEMBERS, 100_000];
+
+/// List inspection for `/list`, `/list/:did/:rkey` and `/starter-pack/:did/:rkey`
+#[component]
+pub fn ListInspectionView() -> impl IntoView {
+    let state = expect_context::<AppState>();
+    let max_members = create_rw_signal(DEFAULT_MAX_LIST_MEMBERS);
+    let include_expired = create_rw_signal(false);
+    let inspection = create_rw_signal::<Option<ListInspection>>(None);
+    let is_running = create_rw_signal(false);
+    let progress = create_rw_signal::<Option<String>>(None);
+    let progress_percent = create_rw_signal(0);
+    let abort = store_value(AbortHandle::new());
+    // Bumped per run so a run superseded by navigation stops updating the view
+    let run_id = store_value(0u64);
+    let navigate = use_navigate();
+    let location = use_location();
+    let params = use_params_map();
+    let routed_list = create_memo(move |_| {
+        let collection = if location
+            .pathname
+            .with(|path| path.starts_with("/starter-pack"))
+        {
+            "app.bsky.graph.starterpack"
+        } else {
+            "app.bsky.graph.list"
+        };
+        params.with(|p| match (p.get("did"), p.get("rkey")) {
+            (Some(did), Some(rkey)) => Some(format!("at://{}/{}/{}", did, collection, rkey)),
+            _ => None,
+        })
+    });
+
+    let run_inspection = move |input: String| {
+        state.error.set(None);
+        inspection.set(None);
+        is_running.set(true);
+        progress.set(Some("Starting list inspection...".to_string()));
+        progress_percent.set(0);
+
+        abort.with_value(AbortHandle::abort);
+        let handle = AbortHandle::new();
+        abort.set_value(handle.clone());
+        run_id.update_value(|id| *id += 1);
+        let id = run_id.get_value();
+        let is_current = move || run_id.try_get_value() == Some(id);
+
+        spawn_local(async move {
+            let on_event = |event: AnalysisEvent| {
+                if let AnalysisEvent::Progress { message, percent } = event {
+                    if is_current() {
+                        progress.set(Some(message));
+                        progress_percent.set(percent);
+                    }
+                }
+            };
+            let result = utils::inspect_list(
+                &input,
+                state.auth_token.get_untracked(),
+                max_members.get_untracked(),
+                include_expired.get_untracked(),
+                handle,
+                on_event,
+            )
+            .await;
+            if !is_current() {
+                return;
+            }
+            match result {
+                Ok(found) => inspection.set(Some(found)),
+                Err(e) => state.error.set(Some(format!("Error: {}", e))),
+            }
+            progress.set(None);
+            is_running.set(false);
+        });
+    };
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+
+        let input = state.subject_input.get_untracked();
+        let Some(path) = utils::list_path(&input) else {
+            state.error.set(Some(
+                "Please enter a list or starter pack AT-URI or Bluesky link".to_string(),
+            ));
+            return;
+        };
+
+        // The route runs the inspection, so the URL can be shared and revisited
+        let current = routed_list.get_untracked();
+        if current.is_some_and(|list| utils::list_path(&list).as_ref() == Some(&path)) {
+            run_inspection(input);
+        } else {
+            navigate(&path, Default::default());
+        }
+    };
+
+    create_effect(move |_| {
+        if let Some(list) = routed_list.get() {
+            state.subject_input.set(list.clone());
+            run_inspection(list);
+        }
+    });
+
+    on_cleanup(move || {
+        abort.try_with_value(AbortHandle::abort);
+    });
+
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6 mb-6">
+            <h2 class="text-xl font-bold mb-1">"📃 List Inspection"</h2>
+            <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                "Check a moderation list, curation list or starter pack for labels on the list itself and on every member. Members are read from the owner's list item records, so taken down accounts are included."
+            </p>
+
+            <form on:submit=on_submit>
+                <input
+                    type="text"
+                    placeholder="Enter a list or starter pack link (e.g., https://bsky.app/profile/alice.bsky.social/lists/3k...)"
+                    class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent bg-white dark:bg-gray-700"
+                    prop:value=move || state.subject_input.get()
+                    on:input=move |ev| state.subject_input.set(event_target_value(&ev))
+                />
+
+                <div class="mt-3 flex flex-wrap items-center gap-4 text-sm">
+                    <label class="flex items-center gap-2">
+                        "Up to"
+                        <select
+                            class="px-2 py-1 border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700"
+                            on:change=move |ev| {
+                                if let Ok(max) = event_target_value(&ev).parse() {
+                                    max_members.set(max);
+                                }
+                            }
+                        >
+                            {LIMIT_CHOICES.into_iter().map(|max| view! {
+                                <option value=max.to_string() selected=move || max_members.get() == max>
+                                    {max.to_string()}
+                                </option>
+                            }).collect_view()}
+                        </select>
+                        "members"
+                    </label>
+                    <label class="flex items-center gap-2 cursor-pointer">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || include_expired.get()
+                            on:change=move |ev| include_expired.set(event_target_checked(&ev))
+                        />
+                        "Count expired labels"
+                    </label>
+                </div>
+
+                <button
+                    type="submit"
+                    disabled=move || is_running.get()
+                    class="mt-4 w-full bg-blue-600 hover:bg-blue-700 disabled:bg-gray-400 text-white font-semibold py-2 px-4 rounded-lg transition-colors"
+                >
+                    {move || if is_running.get() { "🔄 Inspecting..." } else { "📃 Inspect List" }}
+                </button>
+            </form>
+
+            {move || state.error.get().map(|err| view! {
+                <div class="mt-4 p-4 bg-red-100 dark:bg-red-900 border border-red-300 dark:border-red-700 rounded-lg">
+                    <p class="text-red-800 dark:text-red-200 text-sm">{err}</p>
+                </div>
+            })}
+
+            {move || progress.get().map(|msg| view! {
+                <div class="mt-4 p-4 bg-blue-50 dark:bg-blue-900 rounded-lg border border-blue-200 dark:border-blue-700">
+                    <div class="flex items-center justify-between mb-2">
+                        <p class="text-sm font-semibold text-blue-800 dark:text-blue-200">{msg}</p>
+                        <span class="text-sm font-bold text-blue-600 dark:text-blue-400">
+                            {move || progress_percent.get()}"%"
+                        </span>
+                    </div>
+                    <div class="w-full bg-blue-200 dark:bg-blue-800 rounded-full h-2.5 overflow-hidden">
+                        <div
+                            class="bg-blue-600 dark:bg-blue-400 h-2.5 rounded-full transition-all duration-300 ease-out"
+                            style=move || format!("width: {}%", progress_percent.get())
+                        />
+                    </div>
+                    <button
+                        type="button"
+                        on:click=move |_| abort.with_value(AbortHandle::abort)
+                        class="mt-3 px-3 py-1 text-sm font-semibold bg-white dark:bg-gray-700 border border-blue-300 dark:border-blue-600 text-blue-800 dark:text-blue-200 rounded-lg hover:bg-blue-100 dark:hover:bg-blue-800 transition-colors"
+                    >
+                        "⏹ Cancel"
+                    </button>
+                </div>
+            })}
+        </div>
+
+        {move || inspection.get().map(|found| view! { <ListResults inspection=found /> })}
+    }
+}
+
+/// The list's own labels, the member label distribution and the filterable member list
+#[component]
+fn ListResults(inspection: ListInspection) -> impl IntoView {
+    let label_filter = create_rw_signal::<Option<String>>(None);
+    let labeled_only = create_rw_signal(true);
+    let search = create_rw_signal(String::new());
+
+    let kind = inspection.kind.name();
+    let labeled = inspection.labeled_members().count();
+    let counts = inspection.label_counts();
+    let summary = format!(
+        "{} members • {} labeled member{}",
+        inspection.members.len(),
+        labeled,
+        if labeled == 1 { "" } else { "s" }
+    );
+    let mut notes = Vec::new();
+    if inspection.truncated {
+        notes.push("⚠️ The list was longer than the limit; the rest were not checked.".to_string());
+    }
+    if let Some(reason) = &inspection.interruption {
+        notes.push(format!("⏸ The inspection stopped early: {}", reason));
+    }
+    if inspection.unchecked > 0 {
+        notes.push(format!(
+            "⚠️ {} member(s) could not be checked.",
+            inspection.unchecked
+        ));
+    }
+    if inspection.expired_labels > 0 {
+        notes.push(format!(
+            "⌛ {} expired label(s) not counted.",
+            inspection.expired_labels
+        ));
+    }
+    let list_labels = inspection.labels.clone();
+    let members = store_value(inspection.members);
+
+    let shown = move || {
+        let search = search.get().trim().to_lowercase();
+        let label_filter = label_filter.get();
+        let labeled_only = labeled_only.get();
+        members.with_value(|members| {
+            members
+                .iter()
+                .filter(|member| !labeled_only || !member.labels.is_empty())
+                .filter(|member| {
+                    label_filter
+                        .as_ref()
+                        .is_none_or(|val| member.labels.iter().any(|label| &label.val == val))
+                })
+                .filter(|member| {
+                    search.is_empty()
+                        || member.did.contains(&search)
+                        || member
+                            .handle
+                            .as_ref()
+                            .is_some_and(|handle| handle.contains(&search))
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6 mb-6">
+            <p class="text-xs uppercase tracking-wide text-gray-500 dark:text-gray-400">{kind}</p>
+            <h3 class="text-lg font-bold">
+                <A href=utils::check_path(&inspection.uri) class="hover:underline">
+                    {if inspection.name.is_empty() { "(unnamed)".to_string() } else { inspection.name.clone() }}
+                </A>
+            </h3>
+            {inspection.description.clone().map(|description| view! {
+                <p class="mt-1 text-sm text-gray-600 dark:text-gray-400 whitespace-pre-wrap">{description}</p>
+            })}
+
+            <div class="mt-4">
+                {if list_labels.is_empty() {
+                    view! {
+                        <p class="text-sm text-green-700 dark:text-green-300">
+                            {format!("✓ No labels on the {} itself", kind.to_lowercase())}
+                        </p>
+                    }.into_view()
+                } else {
+                    view! {
+                        <p class="text-sm font-semibold mb-2">
+                            {format!("🏷 Labels on the {} itself", kind.to_lowercase())}
+                        </p>
+                        <div class="grid gap-2">
+                            {list_labels.into_iter().map(|label| view! { <LabelBadge label=label /> }).collect_view()}
+                        </div>
+                    }.into_view()
+                }}
+            </div>
+        </div>
+
+        <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6">
+            <h3 class="text-lg font-bold mb-1">{summary}</h3>
+            {notes.into_iter().map(|note| view! {
+                <p class="text-sm text-yellow-800 dark:text-yellow-200">{note}</p>
+            }).collect_view()}
+
+            <div class="mt-4 flex flex-wrap gap-2">
+                {if counts.is_empty() {
+                    view! {
+                        <span class="text-sm text-green-700 dark:text-green-300">"✓ No account labels on any member"</span>
+                    }.into_view()
+                } else {
+                    counts.into_iter().map(|(val, count)| {
+                        let chosen = val.clone();
+                        let is_selected = move || label_filter.with(|filter| filter.as_deref() == Some(chosen.as_str()));
+                        let toggle = val.clone();
+                        view! {
+                            <button
+                                type="button"
+                                class=move || format!(
+                                    "px-2 py-1 rounded text-xs font-mono transition-colors {}",
+                                    if is_selected() {
+                                        "bg-red-600 text-white"
+                                    } else {
+                                        "bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200 hover:bg-red-200 dark:hover:bg-red-800"
+                                    }
+                                )
+                                on:click=move |_| label_filter.update(|filter| {
+                                    *filter = if filter.as_deref() == Some(toggle.as_str()) {
+                                        None
+                                    } else {
+                                        Some(toggle.clone())
+                                    };
+                                })
+                            >
+                                {format!("{} × {}", val, count)}
+                            </button>
+                        }
+                    }).collect_view()
+                }}
+            </div>
+
+            <div class="mt-4 flex flex-wrap items-center gap-4 text-sm">
+                <input
+                    type="search"
+                    placeholder="Filter by handle or DID"
+                    class="px-3 py-1 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700"
+                    prop:value=move || search.get()
+                    on:input=move |ev| search.set(event_target_value(&ev))
+                />
+                <label class="flex items-center gap-2 cursor-pointer">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || labeled_only.get()
+                        on:change=move |ev| labeled_only.set(event_target_checked(&ev))
+                    />
+                    "Labeled members only"
+                </label>
+            </div>
+
+            {move || {
+                let shown = shown();
+                if shown.is_empty() {
+                    return view! {
+                        <p class="mt-4 text-sm text-gray-500 dark:text-gray-400">"No members match these filters."</p>
+                    }.into_view();
+                }
+                view! {
+                    <p class="mt-4 mb-2 text-xs text-gray-500 dark:text-gray-400">
+                        {format!("{} member(s)", shown.len())}
+                    </p>
+                    <ul class="divide-y divide-gray-100 dark:divide-gray-700">
+                        {shown.into_iter().map(|member| view! { <MemberRow member=member /> }).collect_view()}
+                    </ul>
+                }.into_view()
+            }}
+        </div>
+    }
+}
+
+#[component]
+fn MemberRow(member: ListMember) -> impl IntoView {
+    let name = member
+        .handle
+        .clone()
+        .unwrap_or_else(|| utils::shorten_did(&member.did));
+
+    view! {
+        <li class="py-2 text-sm">
+            <div class="flex flex-wrap items-center gap-2">
+                <A href=utils::check_path(&member.did) class="font-semibold text-blue-600 dark:text-blue-400 hover:underline break-all">
+                    {name}
+                </A>
+                {member.display_name.clone().map(|display_name| view! {
+                    <span class="text-gray-600 dark:text-gray-400">{display_name}</span>
+                })}
+                {member.handle.is_none().then(|| view! {
+                    <span class="text-xs text-gray-500 dark:text-gray-400" title="The AppView has no profile for this account">
+                        "no profile (taken down or deactivated?)"
+                    </span>
+                })}
+            </div>
+            <div class="mt-1 flex flex-wrap gap-1">
+                {member.labels.into_iter().map(|label| view! {
+                    <span
+                        class="px-2 py-1 rounded bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200 text-xs font-mono"
+                        title=format!("by {}", label.src)
+                    >
+                        {label.category().icon()} " " {label.val.clone()}
+                    </span>
+                }).collect_view()}
+            </div>
+        </li>
+    }
+}
@@ -2,18 +2,36 @@
 
 mod app;
 mod auth_panel;
+mod batch_check;
 pub mod bulk_analysis;
 mod empty_state;
 mod header;
 mod input_panel;
 mod label_badge;
+mod label_changes;
+mod label_history;
 mod label_viewer;
+mod list_view;
+mod network_scan;
+mod single_check;
+mod snapshot_history;
+mod thread_view;
+mod watchlist;
 
 pub use app::App;
 pub use auth_panel::AuthPanel;
+pub use batch_check::BatchCheck;
 pub use bulk_analysis::BulkAnalysis;
 pub use empty_state::EmptyState;
 pub use header::Header;
 pub use input_panel::InputPanel;
 pub use label_badge::LabelBadge;
+pub use label_changes::LabelChanges;
+pub use label_history::LabelHistory;
 pub use label_viewer::LabelViewer;
+pub use list_view::ListInspectionView;
+pub use network_scan::NetworkScanView;
+pub use single_check::SingleCheck;
+pub use snapshot_history::SnapshotHistory;
+pub use thread_view::ThreadView;
+pub use watchlist::{WatchAlerts, WatchlistView};
@@ -0,0 +1,474 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use leptos::*;
+use leptos_router::{use_navigate, use_params_map, A};
+use wasm_bindgen_futures::spawn_local;
+
+use crate::state::AppState;
+use crate::utils;
+use atproto_client::analysis::DEFAULT_MAX_CONNECTIONS;
+use atproto_client::{AbortHandle, AnalysisEvent, ConnectedAccount, Connection, NetworkScan};
+
+/// Accounts read per list offered in the options
+const LIMIT_CHOICES: [usize; 4] = [1000, DEFAULT_MAX_CONNECTIONS, 10_000, 50_000];
+
+/// Which connections the account list shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RelationFilter {
+    All,
+    Follows,
+    Followers,
+    Mutual,
+}
+
+impl RelationFilter {
+    const ALL: [RelationFilter; 4] = [
+        RelationFilter::All,
+        RelationFilter::Follows,
+        RelationFilter::Followers,
+        RelationFilter::Mutual,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            RelationFilter::All => "All",
+            RelationFilter::Follows => "Follows",
+            RelationFilter::Followers => "Followers",
+            RelationFilter::Mutual => "Mutual",
+        }
+    }
+
+    fn matches(&self, account: &ConnectedAccount) -> bool {
+        match self {
+            RelationFilter::All => true,
+            RelationFilter::Follows => account.followed,
+            RelationFilter::Followers => account.follower,
+            RelationFilter::Mutual => account.is_mutual(),
+        }
+    }
+}
+
+/// Network scan for `/network`: labeled accounts among an account's follows and followers
+#[component]
+pub fn NetworkScanView() -> impl IntoView {
+    let state = expect_context::<AppState>();
+    let follows = create_rw_signal(true);
+    let followers = create_rw_signal(true);
+    let max_accounts = create_rw_signal(DEFAULT_MAX_CONNECTIONS);
+    let include_expired = create_rw_signal(false);
+    let scan = create_rw_signal::<Option<NetworkScan>>(None);
+    let is_scanning = create_rw_signal(false);
+    let progress = create_rw_signal::<Option<String>>(None);
+    let progress_percent = create_rw_signal(0);
+    let abort = store_value(AbortHandle::new());
+    // Bumped per run so a run superseded by navigation stops updating the view
+    let run_id = store_value(0u64);
+    let navigate = use_navigate();
+    let params = use_params_map();
+    let routed_handle = create_memo(move |_| {
+        params.with(|p| {
+            p.get("handle")
+                .map(|handle| handle.trim().to_string())
+                .filter(|handle| !handle.is_empty())
+        })
+    });
+
+    let run_scan = move |input: String| {
+        let connections: Vec<Connection> = [
+            (follows.get_untracked(), Connection::Follows),
+            (followers.get_untracked(), Connection::Followers),
+        ]
+        .into_iter()
+        .filter_map(|(selected, connection)| selected.then_some(connection))
+        .collect();
+
+        state.error.set(None);
+        scan.set(None);
+        is_scanning.set(true);
+        progress.set(Some("Starting network scan...".to_string()));
+        progress_percent.set(0);
+
+        abort.with_value(AbortHandle::abort);
+        let handle = AbortHandle::new();
+        abort.set_value(handle.clone());
+        run_id.update_value(|id| *id += 1);
+        let id = run_id.get_value();
+        let is_current = move || run_id.try_get_value() == Some(id);
+
+        spawn_local(async move {
+            let on_event = |event: AnalysisEvent| {
+                if let AnalysisEvent::Progress { message, percent } = event {
+                    if is_current() {
+                        progress.set(Some(message));
+                        progress_percent.set(percent);
+                    }
+                }
+            };
+            let result = utils::scan_network(
+                &input,
+                state.auth_token.get_untracked(),
+                &connections,
+                max_accounts.get_untracked(),
+                include_expired.get_untracked(),
+                handle,
+                on_event,
+            )
+            .await;
+            if !is_current() {
+                return;
+            }
+            match result {
+                Ok(found) => scan.set(Some(found)),
+                Err(e) => state.error.set(Some(format!("Error: {}", e))),
+            }
+            progress.set(None);
+            is_scanning.set(false);
+        });
+    };
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+
+        let input = state.subject_input.get_untracked();
+        if input.trim().is_empty() {
+            state
+                .error
+                .set(Some("Please enter a Bluesky handle".to_string()));
+            return;
+        }
+        if !follows.get_untracked() && !followers.get_untracked() {
+            state
+                .error
+                .set(Some("Select follows, followers or both".to_string()));
+            return;
+        }
+
+        // The route runs the scan, so the URL can be shared and revisited
+        let path = utils::network_path(&input);
+        let current = routed_handle.get_untracked();
+        if current.is_some_and(|handle| utils::network_path(&handle) == path) {
+            run_scan(input);
+        } else {
+            navigate(&path, Default::default());
+        }
+    };
+
+    create_effect(move |_| {
+        if let Some(handle) = routed_handle.get() {
+            state.subject_input.set(handle.clone());
+            run_scan(handle);
+        }
+    });
+
+    on_cleanup(move || {
+        abort.try_with_value(AbortHandle::abort);
+    });
+
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6 mb-6">
+            <h2 class="text-xl font-bold mb-1">"🕸 Network Scan"</h2>
+            <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                "Check the accounts someone follows and is followed by for account-level labels. Follows come from the account's own follow records, so taken down accounts are included."
+            </p>
+
+            <form on:submit=on_submit>
+                <input
+                    type="text"
+                    placeholder="Enter Bluesky handle or profile link (e.g., alice.bsky.social)"
+                    class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent bg-white dark:bg-gray-700"
+                    prop:value=move || state.subject_input.get()
+                    on:input=move |ev| state.subject_input.set(event_target_value(&ev))
+                />
+
+                <div class="mt-3 flex flex-wrap items-center gap-4 text-sm">
+                    <label class="flex items-center gap-2 cursor-pointer">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || follows.get()
+                            on:change=move |ev| follows.set(event_target_checked(&ev))
+                        />
+                        "Follows"
+                    </label>
+                    <label class="flex items-center gap-2 cursor-pointer">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || followers.get()
+                            on:change=move |ev| followers.set(event_target_checked(&ev))
+                        />
+                        "Followers"
+                    </label>
+                    <label class="flex items-center gap-2">
+                        "Up to"
+                        <select
+                            class="px-2 py-1 border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700"
+                            on:change=move |ev| {
+                                if let Ok(max) = event_target_value(&ev).parse() {
+                                    max_accounts.set(max);
+                                }
+                            }
+                        >
+                            {LIMIT_CHOICES.into_iter().map(|max| view! {
+                                <option value=max.to_string() selected=move || max_accounts.get() == max>
+                                    {max.to_string()}
+                                </option>
+                            }).collect_view()}
+                        </select>
+                        "per list"
+                    </label>
+                    <label class="flex items-center gap-2 cursor-pointer">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || include_expired.get()
+                            on:change=move |ev| include_expired.set(event_target_checked(&ev))
+                        />
+                        "Count expired labels"
+                    </label>
+                </div>
+
+                <button
+                    type="submit"
+                    disabled=move || is_scanning.get()
+                    class="mt-4 w-full bg-blue-600 hover:bg-blue-700 disabled:bg-gray-400 text-white font-semibold py-2 px-4 rounded-lg transition-colors"
+                >
+                    {move || if is_scanning.get() { "🔄 Scanning..." } else { "🕸 Scan Network" }}
+                </button>
+            </form>
+
+            {move || state.error.get().map(|err| view! {
+                <div class="mt-4 p-4 bg-red-100 dark:bg-red-900 border border-red-300 dark:border-red-700 rounded-lg">
+                    <p class="text-red-800 dark:text-red-200 text-sm">{err}</p>
+                </div>
+            })}
+
+            {move || progress.get().map(|msg| view! {
+                <div class="mt-4 p-4 bg-blue-50 dark:bg-blue-900 rounded-lg border border-blue-200 dark:border-blue-700">
+                    <div class="flex items-center justify-between mb-2">
+                        <p class="text-sm font-semibold text-blue-800 dark:text-blue-200">{msg}</p>
+                        <span class="text-sm font-bold text-blue-600 dark:text-blue-400">
+                            {move || progress_percent.get()}"%"
+                        </span>
+                    </div>
+                    <div class="w-full bg-blue-200 dark:bg-blue-800 rounded-full h-2.5 overflow-hidden">
+                        <div
+                            class="bg-blue-600 dark:bg-blue-400 h-2.5 rounded-full transition-all duration-300 ease-out"
+                            style=move || format!("width: {}%", progress_percent.get())
+                        />
+                    </div>
+                    <button
+                        type="button"
+                        on:click=move |_| abort.with_value(AbortHandle::abort)
+                        class="mt-3 px-3 py-1 text-sm font-semibold bg-white dark:bg-gray-700 border border-blue-300 dark:border-blue-600 text-blue-800 dark:text-blue-200 rounded-lg hover:bg-blue-100 dark:hover:bg-blue-800 transition-colors"
+                    >
+                        "⏹ Cancel"
+                    </button>
+                </div>
+            })}
+        </div>
+
+        {move || scan.get().map(|found| view! { <NetworkResults scan=found /> })}
+    }
+}
+
+/// Label summary and the filterable list of connected accounts
+#[component]
+fn NetworkResults(scan: NetworkScan) -> impl IntoView {
+    let label_filter = create_rw_signal::<Option<String>>(None);
+    let relation = create_rw_signal(RelationFilter::All);
+    let labeled_only = create_rw_signal(true);
+    let search = create_rw_signal(String::new());
+
+    let labeled = scan.labeled().count();
+    let counts = scan.label_counts();
+    let summary = format!(
+        "{} follows • {} followers • {} labeled account{}",
+        scan.follows,
+        scan.followers,
+        labeled,
+        if labeled == 1 { "" } else { "s" }
+    );
+    let mut notes = Vec::new();
+    if scan.truncated {
+        notes.push("⚠️ A list was longer than the limit; the rest were not checked.".to_string());
+    }
+    if let Some(reason) = &scan.interruption {
+        notes.push(format!("⏸ The scan stopped early: {}", reason));
+    }
+    if scan.unchecked > 0 {
+        notes.push(format!(
+            "⚠️ {} account(s) could not be checked.",
+            scan.unchecked
+        ));
+    }
+    if scan.expired_labels > 0 {
+        notes.push(format!(
+            "⌛ {} expired label(s) not counted.",
+            scan.expired_labels
+        ));
+    }
+    let accounts = store_value(scan.accounts);
+
+    let shown = move || {
+        let search = search.get().trim().to_lowercase();
+        let label_filter = label_filter.get();
+        let relation = relation.get();
+        let labeled_only = labeled_only.get();
+        accounts.with_value(|accounts| {
+            accounts
+                .iter()
+                .filter(|account| !labeled_only || !account.labels.is_empty())
+                .filter(|account| relation.matches(account))
+                .filter(|account| {
+                    label_filter
+                        .as_ref()
+                        .is_none_or(|val| account.labels.iter().any(|label| &label.val == val))
+                })
+                .filter(|account| {
+                    search.is_empty()
+                        || account.did.contains(&search)
+                        || account
+                            .handle
+                            .as_ref()
+                            .is_some_and(|handle| handle.contains(&search))
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6">
+            <h3 class="text-lg font-bold mb-1">{summary}</h3>
+            {notes.into_iter().map(|note| view! {
+                <p class="text-sm text-yellow-800 dark:text-yellow-200">{note}</p>
+            }).collect_view()}
+
+            <div class="mt-4 flex flex-wrap gap-2">
+                {if counts.is_empty() {
+                    view! {
+                        <span class="text-sm text-green-700 dark:text-green-300">"✓ No account labels in this network"</span>
+                    }.into_view()
+                } else {
+                    counts.into_iter().map(|(val, count)| {
+                        let chosen = val.clone();
+                        let is_selected = move || label_filter.with(|filter| filter.as_deref() == Some(chosen.as_str()));
+                        let toggle = val.clone();
+                        view! {
+                            <button
+                                type="button"
+                                class=move || format!(
+                                    "px-2 py-1 rounded text-xs font-mono transition-colors {}",
+                                    if is_selected() {
+                                        "bg-red-600 text-white"
+                                    } else {
+                                        "bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200 hover:bg-red-200 dark:hover:bg-red-800"
+                                    }
+                                )
+                                on:click=move |_| label_filter.update(|filter| {
+                                    *filter = if filter.as_deref() == Some(toggle.as_str()) {
+                                        None
+                                    } else {
+                                        Some(toggle.clone())
+                                    };
+                                })
+                            >
+                                {format!("{} × {}", val, count)}
+                            </button>
+                        }
+                    }).collect_view()
+                }}
+            </div>
+
+            <div class="mt-4 flex flex-wrap items-center gap-4 text-sm">
+                <input
+                    type="search"
+                    placeholder="Filter by handle or DID"
+                    class="px-3 py-1 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700"
+                    prop:value=move || search.get()
+                    on:input=move |ev| search.set(event_target_value(&ev))
+                />
+                <select
+                    class="px-2 py-1 border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700"
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(choice) = RelationFilter::ALL.into_iter().find(|choice| choice.name() == value) {
+                            relation.set(choice);
+                        }
+                    }
+                >
+                    {RelationFilter::ALL.into_iter().map(|choice| view! {
+                        <option value=choice.name() selected=move || relation.get() == choice>
+                            {choice.name()}
+                        </option>
+                    }).collect_view()}
+                </select>
+                <label class="flex items-center gap-2 cursor-pointer">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || labeled_only.get()
+                        on:change=move |ev| labeled_only.set(event_target_checked(&ev))
+                    />
+                    "Labeled accounts only"
+                </label>
+            </div>
+
+            {move || {
+                let shown = shown();
+                if shown.is_empty() {
+                    return view! {
+                        <p class="mt-4 text-sm text-gray-500 dark:text-gray-400">"No accounts match these filters."</p>
+                    }.into_view();
+                }
+                view! {
+                    <p class="mt-4 mb-2 text-xs text-gray-500 dark:text-gray-400">
+                        {format!("{} account(s)", shown.len())}
+                    </p>
+                    <ul class="divide-y divide-gray-100 dark:divide-gray-700">
+                        {shown.into_iter().map(|account| view! { <AccountRow account=account /> }).collect_view()}
+                    </ul>
+                }.into_view()
+            }}
+        </div>
+    }
+}
+
+#[component]
+fn AccountRow(account: ConnectedAccount) -> impl IntoView {
+    let relation = match (account.followed, account.follower) {
+        (true, true) => "⇄ Mutual",
+        (true, false) => "→ Followed",
+        _ => "← Follower",
+    };
+    let name = account
+        .handle
+        .clone()
+        .unwrap_or_else(|| utils::shorten_did(&account.did));
+
+    view! {
+        <li class="py-2 text-sm">
+            <div class="flex flex-wrap items-center gap-2">
+                <A href=utils::check_path(&account.did) class="font-semibold text-blue-600 dark:text-blue-400 hover:underline break-all">
+                    {name}
+                </A>
+                {account.display_name.clone().map(|display_name| view! {
+                    <span class="text-gray-600 dark:text-gray-400">{display_name}</span>
+                })}
+                <span class="px-2 py-0.5 rounded text-xs bg-gray-100 dark:bg-gray-700">{relation}</span>
+                {account.handle.is_none().then(|| view! {
+                    <span class="text-xs text-gray-500 dark:text-gray-400" title="The AppView has no profile for this account">
+                        "no profile (taken down or deactivated?)"
+                    </span>
+                })}
+            </div>
+            <div class="mt-1 flex flex-wrap gap-1">
+                {account.labels.into_iter().map(|label| view! {
+                    <span
+                        class="px-2 py-1 rounded bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200 text-xs font-mono"
+                        title=format!("by {}", label.src)
+                    >
+                        {label.category().icon()} " " {label.val.clone()}
+                    </span>
+                }).collect_view()}
+            </div>
+        </li>
+    }
+}
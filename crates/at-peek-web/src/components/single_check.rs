@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use atproto_client::{Snapshot, SnapshotKind};
+use leptos::*;
+use leptos_router::{use_params_map, ParamsMap, A};
+use wasm_bindgen_futures::spawn_local;
+
+use super::{InputPanel, LabelHistory, LabelViewer, SnapshotHistory};
+use crate::state::AppState;
+use crate::{snapshots, utils};
+
+/// Single Check page for `/check`, `/check/:subject` and `/post/:did/:rkey`
+///
+/// The subject in the URL is checked on load and whenever navigation changes it.
+#[component]
+pub fn SingleCheck() -> impl IntoView {
+    let state = expect_context::<AppState>();
+    let params = use_params_map();
+    let subject = create_memo(move |_| params.with(route_subject));
+
+    create_effect(move |_| match subject.get() {
+        Some(subject) => {
+            state.subject_input.set(subject.clone());
+            run_check(state, subject);
+        }
+        None => {
+            // A check still running for the previous route must not fill the page
+            state.check_generation.update(|generation| *generation += 1);
+            state.is_loading.set(false);
+            state.labels.set(None);
+            state.checked_subject.set(None);
+            state.error.set(None);
+        }
+    });
+
+    // Posts can be followed up with their whole thread, lists with every member
+    let follow_up = move || {
+        let (path, text) = state.checked_subject.with(|subject| {
+            let subject = subject.as_deref()?;
+            utils::thread_path(subject)
+                .map(|path| (path, "🧵 Check the whole thread"))
+                .or_else(|| utils::list_path(subject).map(|path| (path, "📃 Check every member")))
+        })?;
+        Some(view! {
+            <div class="mb-4 text-right">
+                <A href=path class="text-sm font-semibold text-blue-600 dark:text-blue-400 hover:underline">
+                    {text}
+                </A>
+            </div>
+        })
+    };
+
+    view! {
+        <InputPanel />
+        {follow_up}
+        <LabelViewer />
+        <LabelHistory />
+        <SnapshotHistory subject=state.checked_subject kind=SnapshotKind::Check />
+    }
+}
+
+/// Subject named by the route parameters, if any
+pub(super) fn route_subject(params: &ParamsMap) -> Option<String> {
+    match (params.get("did"), params.get("rkey")) {
+        (Some(did), Some(rkey)) => Some(format!("at://{}/app.bsky.feed.post/{}", did, rkey)),
+        _ => params
+            .get("subject")
+            .map(|subject| subject.trim().to_string())
+            .filter(|subject| !subject.is_empty()),
+    }
+}
+
+/// Fetch the labels on `input` into the shared state
+///
+/// Only the latest check's result is shown: one that finishes after another check has
+/// started is dropped, so a slow earlier lookup can't overwrite a newer subject's labels.
+pub(super) fn run_check(state: AppState, input: String) {
+    state.check_generation.update(|generation| *generation += 1);
+    let generation = state.check_generation.get_untracked();
+    state.error.set(None);
+    state.is_loading.set(true);
+
+    spawn_local(async move {
+        let auth_token = state.auth_token.get_untracked();
+        let result = utils::fetch_labels(&input, auth_token).await;
+        if state.check_generation.get_untracked() != generation {
+            return;
+        }
+        match result {
+            Ok((subject, collection)) => {
+                snapshots::keep(
+                    state,
+                    Snapshot::from_check(&subject, &collection, chrono::Utc::now()),
+                );
+                state.checked_subject.set(Some(subject));
+                state.labels.set(Some(collection));
+                state.error.set(None);
+            }
+            Err(e) => {
+                state.error.set(Some(format!("Error: {}", e)));
+                state.labels.set(None);
+                state.checked_subject.set(None);
+            }
+        }
+        state.is_loading.set(false);
+    });
+}
@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use atproto_client::{SnapshotDiff, SnapshotKind};
+use leptos::*;
+use leptos_router::A;
+use wasm_bindgen_futures::spawn_local;
+
+use super::LabelChanges;
+use crate::state::AppState;
+use crate::{snapshots, utils};
+
+/// Snapshots of `subject` saved in this browser, and what changed between any two
+#[component]
+pub fn SnapshotHistory(
+    #[prop(into)] subject: Signal<Option<String>>,
+    kind: SnapshotKind,
+) -> impl IntoView {
+    let state = expect_context::<AppState>();
+    let saved = create_local_resource(
+        move || (subject.get(), state.snapshots_saved.get()),
+        move |(subject, _)| async move {
+            match subject {
+                Some(subject) => snapshots::list(&subject, kind).await,
+                None => Ok(Vec::new()),
+            }
+        },
+    );
+    // Ids of the snapshots being compared; unset means the two most recent
+    let older = create_rw_signal::<Option<f64>>(None);
+    let newer = create_rw_signal::<Option<f64>>(None);
+    create_effect(move |_| {
+        subject.track();
+        older.set(None);
+        newer.set(None);
+    });
+
+    let on_clear = move |_| {
+        let ids: Vec<f64> = untrack(|| {
+            saved.with(|saved| match saved {
+                Some(Ok(list)) => list.iter().map(|stored| stored.id).collect(),
+                _ => Vec::new(),
+            })
+        });
+        spawn_local(async move {
+            match snapshots::delete(&ids).await {
+                Ok(()) => state.snapshots_saved.update(|saved| *saved += 1),
+                Err(e) => state.error.set(Some(e)),
+            }
+        });
+    };
+
+    move || {
+        let list = match saved.get()? {
+            Ok(list) => list,
+            Err(e) => {
+                return Some(
+                    view! {
+                        <p class="mt-6 text-sm text-yellow-800 dark:text-yellow-200">
+                            {format!("⚠️ Snapshot history unavailable: {}", e)}
+                        </p>
+                    }
+                    .into_view(),
+                );
+            }
+        };
+        if list.is_empty() {
+            return None;
+        }
+
+        let count = list.len();
+        let position =
+            |id: Option<f64>| id.and_then(|id| list.iter().position(|stored| stored.id == id));
+        let newer_index = position(newer.get()).unwrap_or(count - 1);
+        let older_index = position(older.get()).unwrap_or(count.saturating_sub(2));
+        let diff = (older_index != newer_index).then(|| {
+            let (from, to) = (older_index.min(newer_index), older_index.max(newer_index));
+            SnapshotDiff::between(&list[from].snapshot, &list[to].snapshot)
+        });
+
+        let options =
+            move |selected: usize| {
+                list.iter().enumerate().rev().map(|(index, stored)| view! {
+                <option value=stored.id.to_string() selected=index == selected>
+                    {format!(
+                        "{} • {} label{}",
+                        utils::format_timestamp(&stored.snapshot.taken_at.to_rfc3339()),
+                        stored.snapshot.active_labels().count(),
+                        if stored.snapshot.active_labels().count() == 1 { "" } else { "s" }
+                    )}
+                </option>
+            }).collect_view()
+            };
+        let select_class = "px-2 py-1 border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700";
+
+        Some(view! {
+            <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6 mt-6">
+                <div class="flex flex-wrap items-center justify-between gap-2 mb-1">
+                    <h2 class="text-xl font-bold">"🗂 Snapshots"</h2>
+                    <button
+                        type="button"
+                        on:click=on_clear
+                        class="text-sm text-gray-500 dark:text-gray-400 hover:text-red-600 hover:underline"
+                    >
+                        "Clear history"
+                    </button>
+                </div>
+                <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                    {format!(
+                        "{} {} result{} saved in this browser",
+                        count,
+                        kind.name(),
+                        if count == 1 { "" } else { "s" }
+                    )}
+                </p>
+
+                {match diff {
+                    None => view! {
+                        <p class="text-sm text-gray-500 dark:text-gray-400">
+                            "Run this again later to compare the results with this one."
+                        </p>
+                    }.into_view(),
+                    Some(diff) => view! {
+                        <div class="flex flex-wrap items-center gap-2 text-sm mb-4">
+                            "Compare"
+                            <select class=select_class on:change=move |ev| older.set(event_target_value(&ev).parse().ok())>
+                                {options.clone()(older_index)}
+                            </select>
+                            "with"
+                            <select class=select_class on:change=move |ev| newer.set(event_target_value(&ev).parse().ok())>
+                                {options(newer_index)}
+                            </select>
+                        </div>
+                        <SnapshotChanges diff=diff kind=kind />
+                    }.into_view(),
+                }}
+            </div>
+        }.into_view())
+    }
+}
+
+#[component]
+fn SnapshotChanges(diff: SnapshotDiff, kind: SnapshotKind) -> impl IntoView {
+    if diff.is_empty() {
+        return view! {
+            <p class="text-sm text-green-700 dark:text-green-300">"✓ No changes between these snapshots"</p>
+        }
+        .into_view();
+    }
+
+    let is_bulk = kind == SnapshotKind::Bulk;
+    view! {
+        <div class="space-y-4">
+            <div>
+                <h3 class="font-semibold mb-1">
+                    {if is_bulk { "Account labels" } else { "Labels" }}
+                </h3>
+                <LabelChanges changes=diff.labels />
+            </div>
+            {is_bulk.then(|| view! {
+                <div>
+                    <h3 class="font-semibold mb-1">"Labels on records checked both times"</h3>
+                    <LabelChanges changes=diff.record_labels />
+                </div>
+                {if diff.scope_changed {
+                    view! {
+                        <p class="text-sm text-gray-500 dark:text-gray-400">
+                            "Scope changed, not comparable: these runs covered different records, so newly labeled and deleted records aren't shown."
+                        </p>
+                    }.into_view()
+                } else {
+                    view! {
+                        <RecordList
+                            title=format!("🏷️ Newly labeled records ({})", diff.newly_labeled.len())
+                            uris=diff.newly_labeled
+                        />
+                        <RecordList
+                            title=format!("🗑 Deleted records ({})", diff.deleted.len())
+                            uris=diff.deleted
+                        />
+                    }.into_view()
+                }}
+            })}
+        </div>
+    }
+    .into_view()
+}
+
+#[component]
+fn RecordList(title: String, uris: Vec<String>) -> impl IntoView {
+    (!uris.is_empty()).then(|| view! {
+        <div>
+            <h3 class="font-semibold mb-1">{title}</h3>
+            <ul class="space-y-1 text-xs">
+                {uris.into_iter().map(|uri| view! {
+                    <li>
+                        <A href=utils::check_path(&uri) class="text-blue-600 dark:text-blue-400 hover:underline break-all">
+                            {uri.clone()}
+                        </A>
+                    </li>
+                }).collect_view()}
+            </ul>
+        </div>
+    })
+}
@@ -0,0 +1,346 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use leptos::*;
+use leptos_router::{use_navigate, use_params_map, A};
+use wasm_bindgen_futures::spawn_local;
+
+use super::single_check::route_subject;
+use crate::state::AppState;
+use crate::utils;
+use atproto_client::{AbortHandle, AnalysisEvent, Label, PostThread, ThreadPost};
+
+/// Deepest indent in the thread tree; deeper replies line up with it
+const MAX_INDENT: usize = 12;
+
+/// Thread view for `/thread` and `/thread/:did/:rkey`: labels across a whole thread
+#[component]
+pub fn ThreadView() -> impl IntoView {
+    let state = expect_context::<AppState>();
+    let include_expired = create_rw_signal(false);
+    let thread = create_rw_signal::<Option<PostThread>>(None);
+    let is_running = create_rw_signal(false);
+    let progress = create_rw_signal::<Option<String>>(None);
+    let progress_percent = create_rw_signal(0);
+    let abort = store_value(AbortHandle::new());
+    // Bumped per run so a run superseded by navigation stops updating the view
+    let run_id = store_value(0u64);
+    let navigate = use_navigate();
+    let params = use_params_map();
+    let routed_post = create_memo(move |_| params.with(route_subject));
+
+    let run_analysis = move |input: String| {
+        state.error.set(None);
+        thread.set(None);
+        is_running.set(true);
+        progress.set(Some("Starting thread analysis...".to_string()));
+        progress_percent.set(0);
+
+        abort.with_value(AbortHandle::abort);
+        let handle = AbortHandle::new();
+        abort.set_value(handle.clone());
+        run_id.update_value(|id| *id += 1);
+        let id = run_id.get_value();
+        let is_current = move || run_id.try_get_value() == Some(id);
+
+        spawn_local(async move {
+            let on_event = |event: AnalysisEvent| {
+                if let AnalysisEvent::Progress { message, percent } = event {
+                    if is_current() {
+                        progress.set(Some(message));
+                        progress_percent.set(percent);
+                    }
+                }
+            };
+            let result = utils::analyze_thread(
+                &input,
+                state.auth_token.get_untracked(),
+                include_expired.get_untracked(),
+                handle,
+                on_event,
+            )
+            .await;
+            if !is_current() {
+                return;
+            }
+            match result {
+                Ok(found) => thread.set(Some(found)),
+                Err(e) => state.error.set(Some(format!("Error: {}", e))),
+            }
+            progress.set(None);
+            is_running.set(false);
+        });
+    };
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+
+        let input = state.subject_input.get_untracked();
+        let Some(path) = utils::thread_path(&input) else {
+            state.error.set(Some(
+                "Please enter a post AT-URI or Bluesky post link".to_string(),
+            ));
+            return;
+        };
+
+        // The route runs the analysis, so the URL can be shared and revisited
+        let current = routed_post.get_untracked();
+        if current.is_some_and(|post| utils::thread_path(&post).as_ref() == Some(&path)) {
+            run_analysis(input);
+        } else {
+            navigate(&path, Default::default());
+        }
+    };
+
+    create_effect(move |_| {
+        if let Some(post) = routed_post.get() {
+            state.subject_input.set(post.clone());
+            run_analysis(post);
+        }
+    });
+
+    on_cleanup(move || {
+        abort.try_with_value(AbortHandle::abort);
+    });
+
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6 mb-6">
+            <h2 class="text-xl font-bold mb-1">"🧵 Thread Analysis"</h2>
+            <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                "Check every post in a thread, parents and replies, and every account that posted in it for labels."
+            </p>
+
+            <form on:submit=on_submit>
+                <input
+                    type="text"
+                    placeholder="Enter a post link or AT-URI (e.g., https://bsky.app/profile/alice.bsky.social/post/3k...)"
+                    class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent bg-white dark:bg-gray-700"
+                    prop:value=move || state.subject_input.get()
+                    on:input=move |ev| state.subject_input.set(event_target_value(&ev))
+                />
+
+                <div class="mt-3 flex flex-wrap items-center gap-4 text-sm">
+                    <label class="flex items-center gap-2 cursor-pointer">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || include_expired.get()
+                            on:change=move |ev| include_expired.set(event_target_checked(&ev))
+                        />
+                        "Count expired labels"
+                    </label>
+                </div>
+
+                <button
+                    type="submit"
+                    disabled=move || is_running.get()
+                    class="mt-4 w-full bg-blue-600 hover:bg-blue-700 disabled:bg-gray-400 text-white font-semibold py-2 px-4 rounded-lg transition-colors"
+                >
+                    {move || if is_running.get() { "🔄 Analyzing..." } else { "🧵 Analyze Thread" }}
+                </button>
+            </form>
+
+            {move || state.error.get().map(|err| view! {
+                <div class="mt-4 p-4 bg-red-100 dark:bg-red-900 border border-red-300 dark:border-red-700 rounded-lg">
+                    <p class="text-red-800 dark:text-red-200 text-sm">{err}</p>
+                </div>
+            })}
+
+            {move || progress.get().map(|msg| view! {
+                <div class="mt-4 p-4 bg-blue-50 dark:bg-blue-900 rounded-lg border border-blue-200 dark:border-blue-700">
+                    <div class="flex items-center justify-between mb-2">
+                        <p class="text-sm font-semibold text-blue-800 dark:text-blue-200">{msg}</p>
+                        <span class="text-sm font-bold text-blue-600 dark:text-blue-400">
+                            {move || progress_percent.get()}"%"
+                        </span>
+                    </div>
+                    <div class="w-full bg-blue-200 dark:bg-blue-800 rounded-full h-2.5 overflow-hidden">
+                        <div
+                            class="bg-blue-600 dark:bg-blue-400 h-2.5 rounded-full transition-all duration-300 ease-out"
+                            style=move || format!("width: {}%", progress_percent.get())
+                        />
+                    </div>
+                    <button
+                        type="button"
+                        on:click=move |_| abort.with_value(AbortHandle::abort)
+                        class="mt-3 px-3 py-1 text-sm font-semibold bg-white dark:bg-gray-700 border border-blue-300 dark:border-blue-600 text-blue-800 dark:text-blue-200 rounded-lg hover:bg-blue-100 dark:hover:bg-blue-800 transition-colors"
+                    >
+                        "⏹ Cancel"
+                    </button>
+                </div>
+            })}
+        </div>
+
+        {move || thread.get().map(|found| view! { <ThreadResults thread=found /> })}
+    }
+}
+
+/// Label summary and the thread tree with labels inline
+#[component]
+fn ThreadResults(thread: PostThread) -> impl IntoView {
+    let labeled_only = create_rw_signal(false);
+
+    let labeled = thread.labeled_posts().count();
+    let (replies, from_labeled) = thread.labeled_replies();
+    let counts = thread.label_counts();
+    let summary = format!(
+        "{} posts • {} labeled or by labeled accounts • {} of {} replies from labeled accounts",
+        thread.posts.len(),
+        labeled,
+        from_labeled,
+        replies
+    );
+    let mut notes = Vec::new();
+    if let Some(reason) = &thread.interruption {
+        notes.push(format!("⏸ The analysis stopped early: {}", reason));
+    }
+    if thread.unchecked > 0 {
+        notes.push(format!(
+            "⚠️ {} post(s) or account(s) could not be checked.",
+            thread.unchecked
+        ));
+    }
+    if thread.expired_labels > 0 {
+        notes.push(format!(
+            "⌛ {} expired label(s) not counted.",
+            thread.expired_labels
+        ));
+    }
+    let rows: Vec<(ThreadPost, Vec<Label>, bool)> = thread
+        .posts
+        .iter()
+        .map(|post| {
+            (
+                post.clone(),
+                thread.labels_on_author(post).to_vec(),
+                post.uri == thread.uri,
+            )
+        })
+        .collect();
+    let rows = store_value(rows);
+
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6">
+            <h3 class="text-lg font-bold mb-1">{summary}</h3>
+            {notes.into_iter().map(|note| view! {
+                <p class="text-sm text-yellow-800 dark:text-yellow-200">{note}</p>
+            }).collect_view()}
+
+            <div class="mt-4 flex flex-wrap gap-2">
+                {if counts.is_empty() {
+                    view! {
+                        <span class="text-sm text-green-700 dark:text-green-300">"✓ No labels in this thread"</span>
+                    }.into_view()
+                } else {
+                    counts.into_iter().map(|(val, count)| view! {
+                        <span class="px-2 py-1 rounded text-xs font-mono bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200">
+                            {format!("{} × {}", val, count)}
+                        </span>
+                    }).collect_view()
+                }}
+            </div>
+
+            <label class="mt-4 flex items-center gap-2 text-sm cursor-pointer">
+                <input
+                    type="checkbox"
+                    prop:checked=move || labeled_only.get()
+                    on:change=move |ev| labeled_only.set(event_target_checked(&ev))
+                />
+                "Labeled posts and accounts only"
+            </label>
+
+            <ul class="mt-4 space-y-2">
+                {move || {
+                    let labeled_only = labeled_only.get();
+                    rows.with_value(|rows| {
+                        rows.iter()
+                            .filter(|(post, author_labels, _)| {
+                                !labeled_only || !post.labels.is_empty() || !author_labels.is_empty()
+                            })
+                            .map(|(post, author_labels, is_anchor)| view! {
+                                <ThreadPostRow
+                                    post=post.clone()
+                                    author_labels=author_labels.clone()
+                                    is_anchor=*is_anchor
+                                />
+                            })
+                            .collect_view()
+                    })
+                }}
+            </ul>
+        </div>
+    }
+}
+
+#[component]
+fn ThreadPostRow(post: ThreadPost, author_labels: Vec<Label>, is_anchor: bool) -> impl IntoView {
+    let labeled = !post.labels.is_empty() || !author_labels.is_empty();
+    let border = if is_anchor {
+        "border-blue-500"
+    } else if labeled {
+        "border-red-400"
+    } else {
+        "border-gray-200 dark:border-gray-700"
+    };
+    let name = post
+        .author
+        .as_ref()
+        .map(|author| format!("@{}", author.handle))
+        .unwrap_or_else(|| utils::shorten_did(&post.did));
+    let web_link = post.uri.strip_prefix("at://").map(|rest| {
+        format!(
+            "https://bsky.app/profile/{}",
+            rest.replace("/app.bsky.feed.post/", "/post/")
+        )
+    });
+
+    view! {
+        <li
+            class=format!("pl-3 border-l-4 {} text-sm", border)
+            style=format!("margin-left: {}rem", post.depth.min(MAX_INDENT))
+        >
+            <div class="flex flex-wrap items-center gap-2">
+                <A href=utils::check_path(&post.did) class="font-semibold text-blue-600 dark:text-blue-400 hover:underline break-all">
+                    {name}
+                </A>
+                {post.author.as_ref().and_then(|author| author.display_name.clone()).map(|display_name| view! {
+                    <span class="text-gray-600 dark:text-gray-400">{display_name}</span>
+                })}
+                <span class="text-xs text-gray-500 dark:text-gray-400">
+                    {utils::format_timestamp(&post.created_at)}
+                </span>
+                {is_anchor.then(|| view! {
+                    <span class="px-2 py-0.5 rounded text-xs bg-blue-100 dark:bg-blue-900 text-blue-800 dark:text-blue-200">"checked post"</span>
+                })}
+                <A href=utils::check_path(&post.uri) class="text-xs text-blue-600 dark:text-blue-400 hover:underline">"labels"</A>
+                {web_link.filter(|_| post.unavailable.is_none()).map(|href| view! {
+                    <a href=href target="_blank" class="text-xs text-blue-600 dark:text-blue-400 hover:underline">"bsky.app ↗"</a>
+                })}
+            </div>
+            {match &post.unavailable {
+                Some(reason) => view! {
+                    <p class="mt-1 italic text-gray-500 dark:text-gray-400">{format!("Post {}", reason)}</p>
+                }.into_view(),
+                None => view! {
+                    <p class="mt-1 whitespace-pre-wrap break-words text-gray-800 dark:text-gray-200">{post.text.clone()}</p>
+                }.into_view(),
+            }}
+            <div class="mt-1 flex flex-wrap gap-1">
+                {post.labels.iter().map(|label| view! {
+                    <span
+                        class="px-2 py-1 rounded bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200 text-xs font-mono"
+                        title=format!("Post label by {}", label.src)
+                    >
+                        {label.category().icon()} " " {label.val.clone()}
+                    </span>
+                }).collect_view()}
+                {author_labels.into_iter().map(|label| view! {
+                    <span
+                        class="px-2 py-1 rounded bg-orange-100 dark:bg-orange-900 text-orange-800 dark:text-orange-200 text-xs font-mono"
+                        title=format!("Account label by {}", label.src)
+                    >
+                        "👤 " {label.val.clone()}
+                    </span>
+                }).collect_view()}
+            </div>
+        </li>
+    }
+}
@@ -0,0 +1,338 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use leptos::*;
+use leptos_router::A;
+use wasm_bindgen_futures::spawn_local;
+
+use super::LabelChanges;
+use crate::state::AppState;
+use crate::utils;
+use crate::watchlist::{self, INTERVAL_CHOICES, MAX_SNAPSHOTS};
+
+/// Watchlist page for `/watchlist`: watched subjects, their current labels and history
+#[component]
+pub fn WatchlistView() -> impl IntoView {
+    let state = expect_context::<AppState>();
+    let input = create_rw_signal(String::new());
+    state.error.set(None);
+
+    let on_add = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        match watchlist::watch(state, &input.get_untracked()) {
+            Ok(_) => {
+                state.error.set(None);
+                input.set(String::new());
+            }
+            Err(e) => state.error.set(Some(e)),
+        }
+    };
+
+    let on_check_all = move |_| {
+        let subjects = state.watchlist.with_untracked(|list| {
+            list.entries
+                .iter()
+                .map(|entry| entry.subject.clone())
+                .collect()
+        });
+        spawn_local(watchlist::recheck(state, subjects));
+    };
+
+    let on_notify = move |ev: leptos::ev::Event| {
+        let enabled = event_target_checked(&ev);
+        spawn_local(async move {
+            let granted = !enabled || watchlist::request_notification_permission().await;
+            if !granted {
+                state.error.set(Some(
+                    "Browser notifications are blocked for this site; changes will only show as in-app alerts".to_string(),
+                ));
+            }
+            save_settings(state, |list| list.notify = enabled && granted);
+        });
+    };
+
+    let subjects = move || {
+        state.watchlist.with(|list| {
+            list.entries
+                .iter()
+                .map(|entry| entry.subject.clone())
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-6 mb-6">
+            <h2 class="text-xl font-bold mb-1">"👁 Watchlist"</h2>
+            <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                "Watched accounts and records are re-checked in the background while this tab is open. Label changes raise an alert and are kept as snapshots in this browser."
+            </p>
+
+            <form on:submit=on_add class="flex gap-2 mb-4">
+                <input
+                    type="text"
+                    placeholder="Handle, DID, AT-URI or bsky.app link"
+                    class="flex-1 px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-blue-500 focus:border-transparent bg-white dark:bg-gray-700"
+                    prop:value=move || input.get()
+                    on:input=move |ev| input.set(event_target_value(&ev))
+                />
+                <button
+                    type="submit"
+                    class="bg-blue-600 hover:bg-blue-700 text-white font-semibold py-2 px-4 rounded-lg transition-colors"
+                >
+                    "➕ Watch"
+                </button>
+            </form>
+
+            <div class="flex flex-wrap items-center gap-4 text-sm">
+                <label class="flex items-center gap-2">
+                    "Re-check every"
+                    <select
+                        class="px-2 py-1 border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700"
+                        on:change=move |ev| {
+                            if let Ok(minutes) = event_target_value(&ev).parse() {
+                                save_settings(state, |list| list.interval_minutes = minutes);
+                            }
+                        }
+                    >
+                        {INTERVAL_CHOICES.into_iter().map(|minutes| view! {
+                            <option
+                                value=minutes.to_string()
+                                selected=move || state.watchlist.with(|list| list.interval_minutes == minutes)
+                            >
+                                {interval_name(minutes)}
+                            </option>
+                        }).collect_view()}
+                    </select>
+                </label>
+                <label class="flex items-center gap-2 cursor-pointer">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || state.watchlist.with(|list| list.notify)
+                        on:change=on_notify
+                    />
+                    "Browser notifications"
+                </label>
+                <button
+                    type="button"
+                    on:click=on_check_all
+                    disabled=move || state.watch_checking.get() || state.watchlist.with(|list| list.entries.is_empty())
+                    class="ml-auto px-3 py-1 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 disabled:opacity-50 rounded-lg transition-colors"
+                >
+                    {move || if state.watch_checking.get() { "🔄 Checking..." } else { "🔄 Check All Now" }}
+                </button>
+            </div>
+
+            {move || state.error.get().map(|err| view! {
+                <div class="mt-4 p-4 bg-red-100 dark:bg-red-900 border border-red-300 dark:border-red-700 rounded-lg">
+                    <p class="text-red-800 dark:text-red-200 text-sm">{err}</p>
+                </div>
+            })}
+        </div>
+
+        <Show
+            when=move || state.watchlist.with(|list| !list.entries.is_empty())
+            fallback=|| view! {
+                <div class="text-center py-12 text-gray-500 dark:text-gray-400">
+                    "Nothing watched yet. Add an account under appeal to track its labels."
+                </div>
+            }
+        >
+            <div class="space-y-4">
+                <For each=subjects key=|subject| subject.clone() let:subject>
+                    <WatchEntryCard subject=subject />
+                </For>
+            </div>
+        </Show>
+    }
+}
+
+#[component]
+fn WatchEntryCard(subject: String) -> impl IntoView {
+    let state = expect_context::<AppState>();
+    let show_history = create_rw_signal(false);
+    let subject = store_value(subject);
+    let entry = move || {
+        state
+            .watchlist
+            .with(|list| subject.with_value(|subject| list.entry(subject).cloned()))
+    };
+
+    let on_check = move |_| spawn_local(watchlist::recheck(state, vec![subject.get_value()]));
+    let on_remove = move |_| {
+        save_settings(state, |list| list.remove(&subject.get_value()));
+    };
+
+    move || {
+        let entry = entry()?;
+        let latest = entry.latest().cloned();
+        let last_checked = entry
+            .last_checked
+            .map(|at| utils::format_timestamp(&at.to_rfc3339()))
+            .unwrap_or_else(|| "never".to_string());
+        // Newest first, skipping the baseline, which has no changes to show
+        let history: Vec<_> = entry
+            .snapshots
+            .iter()
+            .rev()
+            .filter(|snapshot| !snapshot.changes.is_empty())
+            .cloned()
+            .collect();
+        let history_len = history.len();
+
+        Some(view! {
+            <div class="bg-white dark:bg-gray-800 rounded-lg shadow-md p-4">
+                <div class="flex flex-wrap items-start justify-between gap-2">
+                    <div>
+                        <A href=utils::check_path(&entry.subject) class="font-semibold text-blue-600 dark:text-blue-400 hover:underline break-all">
+                            {entry.subject.clone()}
+                        </A>
+                        <div class="text-xs text-gray-500 dark:text-gray-400">
+                            {format!("Last checked {} • watching since {}", last_checked, utils::format_timestamp(&entry.added_at.to_rfc3339()))}
+                        </div>
+                    </div>
+                    <div class="flex gap-2 text-sm">
+                        <button
+                            type="button"
+                            on:click=on_check
+                            disabled=move || state.watch_checking.get()
+                            class="px-3 py-1 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 disabled:opacity-50 rounded-lg transition-colors"
+                        >
+                            "🔄 Check"
+                        </button>
+                        <button
+                            type="button"
+                            on:click=on_remove
+                            class="px-3 py-1 bg-gray-100 dark:bg-gray-700 hover:bg-red-100 dark:hover:bg-red-900 rounded-lg transition-colors"
+                        >
+                            "✕ Remove"
+                        </button>
+                    </div>
+                </div>
+
+                {entry.last_error.map(|error| view! {
+                    <p class="mt-2 text-sm text-red-700 dark:text-red-300">{format!("⚠️ Last check failed: {}", error)}</p>
+                })}
+
+                <div class="mt-3 flex flex-wrap gap-1">
+                    {match latest {
+                        None => view! {
+                            <span class="text-sm text-gray-500 dark:text-gray-400">"Not checked yet"</span>
+                        }.into_view(),
+                        Some(snapshot) if snapshot.labels.is_empty() => view! {
+                            <span class="text-sm text-green-700 dark:text-green-300">"✓ No labels in effect"</span>
+                        }.into_view(),
+                        Some(snapshot) => snapshot.labels.into_iter().map(|label| view! {
+                            <span
+                                class="px-2 py-1 rounded bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200 text-xs font-mono"
+                                title=format!("by {}", label.src)
+                            >
+                                {label.category().icon()} " " {label.val.clone()}
+                            </span>
+                        }).collect_view(),
+                    }}
+                </div>
+
+                {(history_len > 0).then(|| view! {
+                    <button
+                        type="button"
+                        class="mt-3 text-sm text-blue-600 dark:text-blue-400 hover:underline"
+                        on:click=move |_| show_history.update(|shown| *shown = !*shown)
+                    >
+                        {move || format!(
+                            "{} History ({} change{})",
+                            if show_history.get() { "▼" } else { "▶" },
+                            history_len,
+                            if history_len == 1 { "" } else { "s" }
+                        )}
+                    </button>
+                    <Show when=move || show_history.get()>
+                        <ol class="mt-2 space-y-3 border-l-2 border-gray-200 dark:border-gray-700 pl-4">
+                            {history.iter().map(|snapshot| view! {
+                                <li>
+                                    <div class="text-xs text-gray-500 dark:text-gray-400">
+                                        {format!("{} • {}", utils::format_timestamp(&snapshot.taken_at.to_rfc3339()), snapshot.changes.summary())}
+                                    </div>
+                                    <LabelChanges changes=snapshot.changes.clone() />
+                                </li>
+                            }).collect_view()}
+                        </ol>
+                        {(history_len + 1 >= MAX_SNAPSHOTS).then(|| view! {
+                            <p class="mt-2 text-xs text-gray-500 dark:text-gray-400">
+                                {format!("Only the last {} snapshots are kept.", MAX_SNAPSHOTS)}
+                            </p>
+                        })}
+                    </Show>
+                })}
+            </div>
+        })
+    }
+}
+
+/// Changes found by background re-checks, shown on every page until dismissed
+#[component]
+pub fn WatchAlerts() -> impl IntoView {
+    let state = expect_context::<AppState>();
+
+    move || {
+        let alerts = state.watch_alerts.get();
+        if alerts.is_empty() {
+            return None;
+        }
+
+        Some(view! {
+            <div class="mb-6 p-4 bg-yellow-50 dark:bg-yellow-900 border-2 border-yellow-400 dark:border-yellow-600 rounded-lg">
+                <div class="flex items-center justify-between mb-2">
+                    <h3 class="font-bold">"🔔 Watchlist changes"</h3>
+                    <button
+                        type="button"
+                        class="text-sm hover:underline"
+                        on:click=move |_| state.watch_alerts.set(Vec::new())
+                    >
+                        "Dismiss all"
+                    </button>
+                </div>
+                <div class="space-y-3">
+                    {alerts.into_iter().enumerate().map(|(index, alert)| view! {
+                        <div>
+                            <div class="flex items-center justify-between gap-2 text-sm">
+                                <span>
+                                    <A href="/watchlist" class="font-semibold hover:underline break-all">{alert.subject.clone()}</A>
+                                    {format!(" • {} • {}", alert.changes.summary(), utils::format_timestamp(&alert.at.to_rfc3339()))}
+                                </span>
+                                <button
+                                    type="button"
+                                    class="text-xs hover:underline"
+                                    on:click=move |_| state.watch_alerts.update(|alerts| {
+                                        if index < alerts.len() {
+                                            alerts.remove(index);
+                                        }
+                                    })
+                                >
+                                    "Dismiss"
+                                </button>
+                            </div>
+                            <LabelChanges changes=alert.changes />
+                        </div>
+                    }).collect_view()}
+                </div>
+            </div>
+        })
+    }
+}
+
+/// Change the watchlist and save it, reporting storage errors
+fn save_settings(state: AppState, change: impl FnOnce(&mut watchlist::Watchlist)) {
+    state.watchlist.update(|list| {
+        change(list);
+        if let Err(e) = list.save() {
+            state.error.set(Some(e));
+        }
+    });
+}
+
+fn interval_name(minutes: u32) -> String {
+    match minutes {
+        m if m % 1440 == 0 => format!("{} day{}", m / 1440, if m == 1440 { "" } else { "s" }),
+        m if m % 60 == 0 => format!("{} hour{}", m / 60, if m == 60 { "" } else { "s" }),
+        m => format!("{} min", m),
+    }
+}
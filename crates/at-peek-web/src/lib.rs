@@ -7,8 +7,10 @@ use leptos::*;
 use wasm_bindgen::prelude::*;
 
 mod components;
+mod snapshots;
 mod state;
 mod utils;
+mod watchlist;
 
 use components::App;
 
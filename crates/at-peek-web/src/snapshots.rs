@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Inspection snapshots kept in IndexedDB, one per Single Check or Bulk Analysis
+//!
+//! Each object holds the subject and kind (indexed and filtered on) and the snapshot as
+//! JSON. IndexedDB rather than localStorage, since bulk snapshots list every checked
+//! record and would soon outgrow its quota.
+
+use atproto_client::{Snapshot, SnapshotKind};
+use leptos::*;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{IdbDatabase, IdbObjectStoreParameters, IdbRequest, IdbTransactionMode};
+
+use crate::state::AppState;
+
+const DB_NAME: &str = "at-peek";
+const DB_VERSION: u32 = 1;
+const STORE: &str = "snapshots";
+const SUBJECT_INDEX: &str = "subject";
+
+/// A snapshot and its key in the store
+#[derive(Debug, Clone)]
+pub struct StoredSnapshot {
+    pub id: f64,
+    pub snapshot: Snapshot,
+}
+
+/// Save `snapshot` in the background; a failure only costs the history entry
+pub fn keep(state: AppState, snapshot: Snapshot) {
+    spawn_local(async move {
+        match save(&snapshot).await {
+            Ok(()) => state.snapshots_saved.update(|saved| *saved += 1),
+            Err(e) => log::warn!("Could not save snapshot of {}: {}", snapshot.subject, e),
+        }
+    });
+}
+
+pub async fn save(snapshot: &Snapshot) -> Result<(), String> {
+    let json = serde_json::to_string(snapshot)
+        .map_err(|e| format!("Could not serialize snapshot: {}", e))?;
+    let object = js_sys::Object::new();
+    for (key, value) in [
+        ("subject", snapshot.subject.as_str()),
+        ("kind", kind_key(snapshot.kind)),
+        ("json", json.as_str()),
+    ] {
+        js_sys::Reflect::set(&object, &key.into(), &value.into()).map_err(js_error)?;
+    }
+
+    let store = open()
+        .await?
+        .transaction_with_str_and_mode(STORE, IdbTransactionMode::Readwrite)
+        .and_then(|tx| tx.object_store(STORE))
+        .map_err(js_error)?;
+    finish(&store.add(&object).map_err(js_error)?).await?;
+    Ok(())
+}
+
+/// Snapshots of `subject` from one kind of inspection, oldest first
+pub async fn list(subject: &str, kind: SnapshotKind) -> Result<Vec<StoredSnapshot>, String> {
+    let index = open()
+        .await?
+        .transaction_with_str(STORE)
+        .and_then(|tx| tx.object_store(STORE))
+        .and_then(|store| store.index(SUBJECT_INDEX))
+        .map_err(js_error)?;
+    let objects = finish(
+        &index
+            .get_all_with_key(&JsValue::from_str(subject))
+            .map_err(js_error)?,
+    )
+    .await?;
+
+    let mut snapshots: Vec<StoredSnapshot> = js_sys::Array::from(&objects)
+        .iter()
+        .filter(|object| field(object, "kind").as_string().as_deref() == Some(kind_key(kind)))
+        .filter_map(|object| {
+            let id = field(&object, "id").as_f64()?;
+            let json = field(&object, "json").as_string()?;
+            match serde_json::from_str(&json) {
+                Ok(snapshot) => Some(StoredSnapshot { id, snapshot }),
+                Err(e) => {
+                    log::warn!("Skipping unreadable snapshot {}: {}", id, e);
+                    None
+                }
+            }
+        })
+        .collect();
+    snapshots.sort_by_key(|stored| stored.snapshot.taken_at);
+    Ok(snapshots)
+}
+
+pub async fn delete(ids: &[f64]) -> Result<(), String> {
+    let store = open()
+        .await?
+        .transaction_with_str_and_mode(STORE, IdbTransactionMode::Readwrite)
+        .and_then(|tx| tx.object_store(STORE))
+        .map_err(js_error)?;
+    for id in ids {
+        finish(&store.delete(&JsValue::from_f64(*id)).map_err(js_error)?).await?;
+    }
+    Ok(())
+}
+
+async fn open() -> Result<IdbDatabase, String> {
+    let factory = web_sys::window()
+        .and_then(|window| window.indexed_db().ok().flatten())
+        .ok_or_else(|| "IndexedDB is unavailable in this browser".to_string())?;
+    let request = factory
+        .open_with_u32(DB_NAME, DB_VERSION)
+        .map_err(js_error)?;
+
+    // First open (or a version bump): create the store and its subject index
+    let upgrading = request.clone();
+    let on_upgrade = Closure::once_into_js(move |_: JsValue| {
+        let Ok(db) = upgrading.result().map(IdbDatabase::unchecked_from_js) else {
+            return;
+        };
+        if db.object_store_names().contains(STORE) {
+            return;
+        }
+        let parameters = IdbObjectStoreParameters::new();
+        parameters.set_key_path(&JsValue::from_str("id"));
+        parameters.set_auto_increment(true);
+        let created = db
+            .create_object_store_with_optional_parameters(STORE, &parameters)
+            .and_then(|store| store.create_index_with_str(SUBJECT_INDEX, "subject"));
+        if let Err(e) = created {
+            log::warn!("Could not create snapshot store: {:?}", e);
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+    finish(&request).await.map(IdbDatabase::unchecked_from_js)
+}
+
+/// Wait for a request to complete, giving its result
+async fn finish(request: &IdbRequest) -> Result<JsValue, String> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let succeeded = request.clone();
+        let on_success = Closure::once_into_js(move |_: JsValue| {
+            let result = succeeded.result().unwrap_or(JsValue::UNDEFINED);
+            let _ = resolve.call1(&JsValue::NULL, &result);
+        });
+        let on_error = Closure::once_into_js(move |_: JsValue| {
+            let _ = reject.call1(
+                &JsValue::NULL,
+                &JsValue::from_str("IndexedDB request failed"),
+            );
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+
+    JsFuture::from(promise).await.map_err(js_error)
+}
+
+fn field(object: &JsValue, key: &str) -> JsValue {
+    js_sys::Reflect::get(object, &JsValue::from_str(key)).unwrap_or(JsValue::UNDEFINED)
+}
+
+fn kind_key(kind: SnapshotKind) -> &'static str {
+    match kind {
+        SnapshotKind::Check => "check",
+        SnapshotKind::Bulk => "bulk",
+    }
+}
+
+fn js_error(error: JsValue) -> String {
+    error
+        .as_string()
+        .unwrap_or_else(|| format!("Snapshot storage error: {:?}", error))
+}
@@ -3,6 +3,8 @@
 use atproto_client::LabelCollection;
 use leptos::*;
 
+use crate::watchlist::{WatchAlert, Watchlist};
+
 /// Global application state
 #[derive(Clone, Copy)]
 pub struct AppState {
@@ -12,9 +14,15 @@ pub struct AppState {
     /// Fetched labels
     pub labels: RwSignal<Option<LabelCollection>>,
 
+    /// Subject the fetched labels were queried for (DID or AT-URI)
+    pub checked_subject: RwSignal<Option<String>>,
+
     /// Loading state
     pub is_loading: RwSignal<bool>,
 
+    /// Bumped whenever a single check starts or is cleared, so an older one's result is dropped
+    pub check_generation: RwSignal<u32>,
+
     /// Error message (if any)
     pub error: RwSignal<Option<String>>,
 
@@ -23,6 +31,18 @@ pub struct AppState {
 
     /// Is user authenticated
     pub is_authenticated: RwSignal<bool>,
+
+    /// Subjects re-checked in the background, with their snapshot history
+    pub watchlist: RwSignal<Watchlist>,
+
+    /// Changes found by watchlist re-checks, until dismissed
+    pub watch_alerts: RwSignal<Vec<WatchAlert>>,
+
+    /// A watchlist re-check is running
+    pub watch_checking: RwSignal<bool>,
+
+    /// Bumped whenever a snapshot is saved, so snapshot lists reload
+    pub snapshots_saved: RwSignal<u32>,
 }
 
 impl AppState {
@@ -30,10 +50,16 @@ impl AppState {
         Self {
             subject_input: create_rw_signal(String::new()),
             labels: create_rw_signal(None),
+            checked_subject: create_rw_signal(None),
             is_loading: create_rw_signal(false),
+            check_generation: create_rw_signal(0),
             error: create_rw_signal(None),
             auth_token: create_rw_signal(None),
             is_authenticated: create_rw_signal(false),
+            watchlist: create_rw_signal(Watchlist::load()),
+            watch_alerts: create_rw_signal(Vec::new()),
+            watch_checking: create_rw_signal(false),
+            snapshots_saved: create_rw_signal(0),
         }
     }
 }
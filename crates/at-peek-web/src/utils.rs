@@ -1,14 +1,65 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::components::bulk_analysis::PostWithLabels;
+use atproto_client::export;
 use atproto_client::{
-    create_session, resolve_did, resolve_handle, AtRecord, Did, Handle, LabelCollection,
-    LabelerClient, PostClient,
+    create_session, resolve_did, AbortHandle, AccountSweep, AnalysisCheckpoint, AnalysisScope,
+    BulkAnalysis, BulkAnalyzer, Connection, Did, Label, LabelCollection, LabelerClient,
+    ListInspection, NetworkScan, PostClient, PostThread, PostWithLabels, ProgressReporter,
+    Repository, Subject, SubjectCheck,
 };
-use std::collections::HashMap;
+use wasm_bindgen::JsCast;
+
+/// File formats a bulk analysis can be downloaded as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Html,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Html => "html",
+        }
+    }
+
+    fn mime_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Html => "text/html",
+        }
+    }
+}
+
+/// File formats a single check can be downloaded as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckExportFormat {
+    Json,
+    Markdown,
+}
+
+impl CheckExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            CheckExportFormat::Json => "json",
+            CheckExportFormat::Markdown => "md",
+        }
+    }
+
+    fn mime_type(&self) -> &'static str {
+        match self {
+            CheckExportFormat::Json => "application/json",
+            CheckExportFormat::Markdown => "text/markdown",
+        }
+    }
+}
 
-// Re-export these types from utils since they're used in the public API
-pub use crate::components::bulk_analysis::{BulkAnalysisStats, UserInfo};
+/// localStorage key prefix for interrupted bulk analyses, one entry per account
+const CHECKPOINT_KEY_PREFIX: &str = "at-peek:checkpoint:";
 
 /// Authenticate with Bluesky
 pub async fn authenticate(handle: &str, password: &str) -> Result<String, String> {
@@ -18,55 +69,53 @@ pub async fn authenticate(handle: &str, password: &str) -> Result<String, String
         .map_err(|e| format!("Authentication failed: {}", e))
 }
 
-/// Fetch labels for a given subject (handle, DID, or AT-URI) from multiple sources
+/// Fetch labels for a given subject (handle, DID, AT-URI or web link) from multiple sources,
+/// with the resolved subject (DID or AT-URI) they were queried for
 pub async fn fetch_labels(
     input: &str,
     auth_token: Option<String>,
-) -> Result<LabelCollection, String> {
+) -> Result<(String, LabelCollection), String> {
     let bsky_labeler = if let Some(token) = &auth_token {
         LabelerClient::new_authenticated(token.clone())
     } else {
         LabelerClient::new()
     };
 
-    // Strip @ symbol if present (users might type @alice.bsky.social)
-    let input = input.trim_start_matches('@');
-
-    // Determine what type of input we have
-    let (subject, did_opt) = if input.starts_with("at://") {
-        // AT-URI
-        (input.to_string(), None)
-    } else if input.starts_with("did:") {
-        // DID
-        let did = atproto_client::Did::new(input.to_string());
-        (did.as_str().to_string(), Some(did))
-    } else if input.contains('.') {
-        // Assume it's a handle - resolve to DID first
-        let handle = Handle::new(input.to_string());
-        let did = resolve_handle(&handle)
-            .await
-            .map_err(|e| format!("Failed to resolve handle: {}", e))?;
-        (did.as_str().to_string(), Some(did))
-    } else {
-        return Err("Invalid input format. Expected handle, DID, or AT-URI".to_string());
-    };
+    // Accepts bsky.app and other client links as well as handles, DIDs and AT-URIs
+    let parsed = Subject::parse(input).map_err(|e| e.to_string())?;
+    let (subject, did) = parsed
+        .resolve()
+        .await
+        .map_err(|e| format!("Failed to resolve handle: {}", e))?;
+    // Only accounts are also checked against their PDS; records just against the labelers
+    let did_opt = parsed.is_account().then_some(did);
 
     // Query Bluesky's moderation service
-    let mut all_labels = match bsky_labeler
+    let mut collection = match bsky_labeler
         .query_labels(std::slice::from_ref(&subject))
         .await
     {
-        Ok(collection) => collection.labels,
+        Ok(collection) => collection,
         Err(e) => {
             // Propagate authentication errors to the user
             if matches!(e, atproto_client::Error::AuthenticationRequired(_)) {
                 return Err(e.to_string());
             }
             log::warn!("Failed to query Bluesky labeler: {}", e);
-            Vec::new()
+            LabelCollection {
+                labels: Vec::new(),
+                labeler_did: "https://mod.bsky.app".to_string(),
+                query_timestamp: chrono::Utc::now(),
+                responses: Vec::new(),
+            }
         }
     };
 
+    // Self-labels live in the record itself: the profile for accounts, the record for AT-URIs
+    collection
+        .labels
+        .extend(fetch_self_labels(&subject, did_opt.as_ref()).await);
+
     // If we have a DID, also query the user's PDS for admin labels
     if let Some(did) = did_opt {
         if let Ok(pds_endpoint) = resolve_did(&did).await {
@@ -75,10 +124,11 @@ pub async fn fetch_labels(
             } else {
                 LabelerClient::with_url(pds_endpoint)
             };
-            match pds_labeler.query_labels(&[subject]).await {
-                Ok(collection) => {
-                    all_labels.extend(collection.labels);
-                }
+            match pds_labeler
+                .query_labels(std::slice::from_ref(&subject))
+                .await
+            {
+                Ok(pds_collection) => collection.merge(pds_collection),
                 Err(e) => {
                     // Propagate authentication errors to the user
                     if matches!(e, atproto_client::Error::AuthenticationRequired(_)) {
@@ -90,11 +140,50 @@ pub async fn fetch_labels(
         }
     }
 
-    Ok(LabelCollection {
-        labels: all_labels,
-        labeler_did: "multiple".to_string(),
-        query_timestamp: chrono::Utc::now(),
-    })
+    Ok((subject, collection))
+}
+
+/// Fetch author-applied self-labels for a subject from its repository
+async fn fetch_self_labels(subject: &str, did: Option<&Did>) -> Vec<atproto_client::Label> {
+    let post_client = PostClient::new();
+
+    let record = if let Some(did) = did {
+        post_client
+            .get_record(did, "app.bsky.actor.profile", "self")
+            .await
+    } else {
+        // at://did/collection/rkey - self-labels can only be read from the author's repo
+        let mut parts = subject.trim_start_matches("at://").splitn(3, '/');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(authority), Some(collection), Some(rkey)) if authority.starts_with("did:") => {
+                let did = Did::new(authority.to_string());
+                post_client.get_record(&did, collection, rkey).await
+            }
+            _ => return Vec::new(),
+        }
+    };
+
+    match record {
+        Ok(record) => record.self_labels(),
+        Err(e) => {
+            log::warn!("Failed to fetch record for self-labels: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Read a user-selected file (e.g. a dropped `.car` export) into memory
+pub async fn read_file_bytes(file: web_sys::File) -> Result<Vec<u8>, String> {
+    let buffer = wasm_bindgen_futures::JsFuture::from(file.array_buffer())
+        .await
+        .map_err(|e| format!("Failed to read file: {:?}", e))?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+/// Read a user-selected text or CSV file, replacing invalid UTF-8
+pub async fn read_file_text(file: web_sys::File) -> Result<String, String> {
+    let bytes = read_file_bytes(file).await?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
 }
 
 /// Shorten a DID for display
@@ -113,6 +202,27 @@ pub fn format_timestamp(iso8601: &str) -> String {
         .unwrap_or_else(|_| iso8601.to_string())
 }
 
+/// Labels expiring within this many days get a countdown
+pub const EXPIRING_SOON_DAYS: i64 = 7;
+
+/// Whether `label` expires after `now` but within [`EXPIRING_SOON_DAYS`]
+pub fn expires_soon(label: &Label, now: chrono::DateTime<chrono::Utc>) -> bool {
+    label
+        .expires_at()
+        .is_some_and(|exp| exp > now && exp - now <= chrono::Duration::days(EXPIRING_SOON_DAYS))
+}
+
+/// "Expired … ago" or "Expires in …" for labels with an expiry
+pub fn expiry_note(label: &Label, now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+    let exp = label.expires_at()?;
+    let (exp_str, now_str) = (exp.to_rfc3339(), now.to_rfc3339());
+    Some(if exp <= now {
+        format!("⌛ Expired {} ago", calculate_duration(&exp_str, &now_str))
+    } else {
+        format!("⏳ Expires in {}", calculate_duration(&now_str, &exp_str))
+    })
+}
+
 /// Calculate duration between two timestamps and format as human-readable string
 pub fn calculate_duration(from_timestamp: &str, to_timestamp: &str) -> String {
     let from = chrono::DateTime::parse_from_rfc3339(from_timestamp);
@@ -160,478 +270,438 @@ pub fn calculate_duration(from_timestamp: &str, to_timestamp: &str) -> String {
     }
 }
 
-/// Fetch likes for a post from the AppView
-async fn fetch_likes(post_uri: &str) -> Result<(usize, Vec<UserInfo>), String> {
-    let client = reqwest::Client::new();
-    let encoded_uri = urlencoding::encode(post_uri);
-    let url = format!(
-        "https://public.api.bsky.app/xrpc/app.bsky.feed.getLikes?uri={}&limit=100",
-        encoded_uri
-    );
-
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch likes: {}", e))?;
-
-    if !response.status().is_success() {
-        log::warn!(
-            "Failed to fetch likes for {}: {}",
-            post_uri,
-            response.status()
-        );
-        return Ok((0, Vec::new()));
+/// Analyze all posts from a user for labels
+///
+/// With `resume`, continues from the account's saved checkpoint if it is still fresh and
+/// for the same scope.
+pub async fn analyze_user_posts(
+    input: &str,
+    auth_token: Option<String>,
+    scope: AnalysisScope,
+    resume: bool,
+    abort: AbortHandle,
+    mut progress: impl ProgressReporter,
+) -> Result<BulkAnalysis, String> {
+    // A record link (e.g. a pasted post URL) analyzes its author
+    let subject = Subject::parse(input).map_err(|e| e.to_string())?;
+    if !subject.actor.starts_with("did:") {
+        progress.progress("Resolving handle...".to_string(), 5);
     }
-
-    let json: serde_json::Value = response
-        .json()
+    let (_, did) = subject
+        .resolve()
         .await
-        .map_err(|e| format!("Failed to parse likes response: {}", e))?;
-
-    let mut likers = Vec::new();
-    if let Some(likes) = json.get("likes").and_then(|l| l.as_array()) {
-        for like in likes {
-            if let (Some(did), Some(handle)) = (
-                like.get("actor")
-                    .and_then(|a| a.get("did"))
-                    .and_then(|d| d.as_str()),
-                like.get("actor")
-                    .and_then(|a| a.get("handle"))
-                    .and_then(|h| h.as_str()),
-            ) {
-                let display_name = like
-                    .get("actor")
-                    .and_then(|a| a.get("displayName"))
-                    .and_then(|n| n.as_str())
-                    .map(|s| s.to_string());
-
-                likers.push(UserInfo {
-                    did: did.to_string(),
-                    handle: handle.to_string(),
-                    display_name,
-                });
-            }
-        }
-    }
+        .map_err(|e| format!("Failed to resolve handle: {}", e))?;
 
-    let like_count = json
-        .get("likes")
-        .and_then(|l| l.as_array())
-        .map(|a| a.len())
-        .unwrap_or(0);
+    let analysis = analyzer(&did, auth_token, abort, resume)
+        .with_scope(scope)
+        .analyze_account(&did, &mut progress)
+        .await
+        .map_err(|e| e.to_string())?;
 
-    Ok((like_count, likers))
+    store_checkpoint(&did, &analysis);
+    Ok(analysis)
 }
 
-/// Fetch reposts for a post from the AppView
-async fn fetch_reposts(post_uri: &str) -> Result<(usize, Vec<UserInfo>), String> {
-    let client = reqwest::Client::new();
-    let encoded_uri = urlencoding::encode(post_uri);
-    let url = format!(
-        "https://public.api.bsky.app/xrpc/app.bsky.feed.getRepostedBy?uri={}&limit=100",
-        encoded_uri
-    );
-
-    let response = client
-        .get(&url)
-        .send()
+/// Find every label under an account with a wildcard query, flagging deleted records
+pub async fn sweep_account(
+    input: &str,
+    auth_token: Option<String>,
+    abort: AbortHandle,
+    mut progress: impl ProgressReporter,
+) -> Result<AccountSweep, String> {
+    let (_, did) = Subject::parse(input)
+        .map_err(|e| e.to_string())?
+        .resolve()
         .await
-        .map_err(|e| format!("Failed to fetch reposts: {}", e))?;
-
-    if !response.status().is_success() {
-        log::warn!(
-            "Failed to fetch reposts for {}: {}",
-            post_uri,
-            response.status()
-        );
-        return Ok((0, Vec::new()));
-    }
+        .map_err(|e| format!("Failed to resolve handle: {}", e))?;
 
-    let json: serde_json::Value = response
-        .json()
+    BulkAnalyzer::new(auth_token)
+        .with_abort(abort)
+        .sweep_account(&did, &mut progress)
         .await
-        .map_err(|e| format!("Failed to parse reposts response: {}", e))?;
-
-    let mut reposters = Vec::new();
-    if let Some(users) = json.get("repostedBy").and_then(|r| r.as_array()) {
-        for user in users {
-            if let (Some(did), Some(handle)) = (
-                user.get("did").and_then(|d| d.as_str()),
-                user.get("handle").and_then(|h| h.as_str()),
-            ) {
-                let display_name = user
-                    .get("displayName")
-                    .and_then(|n| n.as_str())
-                    .map(|s| s.to_string());
-
-                reposters.push(UserInfo {
-                    did: did.to_string(),
-                    handle: handle.to_string(),
-                    display_name,
-                });
-            }
-        }
-    }
-
-    let repost_count = json
-        .get("repostedBy")
-        .and_then(|r| r.as_array())
-        .map(|a| a.len())
-        .unwrap_or(0);
+        .map_err(|e| e.to_string())
+}
 
-    Ok((repost_count, reposters))
+/// Check every subject in a pasted list, resolving and querying them in batches
+pub async fn check_subjects(
+    inputs: &[String],
+    auth_token: Option<String>,
+    include_expired: bool,
+    abort: AbortHandle,
+    mut progress: impl ProgressReporter,
+) -> Vec<SubjectCheck> {
+    let scope = AnalysisScope {
+        include_expired,
+        ..AnalysisScope::default()
+    };
+    BulkAnalyzer::new(auth_token)
+        .with_scope(scope)
+        .with_abort(abort)
+        .check_subjects(inputs, &mut progress)
+        .await
 }
 
-/// Extract image and video URLs from a post's embed field
-async fn extract_media_urls(post: &AtRecord, did: &Did) -> (Vec<String>, Option<String>) {
-    let mut image_urls = Vec::new();
-    let mut video_url = None;
+/// Query account labels across an account's follows and/or followers
+pub async fn scan_network(
+    input: &str,
+    auth_token: Option<String>,
+    connections: &[Connection],
+    max_accounts: usize,
+    include_expired: bool,
+    abort: AbortHandle,
+    mut progress: impl ProgressReporter,
+) -> Result<NetworkScan, String> {
+    let (_, did) = Subject::parse(input)
+        .map_err(|e| e.to_string())?
+        .resolve()
+        .await
+        .map_err(|e| format!("Failed to resolve handle: {}", e))?;
 
-    // Get PDS endpoint for blob URLs
-    let pds_endpoint = match resolve_did(did).await {
-        Ok(pds) => pds,
-        Err(_) => return (image_urls, video_url),
+    let scope = AnalysisScope {
+        include_expired,
+        ..AnalysisScope::default()
     };
+    BulkAnalyzer::new(auth_token)
+        .with_scope(scope)
+        .with_abort(abort)
+        .scan_network(&did, connections, max_accounts, &mut progress)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    if let Some(embed) = post.value.get("embed") {
-        let embed_type = embed.get("$type").and_then(|t| t.as_str());
-
-        match embed_type {
-            Some("app.bsky.embed.images") => {
-                // Extract image CIDs
-                if let Some(images) = embed.get("images").and_then(|i| i.as_array()) {
-                    for img in images {
-                        if let Some(cid) = img
-                            .get("image")
-                            .and_then(|i| i.get("ref"))
-                            .and_then(|r| r.get("$link"))
-                            .and_then(|l| l.as_str())
-                        {
-                            let url = format!(
-                                "{}/xrpc/com.atproto.sync.getBlob?did={}&cid={}",
-                                pds_endpoint,
-                                did.as_str(),
-                                cid
-                            );
-                            image_urls.push(url);
-                        }
-                    }
-                }
-            }
-            Some("app.bsky.embed.video") => {
-                // Extract video CID
-                if let Some(cid) = embed
-                    .get("video")
-                    .and_then(|v| v.get("ref"))
-                    .and_then(|r| r.get("$link"))
-                    .and_then(|l| l.as_str())
-                {
-                    video_url = Some(format!(
-                        "{}/xrpc/com.atproto.sync.getBlob?did={}&cid={}",
-                        pds_endpoint,
-                        did.as_str(),
-                        cid
-                    ));
-                }
-            }
-            Some("app.bsky.embed.recordWithMedia") => {
-                // Handle posts with both record and media
-                if let Some(media) = embed.get("media") {
-                    let media_type = media.get("$type").and_then(|t| t.as_str());
-                    if media_type == Some("app.bsky.embed.images") {
-                        if let Some(images) = media.get("images").and_then(|i| i.as_array()) {
-                            for img in images {
-                                if let Some(cid) = img
-                                    .get("image")
-                                    .and_then(|i| i.get("ref"))
-                                    .and_then(|r| r.get("$link"))
-                                    .and_then(|l| l.as_str())
-                                {
-                                    let url = format!(
-                                        "{}/xrpc/com.atproto.sync.getBlob?did={}&cid={}",
-                                        pds_endpoint,
-                                        did.as_str(),
-                                        cid
-                                    );
-                                    image_urls.push(url);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            _ => {}
-        }
+/// Query labels on every post and author in the thread of a post
+pub async fn analyze_thread(
+    input: &str,
+    auth_token: Option<String>,
+    include_expired: bool,
+    abort: AbortHandle,
+    mut progress: impl ProgressReporter,
+) -> Result<PostThread, String> {
+    let subject = Subject::parse(input).map_err(|e| e.to_string())?;
+    if !subject.is_post() {
+        return Err(format!("{} is not a post", input.trim()));
     }
+    let (uri, _) = subject
+        .resolve()
+        .await
+        .map_err(|e| format!("Failed to resolve handle: {}", e))?;
 
-    (image_urls, video_url)
+    let scope = AnalysisScope {
+        include_expired,
+        ..AnalysisScope::default()
+    };
+    BulkAnalyzer::new(auth_token)
+        .with_scope(scope)
+        .with_abort(abort)
+        .analyze_thread(&uri, &mut progress)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Analyze all posts from a user for labels and return both stats and labeled posts
-pub async fn analyze_user_posts<F>(
+/// Query labels on a list or starter pack and on every member
+pub async fn inspect_list(
     input: &str,
     auth_token: Option<String>,
-    mut progress_callback: F,
-) -> Result<(BulkAnalysisStats, Vec<PostWithLabels>), String>
-where
-    F: FnMut(String, u8),
-{
-    // Strip @ symbol if present (users might type @alice.bsky.social)
-    let input = input.trim_start_matches('@');
-
-    // Resolve handle to DID if needed
-    let did = if input.starts_with("did:") {
-        atproto_client::Did::new(input.to_string())
-    } else {
-        let handle = Handle::new(input.to_string());
-        progress_callback("Resolving handle...".to_string(), 5);
+    max_members: usize,
+    include_expired: bool,
+    abort: AbortHandle,
+    mut progress: impl ProgressReporter,
+) -> Result<ListInspection, String> {
+    let subject = Subject::parse(input).map_err(|e| e.to_string())?;
+    if !subject.is_list() {
+        return Err(format!("{} is not a list or starter pack", input.trim()));
+    }
+    let (uri, _) = subject
+        .resolve()
+        .await
+        .map_err(|e| format!("Failed to resolve handle: {}", e))?;
 
-        resolve_handle(&handle)
-            .await
-            .map_err(|e| format!("Failed to resolve handle: {}", e))?
+    let scope = AnalysisScope {
+        include_expired,
+        ..AnalysisScope::default()
     };
+    BulkAnalyzer::new(auth_token)
+        .with_scope(scope)
+        .with_abort(abort)
+        .inspect_list(&uri, max_members, &mut progress)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    // Fetch posts directly from PDS
-    // Note: Banned/suspended accounts may be inaccessible
-    progress_callback("Fetching posts from PDS...".to_string(), 15);
-    let post_client = PostClient::new();
-    let posts = post_client
-        .fetch_posts(&did, 1000)
+/// Account labels on everyone who liked or reposted `post`, and why some accounts weren't
+/// checked if any weren't
+pub async fn engagement_labels(
+    post: &PostWithLabels,
+    auth_token: Option<String>,
+) -> (Vec<Label>, Option<String>) {
+    BulkAnalyzer::new(auth_token).engagement_labels(post).await
+}
+
+/// Analyze a repository export (`.car` file from Bluesky settings) without contacting the PDS
+pub async fn analyze_car_export(
+    car_bytes: &[u8],
+    auth_token: Option<String>,
+    scope: AnalysisScope,
+    resume: bool,
+    abort: AbortHandle,
+    mut progress: impl ProgressReporter,
+) -> Result<BulkAnalysis, String> {
+    progress.progress("Reading repository export...".to_string(), 10);
+
+    let repo =
+        Repository::from_car(car_bytes).map_err(|e| format!("Failed to read CAR file: {}", e))?;
+    let did = repo.did();
+
+    let analysis = analyzer(&did, auth_token, abort, resume)
+        .with_scope(scope)
+        .analyze_repository(&repo, &mut progress)
         .await
-        .map_err(|e| format!("Failed to fetch posts: {}", e))?;
+        .map_err(|e| e.to_string())?;
 
-    progress_callback(
-        format!("Fetched {} posts, querying labels...", posts.len()),
-        20,
-    );
+    store_checkpoint(&did, &analysis);
+    Ok(analysis)
+}
 
-    if posts.is_empty() {
-        return Ok((
-            BulkAnalysisStats {
-                total_posts: 0,
-                posts_with_labels: 0,
-                labels_by_category: HashMap::new(),
-                top_label_values: Vec::new(),
-                account_labels: Vec::new(),
-            },
-            Vec::new(),
-        ));
-    }
+/// Generate a report of `analysis` in the browser and download it
+pub fn export_analysis(analysis: &BulkAnalysis, format: ExportFormat) -> Result<(), String> {
+    let did = analysis.checkpoint.did.as_str();
+    let contents = match format {
+        ExportFormat::Json => export::to_json(did, analysis)
+            .map_err(|e| format!("Failed to generate JSON export: {}", e))?,
+        ExportFormat::Csv => export::to_csv(did, analysis),
+        ExportFormat::Html => export::to_html(did, analysis),
+    };
 
-    // Collect post URIs
-    let uris: Vec<String> = posts.iter().map(|p| p.uri.clone()).collect();
+    let filename = format!(
+        "at-peek-{}-{}.{}",
+        did.replace(':', "_"),
+        chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+        format.extension()
+    );
+    download(&filename, format.mime_type(), &contents)
+}
 
-    log::info!("Fetched {} posts from PDS. Sample URIs:", uris.len());
-    for uri in uris.iter().take(3) {
-        log::info!("  - {}", uri);
-    }
+/// Download a batch check as CSV, one row per (subject, label)
+pub fn export_checks(checks: &[SubjectCheck]) -> Result<(), String> {
+    let format = ExportFormat::Csv;
+    let filename = format!(
+        "at-peek-batch-{}.{}",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+        format.extension()
+    );
+    download(
+        &filename,
+        format.mime_type(),
+        &export::checks_to_csv(checks),
+    )
+}
 
-    // Query labels from Bluesky's moderation service (including !takedown with auth)
-    let batch_size = 25;
-    let mut all_labels = Vec::new();
-    let bsky_labeler = if let Some(token) = auth_token.clone() {
-        log::info!(
-            "Using authenticated labeler client (token: {}...)",
-            &token[..20.min(token.len())]
-        );
-        LabelerClient::new_authenticated(token)
-    } else {
-        log::warn!("Using UNAUTHENTICATED labeler client - admin labels will NOT be visible!");
-        LabelerClient::new()
+/// Generate a report of a single check, with provenance and raw responses, and download it
+pub fn export_labels(
+    subject: &str,
+    collection: &LabelCollection,
+    format: CheckExportFormat,
+) -> Result<(), String> {
+    let contents = match format {
+        CheckExportFormat::Json => export::labels_to_json(subject, collection)
+            .map_err(|e| format!("Failed to generate JSON export: {}", e))?,
+        CheckExportFormat::Markdown => export::labels_to_markdown(subject, collection),
     };
 
-    // First, check for account-level labels on the DID itself
-    progress_callback("Checking account-level labels...".to_string(), 25);
-    log::info!("Querying account-level labels for DID: {}", did.as_str());
+    let filename = format!(
+        "at-peek-check-{}-{}.{}",
+        subject
+            .trim_start_matches("at://")
+            .replace([':', '/', '@'], "_"),
+        chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+        format.extension()
+    );
+    download(&filename, format.mime_type(), &contents)
+}
 
-    let mut account_labels = Vec::new();
-    match bsky_labeler.query_labels(&[did.as_str().to_string()]).await {
-        Ok(collection) => {
-            log::info!(
-                "Account-level query returned {} labels",
-                collection.labels.len()
-            );
-            for label in &collection.labels {
-                log::info!("  Account Label: {} on {}", label.val, label.uri);
-            }
-            account_labels = collection.labels.clone();
-            all_labels.extend(collection.labels);
-        }
-        Err(e) => {
-            // Propagate authentication errors to the user
-            if matches!(e, atproto_client::Error::AuthenticationRequired(_)) {
-                return Err(e.to_string());
-            }
-            log::error!("Failed to query account-level labels: {}", e);
-        }
-    }
+/// Save `contents` as a file via a temporary object URL
+fn download(filename: &str, mime_type: &str, contents: &str) -> Result<(), String> {
+    let js_error = |e: wasm_bindgen::JsValue| format!("Download failed: {:?}", e);
+
+    let parts = js_sys::Array::of1(&wasm_bindgen::JsValue::from_str(contents));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob =
+        web_sys::Blob::new_with_str_sequence_and_options(&parts, &options).map_err(js_error)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js_error)?;
+
+    let anchor = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| "Download failed: no document".to_string())?
+        .create_element("a")
+        .map_err(js_error)?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|_| "Download failed: not an anchor element".to_string())?;
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    // Some browsers start reading the blob only after the click handler returns; revoking
+    // the URL right away can leave the download empty or failed
+    leptos::set_timeout(
+        move || {
+            let _ = web_sys::Url::revoke_object_url(&url);
+        },
+        std::time::Duration::from_secs(1),
+    );
+    Ok(())
+}
 
-    let total_batches = uris.len().div_ceil(batch_size);
-    for (i, chunk) in uris.chunks(batch_size).enumerate() {
-        // Progress from 30% to 85% across all batches
-        let batch_progress = 30 + ((i as f32 / total_batches as f32) * 55.0) as u8;
-        progress_callback(
-            format!(
-                "Querying mod.bsky.app: batch {}/{}...",
-                i + 1,
-                total_batches
-            ),
-            batch_progress,
-        );
-
-        log::info!("Querying batch {} with {} URIs", i + 1, chunk.len());
-
-        match bsky_labeler.query_labels(chunk).await {
-            Ok(collection) => {
-                log::info!(
-                    "Batch {} returned {} labels",
-                    i + 1,
-                    collection.labels.len()
-                );
-                for label in &collection.labels {
-                    log::info!("  Label: {} on {}", label.val, label.uri);
-                }
-                all_labels.extend(collection.labels);
-            }
-            Err(e) => {
-                // Propagate authentication errors to the user
-                if matches!(e, atproto_client::Error::AuthenticationRequired(_)) {
-                    return Err(e.to_string());
-                }
-                log::error!("Failed to query mod.bsky.app batch {}: {}", i + 1, e);
-            }
-        }
+/// Route of a single check; post AT-URIs get the readable `/post/:did/:rkey` form
+///
+/// Links and `@handles` are normalized first, so pasted URLs give clean routes; input that
+/// doesn't parse is kept as-is and reported when the route runs.
+pub fn check_path(input: &str) -> String {
+    let Ok(subject) = Subject::parse(input) else {
+        return format!("/check/{}", path_segment(input.trim()));
+    };
+
+    match &subject.record {
+        Some((collection, rkey)) if collection == "app.bsky.feed.post" => format!(
+            "/post/{}/{}",
+            path_segment(&subject.actor),
+            path_segment(rkey)
+        ),
+        _ => format!("/check/{}", path_segment(&subject.to_string())),
     }
+}
 
-    log::info!(
-        "Total labels found across all batches: {}",
-        all_labels.len()
-    );
+/// Route of a bulk analysis of an account (handle, DID or link; records give their author)
+pub fn bulk_path(input: &str) -> String {
+    account_path("bulk", input)
+}
+
+/// Route of a network scan of an account (handle, DID or link; records give their author)
+pub fn network_path(input: &str) -> String {
+    account_path("network", input)
+}
 
-    progress_callback("Analyzing results...".to_string(), 90);
+/// Route of the thread view of a post (AT-URI or link), if `input` is one
+pub fn thread_path(input: &str) -> Option<String> {
+    let subject = Subject::parse(input).ok().filter(Subject::is_post)?;
+    let (_, rkey) = subject.record?;
+    Some(format!(
+        "/thread/{}/{}",
+        path_segment(&subject.actor),
+        path_segment(&rkey)
+    ))
+}
 
-    // Calculate statistics (only for post-level labels, not account labels)
-    let mut posts_with_labels_set: std::collections::HashSet<String> =
-        std::collections::HashSet::new();
-    let mut labels_by_category: HashMap<atproto_client::LabelCategory, usize> = HashMap::new();
-    let mut label_value_counts: HashMap<String, usize> = HashMap::new();
+/// Route of the list inspection of a list or starter pack (AT-URI or link), if `input` is one
+pub fn list_path(input: &str) -> Option<String> {
+    let subject = Subject::parse(input).ok().filter(Subject::is_list)?;
+    let (collection, rkey) = subject.record?;
+    let mode = if collection == "app.bsky.graph.starterpack" {
+        "starter-pack"
+    } else {
+        "list"
+    };
+    Some(format!(
+        "/{}/{}/{}",
+        mode,
+        path_segment(&subject.actor),
+        path_segment(&rkey)
+    ))
+}
 
-    for label in &all_labels {
-        if !label.neg {
-            // Only count post URIs (not account-level DIDs)
-            // Post URIs start with "at://" while DIDs start with "did:"
-            if label.uri.starts_with("at://") {
-                posts_with_labels_set.insert(label.uri.clone());
+fn account_path(mode: &str, input: &str) -> String {
+    let actor = match Subject::parse(input) {
+        Ok(subject) => subject.actor,
+        Err(_) => input.trim().to_string(),
+    };
+    format!("/{}/{}", mode, path_segment(&actor))
+}
 
-                let category = label.category();
-                *labels_by_category.entry(category).or_insert(0) += 1;
-                *label_value_counts.entry(label.val.clone()).or_insert(0) += 1;
-            }
-        }
+/// Percent-encode one path segment, leaving the `:` in DIDs readable
+fn path_segment(value: &str) -> String {
+    String::from(js_sys::encode_uri_component(value)).replace("%3A", ":")
+}
+
+/// Parse the value of an `<input type="date">` as midnight UTC
+pub fn parse_date_input(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.and_time(Default::default()).and_utc())
+}
+
+/// Forget an interrupted analysis so the next run starts over
+pub fn clear_checkpoint(did: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(&format!("{}{}", CHECKPOINT_KEY_PREFIX, did));
     }
+}
 
-    // Sort label values by count
-    let mut top_label_values: Vec<(String, usize)> = label_value_counts.into_iter().collect();
-    top_label_values.sort_by(|a, b| b.1.cmp(&a.1));
-
-    // Build posts with labels for display
-    let mut labeled_posts = Vec::new();
-
-    // If account has moderation labels (e.g., banned), show last 10 posts regardless of individual labels
-    let has_account_moderation = !account_labels.is_empty();
-    let mut posts_added = 0;
-    let mut posts_processed = 0;
-
-    for post in &posts {
-        posts_processed += 1;
-        // Update progress from 90% to 99% as we process posts
-        if posts_processed % 100 == 0 || posts_processed == posts.len() {
-            let process_progress = 90 + ((posts_processed as f32 / posts.len() as f32) * 9.0) as u8;
-            progress_callback(
-                format!("Processing posts ({}/{})...", posts_processed, posts.len()),
-                process_progress,
-            );
-        }
-        let post_labels: Vec<_> = all_labels
-            .iter()
-            .filter(|l| l.uri == post.uri)
-            .cloned()
-            .collect();
-
-        // Show post if: has labels OR (account is moderated AND we haven't shown 10 yet)
-        let should_show = !post_labels.is_empty() || (has_account_moderation && posts_added < 10);
-
-        if should_show {
-            let text = post
-                .value
-                .get("text")
-                .and_then(|t| t.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let created_at = post
-                .value
-                .get("createdAt")
-                .and_then(|t| t.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            // Extract media URLs from embed
-            let (image_urls, video_url) = extract_media_urls(post, &did).await;
-            let has_media = !image_urls.is_empty() || video_url.is_some();
-
-            // Fetch likes and reposts (especially useful for moderated posts)
-            let (like_count, likers) = fetch_likes(&post.uri).await.unwrap_or((0, Vec::new()));
-            let (repost_count, reposters) =
-                fetch_reposts(&post.uri).await.unwrap_or((0, Vec::new()));
+/// An analyzer for `did`, picking up its saved checkpoint only when resuming
+fn analyzer(
+    did: &Did,
+    auth_token: Option<String>,
+    abort: AbortHandle,
+    resume: bool,
+) -> BulkAnalyzer {
+    let analyzer = BulkAnalyzer::new(auth_token).with_abort(abort);
+    if !resume {
+        return analyzer;
+    }
 
+    match load_checkpoint(did.as_str()) {
+        Some(checkpoint) => {
             log::info!(
-                "Post {} has {} likes and {} reposts",
-                post.uri,
-                like_count,
-                repost_count
+                "Resuming analysis of {} ({} posts already checked)",
+                did,
+                checkpoint.queried_uris.len()
             );
-
-            labeled_posts.push(PostWithLabels {
-                uri: post.uri.clone(),
-                text,
-                labels: post_labels,
-                created_at,
-                has_media,
-                image_urls,
-                video_url,
-                like_count,
-                repost_count,
-                likers,
-                reposters,
-            });
-
-            posts_added += 1;
+            analyzer.with_checkpoint(checkpoint)
         }
+        None => analyzer,
+    }
+}
+
+fn load_checkpoint(did: &str) -> Option<AnalysisCheckpoint> {
+    let key = format!("{}{}", CHECKPOINT_KEY_PREFIX, did);
+    let json = local_storage()?.get_item(&key).ok()??;
+    serde_json::from_str(&json).ok()
+}
+
+/// Keep the checkpoint of an incomplete run, drop it once a run completes
+fn store_checkpoint(did: &Did, analysis: &BulkAnalysis) {
+    if analysis.complete {
+        clear_checkpoint(did.as_str());
+        return;
     }
 
-    // Sort posts by number of labels (most labeled first), then by recency
-    labeled_posts.sort_by(|a, b| {
-        let label_cmp = b.labels.len().cmp(&a.labels.len());
-        if label_cmp == std::cmp::Ordering::Equal {
-            // If same number of labels, sort by created_at (most recent first)
-            b.created_at.cmp(&a.created_at)
-        } else {
-            label_cmp
+    let key = format!("{}{}", CHECKPOINT_KEY_PREFIX, did);
+    match (local_storage(), serde_json::to_string(&analysis.checkpoint)) {
+        (Some(storage), Ok(json)) => {
+            if storage.set_item(&key, &json).is_err() {
+                log::warn!("Could not save analysis checkpoint for {}", did);
+            }
         }
-    });
+        _ => log::warn!("Could not save analysis checkpoint for {}", did),
+    }
+}
 
-    progress_callback("Analysis complete!".to_string(), 100);
+pub fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
 
-    Ok((
-        BulkAnalysisStats {
-            total_posts: posts.len(),
-            posts_with_labels: posts_with_labels_set.len(),
-            labels_by_category,
-            top_label_values,
-            account_labels,
-        },
-        labeled_posts,
-    ))
+/// Verify a single post against a fresh inclusion proof from its PDS, returning the commit CID
+pub async fn verify_post(uri: &str) -> Result<String, String> {
+    let (did, collection, rkey) = uri
+        .strip_prefix("at://")
+        .and_then(|rest| {
+            let mut parts = rest.splitn(3, '/');
+            Some((parts.next()?, parts.next()?, parts.next()?))
+        })
+        .ok_or_else(|| format!("Not a record URI: {}", uri))?;
+
+    PostClient::new()
+        .verify_record(&Did::new(did.to_string()), collection, rkey)
+        .await
+        .map(|verified| verified.commit_cid.to_string())
+        .map_err(|e| format!("Verification failed: {}", e))
 }
@@ -0,0 +1,423 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Watchlist: subjects re-checked in the background while the app is open
+//!
+//! Each entry keeps a snapshot of its labels whenever they change, so the history reads
+//! as a list of diffs. The list lives in localStorage and survives reloads.
+
+use atproto_client::{Label, LabelCollection, LabelDiff, Subject};
+use chrono::{DateTime, Utc};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+use crate::state::AppState;
+use crate::utils;
+
+/// localStorage key for the watchlist
+const WATCHLIST_KEY: &str = "at-peek:watchlist";
+
+/// Snapshots kept per entry; the oldest are dropped first
+pub const MAX_SNAPSHOTS: usize = 50;
+
+/// Minutes between re-checks of each entry unless changed
+pub const DEFAULT_INTERVAL_MINUTES: u32 = 60;
+
+/// Re-check intervals offered in the UI, in minutes
+pub const INTERVAL_CHOICES: [u32; 5] = [15, 30, 60, 360, 1440];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Watchlist {
+    pub interval_minutes: u32,
+    /// Raise browser notifications for changes (in-app alerts are always shown)
+    pub notify: bool,
+    pub entries: Vec<WatchEntry>,
+}
+
+/// A watched account or record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchEntry {
+    /// Normalized subject: handle, DID or AT-URI
+    pub subject: String,
+    pub added_at: DateTime<Utc>,
+    pub last_checked: Option<DateTime<Utc>>,
+    /// Why the last check failed, cleared by the next successful one
+    pub last_error: Option<String>,
+    /// One per change in labels, oldest first; the first is the baseline
+    pub snapshots: Vec<WatchSnapshot>,
+}
+
+/// Labels in effect on a subject when they last changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchSnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub labels: Vec<Label>,
+    /// Changes since the previous snapshot (empty for the baseline)
+    pub changes: LabelDiff,
+}
+
+/// A change found by a re-check, shown until dismissed
+#[derive(Debug, Clone)]
+pub struct WatchAlert {
+    pub subject: String,
+    pub at: DateTime<Utc>,
+    pub changes: LabelDiff,
+}
+
+impl Default for Watchlist {
+    fn default() -> Self {
+        Self {
+            interval_minutes: DEFAULT_INTERVAL_MINUTES,
+            notify: false,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl Watchlist {
+    /// The saved watchlist, or an empty one
+    pub fn load() -> Self {
+        utils::local_storage()
+            .and_then(|storage| storage.get_item(WATCHLIST_KEY).ok().flatten())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| format!("Could not serialize watchlist: {}", e))?;
+        utils::local_storage()
+            .ok_or_else(|| "Local storage is unavailable".to_string())?
+            .set_item(WATCHLIST_KEY, &json)
+            .map_err(|_| "Could not save the watchlist (storage full?)".to_string())
+    }
+
+    /// Start watching `input`, returning the normalized subject
+    pub fn add(&mut self, input: &str) -> Result<String, String> {
+        let subject = Subject::parse(input)
+            .map_err(|e| e.to_string())?
+            .to_string();
+        if self.entry(&subject).is_some() {
+            return Err(format!("{} is already on the watchlist", subject));
+        }
+
+        self.entries.push(WatchEntry {
+            subject: subject.clone(),
+            added_at: Utc::now(),
+            last_checked: None,
+            last_error: None,
+            snapshots: Vec::new(),
+        });
+        Ok(subject)
+    }
+
+    pub fn remove(&mut self, subject: &str) {
+        self.entries.retain(|entry| entry.subject != subject);
+    }
+
+    pub fn entry(&self, subject: &str) -> Option<&WatchEntry> {
+        self.entries.iter().find(|entry| entry.subject == subject)
+    }
+
+    pub fn entry_mut(&mut self, subject: &str) -> Option<&mut WatchEntry> {
+        self.entries
+            .iter_mut()
+            .find(|entry| entry.subject == subject)
+    }
+
+    /// Subjects not checked within the interval
+    pub fn due(&self, now: DateTime<Utc>) -> Vec<String> {
+        let interval = chrono::Duration::minutes(i64::from(self.interval_minutes));
+        self.entries
+            .iter()
+            .filter(|entry| {
+                entry
+                    .last_checked
+                    .is_none_or(|checked| now - checked >= interval)
+            })
+            .map(|entry| entry.subject.clone())
+            .collect()
+    }
+}
+
+impl WatchEntry {
+    pub fn latest(&self) -> Option<&WatchSnapshot> {
+        self.snapshots.last()
+    }
+
+    /// Record a successful check, returning the changes since the last snapshot
+    pub fn record(&mut self, collection: &LabelCollection, now: DateTime<Utc>) -> LabelDiff {
+        self.last_checked = Some(now);
+        self.last_error = None;
+
+        let labels: Vec<Label> = collection
+            .labels
+            .iter()
+            .filter(|label| !label.neg && !label.is_expired_at(now))
+            .cloned()
+            .collect();
+        let changes = match self.latest() {
+            Some(previous) => LabelDiff::between(
+                &previous.labels,
+                previous.taken_at,
+                &collection.labels,
+                &collection.raw_labels(),
+                now,
+            ),
+            None => LabelDiff::default(),
+        };
+
+        if self.snapshots.is_empty() || !changes.is_empty() {
+            self.snapshots.push(WatchSnapshot {
+                taken_at: now,
+                labels,
+                changes: changes.clone(),
+            });
+            let excess = self.snapshots.len().saturating_sub(MAX_SNAPSHOTS);
+            self.snapshots.drain(..excess);
+        }
+        changes
+    }
+
+    pub fn record_error(&mut self, error: String, now: DateTime<Utc>) {
+        self.last_checked = Some(now);
+        self.last_error = Some(error);
+    }
+}
+
+/// Start watching `input` and take its baseline snapshot
+pub fn watch(state: AppState, input: &str) -> Result<String, String> {
+    let mut added = Err(String::new());
+    state.watchlist.update(|watchlist| {
+        added = watchlist
+            .add(input)
+            .and_then(|subject| watchlist.save().map(|()| subject));
+    });
+    let subject = added?;
+
+    spawn_local(recheck(state, vec![subject.clone()]));
+    Ok(subject)
+}
+
+/// Re-check due entries now and then every minute, for as long as the app is open
+pub fn start_background_checks(state: AppState) {
+    let tick = move || {
+        let due = state
+            .watchlist
+            .with_untracked(|watchlist| watchlist.due(Utc::now()));
+        if !due.is_empty() {
+            spawn_local(recheck(state, due));
+        }
+    };
+
+    tick();
+    match set_interval_with_handle(tick, std::time::Duration::from_secs(60)) {
+        Ok(handle) => on_cleanup(move || handle.clear()),
+        Err(e) => log::warn!("Could not schedule watchlist checks: {:?}", e),
+    }
+}
+
+/// Re-check `subjects` one at a time, saving after each and alerting on changes
+pub async fn recheck(state: AppState, subjects: Vec<String>) {
+    // A manual "check now" during a background pass waits for the next tick
+    if state.watch_checking.get_untracked() {
+        return;
+    }
+    state.watch_checking.set(true);
+
+    for subject in subjects {
+        let result = utils::fetch_labels(&subject, state.auth_token.get_untracked()).await;
+        let now = Utc::now();
+
+        let mut changes = LabelDiff::default();
+        let mut notify = false;
+        state.watchlist.update(|watchlist| {
+            notify = watchlist.notify;
+            // The entry may have been removed while its check was running
+            if let Some(entry) = watchlist.entry_mut(&subject) {
+                match &result {
+                    Ok((_, collection)) => changes = entry.record(collection, now),
+                    Err(e) => entry.record_error(e.clone(), now),
+                }
+            }
+            if let Err(e) = watchlist.save() {
+                log::warn!("{}", e);
+            }
+        });
+
+        if !changes.is_empty() {
+            if notify {
+                send_notification(&subject, &changes);
+            }
+            state.watch_alerts.update(|alerts| {
+                alerts.push(WatchAlert {
+                    subject,
+                    at: now,
+                    changes,
+                })
+            });
+        }
+    }
+
+    state.watch_checking.set(false);
+}
+
+/// Ask for permission to show browser notifications, returning whether it was granted
+pub async fn request_notification_permission() -> bool {
+    if Notification::permission() == NotificationPermission::Granted {
+        return true;
+    }
+    match Notification::request_permission() {
+        Ok(promise) => {
+            let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+            Notification::permission() == NotificationPermission::Granted
+        }
+        Err(e) => {
+            log::warn!("Notifications unavailable: {:?}", e);
+            false
+        }
+    }
+}
+
+fn send_notification(subject: &str, changes: &LabelDiff) {
+    if Notification::permission() != NotificationPermission::Granted {
+        return;
+    }
+
+    let options = NotificationOptions::new();
+    options.set_body(&changes.summary());
+    // One notification per subject; a newer change replaces the older one
+    options.set_tag(subject);
+    if let Err(e) =
+        Notification::new_with_options(&format!("Labels changed on {}", subject), &options)
+    {
+        log::warn!("Could not show notification: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DID: &str = "did:plc:4qqizocrnriintskkh6trnzv";
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        timestamp.parse().unwrap()
+    }
+
+    fn label(val: &str, cts: &str) -> Label {
+        Label {
+            val: val.to_string(),
+            uri: DID.to_string(),
+            cid: None,
+            src: "did:plc:ar7c4by46qjdydhdevvrndac".to_string(),
+            cts: cts.to_string(),
+            exp: None,
+            neg: false,
+            self_applied: false,
+            provenance: None,
+        }
+    }
+
+    fn collection(labels: Vec<Label>, at: DateTime<Utc>) -> LabelCollection {
+        LabelCollection {
+            labels,
+            labeler_did: "did:plc:ar7c4by46qjdydhdevvrndac".to_string(),
+            query_timestamp: at,
+            responses: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn add_normalizes_and_rejects_repeats() {
+        let mut watchlist = Watchlist::default();
+        assert_eq!(
+            watchlist.add("https://bsky.app/profile/Alice.Bsky.Social"),
+            Ok("alice.bsky.social".to_string())
+        );
+        assert!(watchlist.add("@alice.bsky.social").is_err());
+        assert!(watchlist.add("not a subject").is_err());
+        assert_eq!(watchlist.entries.len(), 1);
+    }
+
+    #[test]
+    fn due_after_the_interval() {
+        let mut watchlist = Watchlist {
+            interval_minutes: 60,
+            ..Watchlist::default()
+        };
+        watchlist.add("alice.bsky.social").unwrap();
+        watchlist.add(DID).unwrap();
+        let now = at("2026-03-10T12:00:00Z");
+
+        // Never checked: due right away
+        assert_eq!(watchlist.due(now).len(), 2);
+
+        watchlist.entry_mut(DID).unwrap().last_checked = Some(at("2026-03-10T11:30:00Z"));
+        watchlist
+            .entry_mut("alice.bsky.social")
+            .unwrap()
+            .record_error("offline".to_string(), at("2026-03-10T11:00:00Z"));
+        assert_eq!(watchlist.due(now), vec!["alice.bsky.social".to_string()]);
+        assert_eq!(watchlist.due(at("2026-03-10T12:30:00Z")).len(), 2);
+    }
+
+    #[test]
+    fn record_keeps_a_snapshot_per_change() {
+        let mut watchlist = Watchlist::default();
+        watchlist.add(DID).unwrap();
+        let entry = watchlist.entry_mut(DID).unwrap();
+        entry.last_error = Some("offline".to_string());
+
+        // The first check is the baseline
+        let first = at("2026-03-01T00:00:00Z");
+        let spam = label("spam", "2026-02-01T00:00:00Z");
+        let changes = entry.record(&collection(vec![spam.clone()], first), first);
+        assert!(changes.is_empty());
+        assert_eq!(entry.snapshots.len(), 1);
+        assert_eq!(entry.last_error, None);
+
+        // No change: checked, but no new snapshot
+        let second = at("2026-03-02T00:00:00Z");
+        assert!(entry
+            .record(&collection(vec![spam.clone()], second), second)
+            .is_empty());
+        assert_eq!(entry.snapshots.len(), 1);
+        assert_eq!(entry.last_checked, Some(second));
+
+        let third = at("2026-03-03T00:00:00Z");
+        let rude = label("rude", "2026-03-02T12:00:00Z");
+        let changes = entry.record(&collection(vec![spam, rude], third), third);
+        assert_eq!(changes.summary(), "1 added");
+        assert_eq!(entry.snapshots.len(), 2);
+        assert_eq!(entry.latest().unwrap().labels.len(), 2);
+    }
+
+    #[test]
+    fn record_drops_the_oldest_snapshots() {
+        let mut entry = WatchEntry {
+            subject: DID.to_string(),
+            added_at: at("2026-01-01T00:00:00Z"),
+            last_checked: None,
+            last_error: None,
+            snapshots: Vec::new(),
+        };
+        let start = at("2026-01-01T00:00:00Z");
+        for run in 0..=MAX_SNAPSHOTS {
+            let now = start + chrono::Duration::hours(run as i64);
+            // Alternate between labeled and not, so that every check is a change
+            let labels = if run % 2 == 0 {
+                vec![label("spam", "2025-12-01T00:00:00Z")]
+            } else {
+                Vec::new()
+            };
+            entry.record(&collection(labels, now), now);
+        }
+
+        assert_eq!(entry.snapshots.len(), MAX_SNAPSHOTS);
+        assert_eq!(
+            entry.snapshots[0].taken_at,
+            start + chrono::Duration::hours(1)
+        );
+    }
+}
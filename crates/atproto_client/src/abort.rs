@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Cooperative cancellation for long-running operations
+//!
+//! Runtime-agnostic (no timers or executors), so it works the same under tokio and in
+//! the browser.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use futures::future::{self, Either};
+
+/// Shared flag that stops an operation at its next await point
+///
+/// Clones share the same flag: keep one in the UI and hand another to the engine.
+#[derive(Clone, Default)]
+pub struct AbortHandle {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    aborted: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl AbortHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation; pending [`AbortHandle::run`] calls return `None`
+    pub fn abort(&self) {
+        self.inner.aborted.store(true, Ordering::SeqCst);
+
+        if let Ok(mut wakers) = self.inner.wakers.lock() {
+            wakers.drain(..).for_each(Waker::wake);
+        }
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.inner.aborted.load(Ordering::SeqCst)
+    }
+
+    /// Drive `fut` to completion unless cancellation is requested first
+    pub async fn run<F: Future>(&self, fut: F) -> Option<F::Output> {
+        if self.is_aborted() {
+            return None;
+        }
+
+        let fut = std::pin::pin!(fut);
+        match future::select(fut, Aborted(self)).await {
+            Either::Left((output, _)) => Some(output),
+            Either::Right(((), _)) => None,
+        }
+    }
+}
+
+impl std::fmt::Debug for AbortHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AbortHandle")
+            .field("aborted", &self.is_aborted())
+            .finish()
+    }
+}
+
+/// Resolves once the handle is aborted
+struct Aborted<'a>(&'a AbortHandle);
+
+impl Future for Aborted<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0.is_aborted() {
+            return Poll::Ready(());
+        }
+
+        if let Ok(mut wakers) = self.0.inner.wakers.lock() {
+            if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+        }
+
+        // abort() may have run between the first check and registering the waker
+        if self.0.is_aborted() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,2650 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Bulk label analysis of an account's posts, independent of any UI
+//!
+//! [`BulkAnalyzer`] does the network work (fetching posts, querying labels, engagement);
+//! the aggregation steps are plain functions over already fetched data so they can be
+//! driven from recorded fixtures.
+
+use std::collections::{HashMap, HashSet};
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    resolve_did, resolve_signing_key, AbortHandle, ActorList, AnalysisScope, AppViewClient,
+    AtRecord, Did, Error, Label, LabelCategory, LabelerClient, PostClient, PostThread, Repository,
+    Result, Subject, UserInfo,
+};
+
+/// Default number of most recent records analyzed per account
+pub const DEFAULT_MAX_POSTS: usize = 1000;
+
+/// Default number of requests in flight at once
+///
+/// High enough to finish a 1000-post account in seconds, low enough to stay clear of
+/// the labeler's and AppView's rate limits.
+pub const DEFAULT_CONCURRENCY: usize = 6;
+
+/// Records per listRecords page
+const LIST_PAGE_SIZE: u32 = 100;
+
+/// Subjects per queryLabels request
+const LABEL_BATCH_SIZE: usize = 25;
+
+/// Unlabeled posts still shown for accounts with account-level labels
+const MODERATED_ACCOUNT_SAMPLE: usize = 10;
+
+/// Most labels a wildcard sweep reads per account
+pub const MAX_SWEEP_LABELS: usize = 10_000;
+
+/// Default number of follows and of followers a network scan reads
+pub const DEFAULT_MAX_CONNECTIONS: usize = 5000;
+
+/// Default number of members a list inspection reads
+pub const DEFAULT_MAX_LIST_MEMBERS: usize = 10_000;
+
+/// Hours after which an interrupted analysis starts over instead of resuming
+pub const CHECKPOINT_TTL_HOURS: i64 = 24;
+
+/// Likers, and reposters, listed per post by default: one AppView page each
+pub const DEFAULT_ENGAGEMENT_ACCOUNTS: usize = 100;
+
+/// Most likers, and most reposters, [`BulkAnalyzer::with_engagement_accounts`] allows
+pub const MAX_ENGAGEMENT_ACCOUNTS: usize = 10_000;
+
+/// Accounts listed by DID, with the profile if the listing had one, and whether the list
+/// was cut short
+type Listing = (Vec<(String, Option<UserInfo>)>, bool);
+
+/// Something that happened during an analysis, reported as soon as it is known
+#[derive(Debug, Clone)]
+pub enum AnalysisEvent {
+    /// Status message with overall percent complete
+    Progress { message: String, percent: u8 },
+    /// Statistics over the posts checked so far
+    Stats(BulkAnalysisStats),
+    /// A post selected for display, before its engagement is fetched
+    PostFound(PostWithLabels),
+    /// A previously found post with its likers and reposters filled in
+    PostEnriched(PostWithLabels),
+}
+
+/// Receives [`AnalysisEvent`]s during an analysis
+pub trait ProgressReporter {
+    fn report(&mut self, event: AnalysisEvent);
+
+    /// Report a status message
+    fn progress(&mut self, message: String, percent: u8) {
+        self.report(AnalysisEvent::Progress { message, percent });
+    }
+}
+
+impl<F: FnMut(AnalysisEvent)> ProgressReporter for F {
+    fn report(&mut self, event: AnalysisEvent) {
+        self(event)
+    }
+}
+
+/// Aggregate statistics over the analyzed posts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkAnalysisStats {
+    pub total_posts: usize,
+    /// Posts labeled by a labeler service (moderation or third-party labels)
+    pub posts_with_labels: usize,
+    /// Posts carrying author-applied self-labels
+    pub self_labeled_posts: usize,
+    pub labels_by_category: HashMap<LabelCategory, usize>,
+    pub top_label_values: Vec<(String, usize)>,
+    pub account_labels: Vec<Label>,
+    /// Expired labels left out of the counts above (zero when expired labels are included)
+    #[serde(default)]
+    pub expired_labels: usize,
+}
+
+/// A post selected for display, with its labels and engagement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostWithLabels {
+    pub uri: String,
+    /// NSID of the analyzed record; a liked or reposted post has the like's or repost's
+    #[serde(default)]
+    pub collection: String,
+    pub text: String,
+    pub labels: Vec<Label>,
+    pub created_at: String,
+    pub has_media: bool,
+    pub image_urls: Vec<String>,
+    pub video_url: Option<String>,
+    /// Likes and reposts as the AppView counts them, which can exceed the accounts listed
+    pub like_count: usize,
+    pub repost_count: usize,
+    pub likers: Vec<UserInfo>,
+    pub reposters: Vec<UserInfo>,
+    /// CID of the signed repo commit this post was verified against, if any
+    pub verified_commit: Option<String>,
+    /// Whether there were more likers or reposters than the analyzer lists per post
+    #[serde(default)]
+    pub engagement_truncated: bool,
+    /// Why the counts, likers or reposters couldn't be fetched, if they couldn't
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engagement_error: Option<String>,
+    /// Account labels on the likers and reposters, once queried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engagement_labels: Option<Vec<Label>>,
+}
+
+impl PostWithLabels {
+    /// Likers and reposters carrying account labels, each once, with their labels
+    pub fn labeled_engagers(&self) -> Vec<(&UserInfo, Vec<&Label>)> {
+        let Some(labels) = &self.engagement_labels else {
+            return Vec::new();
+        };
+
+        let mut seen = HashSet::new();
+        self.likers
+            .iter()
+            .chain(&self.reposters)
+            .filter(|user| seen.insert(user.did.as_str()))
+            .filter_map(|user| {
+                let on_user: Vec<&Label> = labels
+                    .iter()
+                    .filter(|label| label.uri == user.did)
+                    .collect();
+                (!on_user.is_empty()).then_some((user, on_user))
+            })
+            .collect()
+    }
+}
+
+/// Result of a bulk analysis run
+///
+/// A cancelled or failed run still returns whatever was checked up to that point, with
+/// `complete` unset and a checkpoint to resume from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkAnalysis {
+    pub stats: BulkAnalysisStats,
+    /// Labeled posts, most labeled first
+    pub labeled_posts: Vec<PostWithLabels>,
+    /// Whether the account and every fetched post were checked
+    pub complete: bool,
+    /// Why an incomplete run stopped early
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interruption: Option<String>,
+    /// Fetched posts whose labels have not been checked yet
+    pub pending_posts: usize,
+    /// Progress so far, for [`BulkAnalyzer::with_checkpoint`]
+    #[serde(skip)]
+    pub checkpoint: AnalysisCheckpoint,
+}
+
+/// Label queries already answered for an account, so an interrupted run can resume
+///
+/// Only resumed by a run with the same scope within [`CHECKPOINT_TTL_HOURS`] of the run
+/// that started it; after that labels may have changed, so starting over is cheaper than
+/// being wrong.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnalysisCheckpoint {
+    pub did: String,
+    /// When the first run of this analysis started (missing on old checkpoints, which
+    /// are therefore treated as expired)
+    #[serde(default)]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Records the run covered; a run with another scope checks different posts
+    #[serde(default)]
+    pub scope: AnalysisScope,
+    /// Account-level labels, once they have been queried
+    pub account_labels: Option<Vec<Label>>,
+    /// Post URIs whose labels have been queried (the resume cursor)
+    pub queried_uris: Vec<String>,
+    /// Labeler-applied labels found on those posts
+    pub labels: Vec<Label>,
+}
+
+impl AnalysisCheckpoint {
+    /// Empty checkpoint for a run over `scope` of an account's records
+    pub fn new(did: &Did, scope: &AnalysisScope) -> Self {
+        Self {
+            did: did.as_str().to_string(),
+            created_at: chrono::Utc::now(),
+            scope: scope.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// Why a run over `scope` of `did`'s records can't resume from this checkpoint
+    pub fn stale_reason(
+        &self,
+        did: &Did,
+        scope: &AnalysisScope,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<String> {
+        if self.did != did.as_str() {
+            Some(format!("it is for {}", self.did))
+        } else if self.scope != *scope {
+            Some("it covers a different scope".to_string())
+        } else if now - self.created_at >= chrono::Duration::hours(CHECKPOINT_TTL_HOURS) {
+            Some(format!(
+                "it was started at {}",
+                self.created_at.to_rfc3339()
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether the record a label is on is still in the account's repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordStatus {
+    /// The label is on the account itself, not a record
+    Account,
+    Present,
+    /// Deleted (or never existed): the label outlives the record
+    Deleted,
+    /// The PDS couldn't tell us
+    Unknown,
+}
+
+impl RecordStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecordStatus::Account => "Account",
+            RecordStatus::Present => "Present",
+            RecordStatus::Deleted => "Deleted",
+            RecordStatus::Unknown => "Unknown",
+        }
+    }
+}
+
+/// A label found by a wildcard sweep, with the state of the record it is on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweptLabel {
+    #[serde(flatten)]
+    pub label: Label,
+    pub record_status: RecordStatus,
+}
+
+/// Every label under an account, from one `at://<did>/*` query instead of per-record ones
+///
+/// Unlike [`BulkAnalysis`] this finds labels on deleted records and on records in any
+/// collection, but knows nothing about the records beyond whether they still exist.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountSweep {
+    pub did: String,
+    /// Oldest first
+    pub labels: Vec<SweptLabel>,
+    /// Whether the labeler had more than [`MAX_SWEEP_LABELS`] labels and the rest were skipped
+    pub truncated: bool,
+}
+
+impl AccountSweep {
+    /// Labels whose record is no longer in the repository
+    pub fn on_deleted_records(&self) -> impl Iterator<Item = &SweptLabel> {
+        self.labels
+            .iter()
+            .filter(|swept| swept.record_status == RecordStatus::Deleted)
+    }
+}
+
+/// Outcome of checking one subject in a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Labeled,
+    Clean,
+    /// Unrecognized, unresolvable, or its label query failed
+    Failed,
+}
+
+impl CheckStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CheckStatus::Labeled => "Labeled",
+            CheckStatus::Clean => "Clean",
+            CheckStatus::Failed => "Failed",
+        }
+    }
+}
+
+/// Labels on one subject of a batch check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubjectCheck {
+    /// As pasted or read from the file
+    pub input: String,
+    /// Resolved label subject (DID or AT-URI), if resolution got that far
+    pub subject: Option<String>,
+    /// Labels in effect (or all of them, with the scope's `include_expired`)
+    pub labels: Vec<Label>,
+    /// Expired labels left out of `labels`
+    #[serde(default)]
+    pub expired_labels: usize,
+    pub error: Option<String>,
+}
+
+impl SubjectCheck {
+    fn new(input: &str) -> Self {
+        Self {
+            input: input.to_string(),
+            subject: None,
+            labels: Vec::new(),
+            expired_labels: 0,
+            error: None,
+        }
+    }
+
+    pub fn status(&self) -> CheckStatus {
+        if self.error.is_some() {
+            CheckStatus::Failed
+        } else if self.labels.is_empty() {
+            CheckStatus::Clean
+        } else {
+            CheckStatus::Labeled
+        }
+    }
+}
+
+/// How an account is connected to the one a network scan is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Connection {
+    /// Followed by the scanned account
+    Follows,
+    /// Follows the scanned account
+    Followers,
+}
+
+impl Connection {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Connection::Follows => "Follows",
+            Connection::Followers => "Followers",
+        }
+    }
+}
+
+/// An account followed by or following the scanned account, with its account labels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectedAccount {
+    pub did: String,
+    /// Missing for follows the AppView has no profile for (taken down, deactivated)
+    pub handle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// Followed by the scanned account
+    pub followed: bool,
+    /// Follows the scanned account
+    pub follower: bool,
+    /// Account-level labels in effect (or all of them, with the scope's `include_expired`)
+    pub labels: Vec<Label>,
+}
+
+impl ConnectedAccount {
+    pub fn is_mutual(&self) -> bool {
+        self.followed && self.follower
+    }
+}
+
+/// Account labels across an account's follows and followers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkScan {
+    pub did: String,
+    /// In listing order: follows (newest first), then followers not already followed
+    pub accounts: Vec<ConnectedAccount>,
+    pub follows: usize,
+    pub followers: usize,
+    /// Whether a list had more accounts than the scan's limit and the rest were skipped
+    pub truncated: bool,
+    /// Accounts whose label query failed
+    pub unchecked: usize,
+    /// Expired labels left out of the accounts' labels
+    pub expired_labels: usize,
+    /// Why the scan stopped before every account was checked
+    pub interruption: Option<String>,
+}
+
+impl NetworkScan {
+    pub fn labeled(&self) -> impl Iterator<Item = &ConnectedAccount> {
+        self.accounts
+            .iter()
+            .filter(|account| !account.labels.is_empty())
+    }
+
+    /// Label values with the number of accounts carrying each, most common first
+    pub fn label_counts(&self) -> Vec<(String, usize)> {
+        count_label_values(
+            self.accounts
+                .iter()
+                .map(|account| account.labels.as_slice()),
+        )
+    }
+}
+
+/// What kind of list a list inspection covered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListKind {
+    /// `app.bsky.graph.defs#modlist`: members are muted or blocked by subscribers
+    Moderation,
+    /// `app.bsky.graph.defs#curatelist`
+    Curation,
+    /// `app.bsky.graph.defs#referencelist`, as used by starter packs
+    Reference,
+    /// `app.bsky.graph.starterpack`, inspected through its reference list
+    StarterPack,
+}
+
+impl ListKind {
+    /// Kind of a list record from its `purpose`
+    pub fn from_purpose(purpose: &str) -> Self {
+        match purpose.rsplit('#').next() {
+            Some("modlist") => ListKind::Moderation,
+            Some("referencelist") => ListKind::Reference,
+            _ => ListKind::Curation,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ListKind::Moderation => "Moderation list",
+            ListKind::Curation => "Curation list",
+            ListKind::Reference => "Reference list",
+            ListKind::StarterPack => "Starter pack",
+        }
+    }
+}
+
+/// A member of an inspected list, with its account labels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListMember {
+    pub did: String,
+    /// Missing for members the AppView has no profile for (taken down, deactivated)
+    pub handle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// Account-level labels in effect (or all of them, with the scope's `include_expired`)
+    pub labels: Vec<Label>,
+}
+
+/// Labels on a list or starter pack and on every member
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListInspection {
+    /// The list or starter pack inspected
+    pub uri: String,
+    /// The list holding the members; the same as `uri` unless this is a starter pack
+    pub list_uri: String,
+    pub kind: ListKind,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Labels on the list or starter pack record itself (and a pack's list), self-labels
+    /// included
+    pub labels: Vec<Label>,
+    /// In the order the list items were created, newest first
+    pub members: Vec<ListMember>,
+    /// Whether the list had more members than the limit and the rest were skipped
+    pub truncated: bool,
+    /// Members whose label query failed
+    pub unchecked: usize,
+    /// Expired labels left out
+    pub expired_labels: usize,
+    /// Why labeling stopped before every member was checked
+    pub interruption: Option<String>,
+}
+
+impl ListInspection {
+    pub fn labeled_members(&self) -> impl Iterator<Item = &ListMember> {
+        self.members
+            .iter()
+            .filter(|member| !member.labels.is_empty())
+    }
+
+    /// Label values with the number of members carrying each, most common first
+    pub fn label_counts(&self) -> Vec<(String, usize)> {
+        count_label_values(self.members.iter().map(|member| member.labels.as_slice()))
+    }
+}
+
+/// Runs bulk analyses against the network
+#[derive(Clone)]
+pub struct BulkAnalyzer {
+    labeler: LabelerClient,
+    posts: PostClient,
+    appview: AppViewClient,
+    scope: AnalysisScope,
+    fetch_engagement: bool,
+    engagement_accounts: usize,
+    label_engagement: bool,
+    concurrency: usize,
+    abort: AbortHandle,
+    checkpoint: Option<AnalysisCheckpoint>,
+}
+
+impl BulkAnalyzer {
+    /// Create an analyzer querying Bluesky's labeler, authenticated if a token is given
+    pub fn new(auth_token: Option<String>) -> Self {
+        let labeler = match auth_token {
+            Some(token) => LabelerClient::new_authenticated(token),
+            None => {
+                log::warn!(
+                    "Using UNAUTHENTICATED labeler client - admin labels will NOT be visible!"
+                );
+                LabelerClient::new()
+            }
+        };
+
+        Self {
+            labeler,
+            posts: PostClient::new(),
+            appview: AppViewClient::new(),
+            scope: AnalysisScope::default(),
+            fetch_engagement: true,
+            engagement_accounts: DEFAULT_ENGAGEMENT_ACCOUNTS,
+            label_engagement: false,
+            concurrency: DEFAULT_CONCURRENCY,
+            abort: AbortHandle::new(),
+            checkpoint: None,
+        }
+    }
+
+    /// Analyze the records selected by `scope` (by default, the 1000 most recent posts)
+    pub fn with_scope(mut self, scope: AnalysisScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Analyze at most this many of the most recent records in scope
+    pub fn with_max_posts(mut self, max_posts: usize) -> Self {
+        self.scope.max_records = Some(max_posts);
+        self
+    }
+
+    /// Whether to fetch like and repost counts, likers and reposters for each labeled post
+    pub fn with_engagement(mut self, fetch_engagement: bool) -> Self {
+        self.fetch_engagement = fetch_engagement;
+        self
+    }
+
+    /// Likers, and reposters, to list per post (by default [`DEFAULT_ENGAGEMENT_ACCOUNTS`],
+    /// at most [`MAX_ENGAGEMENT_ACCOUNTS`])
+    ///
+    /// Each 100 accounts cost an AppView request per list, so raising this multiplies the
+    /// requests made for every displayed post.
+    pub fn with_engagement_accounts(mut self, max_accounts: usize) -> Self {
+        self.engagement_accounts = max_accounts.min(MAX_ENGAGEMENT_ACCOUNTS);
+        self
+    }
+
+    /// Whether to also query account labels on every liker and reposter fetched
+    pub fn with_engagement_labels(mut self, label_engagement: bool) -> Self {
+        self.label_engagement = label_engagement;
+        self
+    }
+
+    /// Maximum number of label or engagement requests in flight at once (at least 1)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Stop the analysis when `abort` is triggered, keeping the results gathered so far
+    pub fn with_abort(mut self, abort: AbortHandle) -> Self {
+        self.abort = abort;
+        self
+    }
+
+    /// Skip label queries already answered by an earlier, interrupted run
+    ///
+    /// Ignored if the checkpoint belongs to a different account or scope, or has expired
+    /// (see [`AnalysisCheckpoint::stale_reason`]).
+    pub fn with_checkpoint(mut self, checkpoint: AnalysisCheckpoint) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Analyze an account's records in scope, downloading its repository from the PDS
+    ///
+    /// Falls back to paging through `listRecords` if the repository can't be downloaded.
+    pub async fn analyze_account(
+        &self,
+        did: &Did,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<BulkAnalysis> {
+        // Note: Banned/suspended accounts may be inaccessible
+        progress.progress("Downloading repository from PDS...".to_string(), 10);
+
+        let fetched = self.abort.run(self.posts.fetch_repo(did)).await;
+        let (posts, verified_commit) = match fetched.ok_or(Error::Cancelled)? {
+            // One getRepo request replaces up to ten listRecords pages
+            Ok(repo) => {
+                progress.progress("Verifying repository signature...".to_string(), 15);
+                let verified_commit = verify_repo(&repo, did).await;
+                (self.scoped_records(&repo)?, verified_commit)
+            }
+            // The PDS answered with another account's repository; its listRecords can't
+            // be trusted for this one either
+            Err(e @ Error::Verification(_)) => return Err(e),
+            Err(e) => {
+                log::warn!(
+                    "Repository download failed, falling back to listRecords: {}",
+                    e
+                );
+                progress.progress("Fetching records from PDS...".to_string(), 15);
+                let records = self.abort.run(self.list_scoped_records(did));
+                (records.await.ok_or(Error::Cancelled)??, None)
+            }
+        };
+
+        self.analyze_posts(did, posts, verified_commit, progress)
+            .await
+    }
+
+    /// Analyze a repository that is already in memory (e.g. an offline export)
+    pub async fn analyze_repository(
+        &self,
+        repo: &Repository,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<BulkAnalysis> {
+        let did = repo.did();
+        if !did.validate() {
+            return Err(Error::InvalidDid(format!(
+                "Repository commit has an invalid DID: {}",
+                did
+            )));
+        }
+
+        // Exports can be edited after download, so only trust them if the signature holds
+        progress.progress("Verifying repository signature...".to_string(), 15);
+        let verified_commit = verify_repo(repo, &did).await;
+
+        let posts = self.scoped_records(repo)?;
+        log::info!("Loaded {} records for {} from repository", posts.len(), did);
+
+        self.analyze_posts(&did, posts, verified_commit, progress)
+            .await
+    }
+
+    /// Query labels for already fetched posts and build stats plus the labeled post list
+    ///
+    /// Stops early if the abort handle fires or the labeler rejects our credentials; the
+    /// result then covers only the posts checked so far.
+    pub async fn analyze_posts(
+        &self,
+        did: &Did,
+        posts: Vec<AtRecord>,
+        verified_commit: Option<String>,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<BulkAnalysis> {
+        progress.progress(
+            format!("Fetched {} posts, querying labels...", posts.len()),
+            20,
+        );
+
+        let stale = self
+            .checkpoint
+            .as_ref()
+            .map(|checkpoint| checkpoint.stale_reason(did, &self.scope, chrono::Utc::now()));
+        let mut checkpoint = match (&self.checkpoint, stale.flatten()) {
+            (Some(checkpoint), None) => checkpoint.clone(),
+            (Some(_), Some(reason)) => {
+                log::warn!("Not resuming from checkpoint: {}", reason);
+                AnalysisCheckpoint::new(did, &self.scope)
+            }
+            (None, _) => AnalysisCheckpoint::new(did, &self.scope),
+        };
+        let mut interruption = None;
+
+        // First, check for account-level labels on the DID itself
+        if checkpoint.account_labels.is_none() {
+            progress.progress("Checking account-level labels...".to_string(), 25);
+            let subject = [did.as_str().to_string()];
+            match self.abort.run(self.query_batch(&subject)).await {
+                Some(Ok(labels)) => checkpoint.account_labels = Some(labels),
+                Some(Err(e)) => interruption = stop_reason(e),
+                None => interruption = Some(Error::Cancelled.to_string()),
+            }
+        }
+
+        let pending: Vec<String> = {
+            let queried: HashSet<&str> =
+                checkpoint.queried_uris.iter().map(String::as_str).collect();
+            posts
+                .iter()
+                .map(|post| &post.uri)
+                .filter(|uri| !queried.contains(uri.as_str()))
+                .cloned()
+                .collect()
+        };
+        if pending.len() < posts.len() {
+            log::info!(
+                "Resuming: {} of {} posts already checked",
+                posts.len() - pending.len(),
+                posts.len()
+            );
+        }
+
+        // Blob URLs point at the PDS; resolve it once rather than per post. A cancelled
+        // lookup leaves the posts without media, and the label batches below stop at once
+        let pds_endpoint = if posts.iter().any(has_embed) {
+            self.abort.run(resolve_did(did)).await.and_then(Result::ok)
+        } else {
+            None
+        };
+        let display = |post: &AtRecord, labels: Vec<Label>| {
+            let mut labeled = to_post_with_labels(post, labels, did, pds_endpoint.as_deref());
+            // Liked and reposted posts live in other repositories
+            if post.author_did().as_ref() == Some(did) {
+                labeled.verified_commit = verified_commit.clone();
+            }
+            labeled
+        };
+
+        let by_uri: HashMap<&str, &AtRecord> =
+            posts.iter().map(|post| (post.uri.as_str(), post)).collect();
+        let batch_posts = |uris: &[String]| -> Vec<&AtRecord> {
+            uris.iter()
+                .filter_map(|uri| by_uri.get(uri.as_str()).copied())
+                .collect()
+        };
+        // The checkpoint keeps every label, so expiry is applied afresh on each run
+        let include_expired = self.scope.include_expired;
+        let now = chrono::Utc::now();
+        let (account_labels, expired_account_labels) = in_effect(
+            checkpoint.account_labels.as_deref().unwrap_or_default(),
+            include_expired,
+            now,
+        );
+        let mut tally = Tally {
+            include_expired,
+            now,
+            expired: expired_account_labels,
+            ..Tally::default()
+        };
+
+        // Posts checked by an earlier run count as the first batch
+        if !checkpoint.queried_uris.is_empty() {
+            let found = tally.add(batch_posts(&checkpoint.queried_uris), &checkpoint.labels);
+            tally.report(found, &account_labels, display, progress);
+        }
+
+        let total_batches = pending.len().div_ceil(LABEL_BATCH_SIZE);
+        let mut batches = stream::iter(pending.chunks(LABEL_BATCH_SIZE))
+            .map(|chunk| async move { (chunk, self.query_batch(chunk).await) })
+            .buffer_unordered(self.concurrency);
+        let mut batches_done = 0;
+
+        while interruption.is_none() {
+            let (chunk, batch) = match self.abort.run(batches.next()).await {
+                Some(Some(done)) => done,
+                Some(None) => break,
+                None => {
+                    interruption = Some(Error::Cancelled.to_string());
+                    break;
+                }
+            };
+            batches_done += 1;
+
+            // Failed batches stay unqueried so that a resumed run retries them
+            match batch {
+                Ok(labels) => {
+                    let found = tally.add(batch_posts(chunk), &labels);
+                    tally.report(found, &account_labels, display, progress);
+
+                    checkpoint.queried_uris.extend_from_slice(chunk);
+                    checkpoint.labels.extend(labels);
+                }
+                Err(e) => interruption = stop_reason(e),
+            }
+
+            // Progress from 30% to 85% across all batches
+            let batch_progress = 30 + ((batches_done as f32 / total_batches as f32) * 55.0) as u8;
+            progress.progress(
+                format!(
+                    "Querying mod.bsky.app: batch {}/{}...",
+                    batches_done, total_batches
+                ),
+                batch_progress,
+            );
+        }
+        drop(batches);
+
+        log::info!(
+            "Total labels found across all batches: {}",
+            checkpoint.labels.len()
+        );
+
+        progress.progress("Analyzing results...".to_string(), 90);
+
+        // Only report on posts that were actually checked, newest first
+        let (checked, pending_posts) = {
+            let queried: HashSet<&str> =
+                checkpoint.queried_uris.iter().map(String::as_str).collect();
+            let (checked, pending): (Vec<&AtRecord>, Vec<&AtRecord>) = posts
+                .iter()
+                .partition(|post| queried.contains(post.uri.as_str()));
+            let checked: Vec<AtRecord> = checked.into_iter().cloned().collect();
+            (checked, pending.len())
+        };
+
+        let complete = checkpoint.account_labels.is_some() && pending_posts == 0;
+        if !complete && interruption.is_none() {
+            interruption = Some(if pending_posts > 0 {
+                format!("{} posts could not be checked", pending_posts)
+            } else {
+                "Account labels could not be checked".to_string()
+            });
+        }
+
+        let stats = tally.stats(&checked, &account_labels);
+        let selected = select_posts(&checked, &tally.labels, !stats.account_labels.is_empty());
+        progress.report(AnalysisEvent::Stats(stats.clone()));
+
+        // Labeled posts were reported as their batches came in; the sample of unlabeled
+        // posts shown for moderated accounts is only known now
+        for (post, _) in selected.iter().filter(|(_, labels)| labels.is_empty()) {
+            progress.report(AnalysisEvent::PostFound(display(post, Vec::new())));
+        }
+
+        let total_selected = selected.len();
+        let mut posts_stream = stream::iter(selected)
+            .map(|(post, post_labels)| {
+                let mut labeled = display(post, post_labels);
+
+                // A cancelled lookup leaves the post without engagement, but keeps it
+                async move {
+                    if self.fetch_engagement && is_post(&labeled.uri) {
+                        self.abort.run(self.add_engagement(&mut labeled)).await;
+                    }
+                    labeled
+                }
+            })
+            .buffer_unordered(self.concurrency);
+
+        let mut labeled_posts = Vec::with_capacity(total_selected);
+        while let Some(labeled) = posts_stream.next().await {
+            if self.fetch_engagement {
+                progress.report(AnalysisEvent::PostEnriched(labeled.clone()));
+            }
+            labeled_posts.push(labeled);
+
+            // Update progress from 90% to 99% as we process posts
+            let process_progress =
+                90 + ((labeled_posts.len() as f32 / total_selected as f32) * 9.0) as u8;
+            progress.progress(
+                format!(
+                    "Processing posts ({}/{})...",
+                    labeled_posts.len(),
+                    total_selected
+                ),
+                process_progress,
+            );
+        }
+
+        sort_labeled_posts(&mut labeled_posts);
+
+        match &interruption {
+            Some(reason) => progress.progress(format!("Analysis stopped: {}", reason), 100),
+            None => progress.progress("Analysis complete!".to_string(), 100),
+        }
+
+        Ok(BulkAnalysis {
+            stats,
+            labeled_posts,
+            complete,
+            interruption,
+            pending_posts,
+            checkpoint,
+        })
+    }
+
+    /// Query every label under an account with a wildcard pattern, then check which of the
+    /// labeled records still exist
+    ///
+    /// Ignores the scope: the point is to find labels the scoped record list can't see.
+    pub async fn sweep_account(
+        &self,
+        did: &Did,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<AccountSweep> {
+        progress.progress("Querying all labels under the account...".to_string(), 10);
+
+        let patterns = [did.as_str().to_string(), format!("at://{}/*", did)];
+        let query = self.labeler.query_all_labels(&patterns, MAX_SWEEP_LABELS);
+        let collection = self.abort.run(query).await.ok_or(Error::Cancelled)??;
+        let raw_labels: usize = collection
+            .responses
+            .iter()
+            .filter_map(|response| response.body.get("labels")?.as_array().map(Vec::len))
+            .sum();
+        log::info!(
+            "Sweep found {} active labels under {}",
+            collection.labels.len(),
+            did
+        );
+
+        let mut record_uris: Vec<String> = collection
+            .labels
+            .iter()
+            .filter(|label| label.uri.starts_with("at://"))
+            .map(|label| label.uri.clone())
+            .collect();
+        record_uris.sort();
+        record_uris.dedup();
+
+        progress.progress(
+            format!(
+                "Checking whether {} labeled records still exist...",
+                record_uris.len()
+            ),
+            50,
+        );
+        let statuses = self.record_statuses(did, &record_uris).await?;
+
+        let mut labels: Vec<SweptLabel> = collection
+            .labels
+            .into_iter()
+            .map(|label| {
+                let record_status = if label.uri.starts_with("at://") {
+                    statuses
+                        .get(&label.uri)
+                        .copied()
+                        .unwrap_or(RecordStatus::Unknown)
+                } else {
+                    RecordStatus::Account
+                };
+                SweptLabel {
+                    label,
+                    record_status,
+                }
+            })
+            .collect();
+        labels.sort_by(|a, b| a.label.cts.cmp(&b.label.cts));
+
+        progress.progress("Sweep complete".to_string(), 100);
+
+        Ok(AccountSweep {
+            did: did.as_str().to_string(),
+            labels,
+            truncated: raw_labels >= MAX_SWEEP_LABELS,
+        })
+    }
+
+    /// Check many subjects at once: resolve them concurrently, then query their labels in
+    /// batches of [`LABEL_BATCH_SIZE`] subjects
+    ///
+    /// Failures are reported per subject. A cancelled run keeps what was checked so far and
+    /// marks the rest as not checked.
+    pub async fn check_subjects(
+        &self,
+        inputs: &[String],
+        progress: &mut impl ProgressReporter,
+    ) -> Vec<SubjectCheck> {
+        let now = chrono::Utc::now();
+        let mut checks: Vec<SubjectCheck> = inputs
+            .iter()
+            .map(|input| SubjectCheck::new(input))
+            .collect();
+        let mut cancelled = false;
+
+        progress.progress(format!("Resolving {} subjects...", inputs.len()), 5);
+        let mut resolving = stream::iter(inputs.iter().enumerate())
+            .map(|(index, input)| async move {
+                let resolved = match Subject::parse(input) {
+                    Ok(subject) => subject.resolve().await.map(|(subject, _)| subject),
+                    Err(e) => Err(e),
+                };
+                (index, resolved)
+            })
+            .buffer_unordered(self.concurrency);
+        let mut resolved = 0;
+        loop {
+            let (index, result) = match self.abort.run(resolving.next()).await {
+                Some(Some(done)) => done,
+                Some(None) => break,
+                None => {
+                    cancelled = true;
+                    break;
+                }
+            };
+            match result {
+                Ok(subject) => checks[index].subject = Some(subject),
+                Err(e) => checks[index].error = Some(e.to_string()),
+            }
+
+            // Progress from 5% to 45% across all subjects
+            resolved += 1;
+            progress.progress(
+                format!("Resolved {}/{} subjects...", resolved, inputs.len()),
+                5 + ((resolved as f32 / inputs.len() as f32) * 40.0) as u8,
+            );
+        }
+        drop(resolving);
+
+        // A handle and its DID resolve to the same subject, which is queried once
+        let mut subjects: Vec<String> = checks
+            .iter()
+            .filter_map(|check| check.subject.clone())
+            .collect();
+        subjects.sort();
+        subjects.dedup();
+
+        let mut outcomes: HashMap<&str, std::result::Result<Vec<Label>, String>> = HashMap::new();
+        let total_batches = subjects.len().div_ceil(LABEL_BATCH_SIZE);
+        let mut batches = stream::iter(subjects.chunks(LABEL_BATCH_SIZE))
+            .map(|chunk| async move { (chunk, self.query_batch(chunk).await) })
+            .buffer_unordered(self.concurrency);
+        let mut batches_done = 0;
+        while !cancelled {
+            let (chunk, batch) = match self.abort.run(batches.next()).await {
+                Some(Some(done)) => done,
+                Some(None) => break,
+                None => {
+                    cancelled = true;
+                    break;
+                }
+            };
+            for subject in chunk {
+                let outcome = match &batch {
+                    Ok(labels) => Ok(labels
+                        .iter()
+                        .filter(|label| &label.uri == subject)
+                        .cloned()
+                        .collect()),
+                    Err(e) => Err(e.to_string()),
+                };
+                outcomes.insert(subject, outcome);
+            }
+
+            // Progress from 45% to 95% across all batches
+            batches_done += 1;
+            progress.progress(
+                format!(
+                    "Querying mod.bsky.app: batch {}/{}...",
+                    batches_done, total_batches
+                ),
+                45 + ((batches_done as f32 / total_batches as f32) * 50.0) as u8,
+            );
+        }
+        drop(batches);
+
+        for check in checks.iter_mut().filter(|check| check.error.is_none()) {
+            match check
+                .subject
+                .as_deref()
+                .and_then(|subject| outcomes.get(subject))
+            {
+                Some(Ok(labels)) => {
+                    let (labels, expired) = in_effect(labels, self.scope.include_expired, now);
+                    check.labels = labels;
+                    check.expired_labels = expired;
+                }
+                Some(Err(e)) => check.error = Some(e.clone()),
+                None => check.error = Some(format!("Not checked ({})", Error::Cancelled)),
+            }
+        }
+
+        let message = if cancelled {
+            "Batch check cancelled"
+        } else {
+            "Batch check complete"
+        };
+        progress.progress(message.to_string(), 100);
+        checks
+    }
+
+    /// Query account labels on the accounts `did` follows and is followed by
+    ///
+    /// Follows are read from the account's `app.bsky.graph.follow` records, which still
+    /// point at taken down accounts the AppView leaves out; followers can only come from
+    /// the AppView. Each list stops after `max_accounts`. Cancelling while the accounts'
+    /// handles are looked up keeps the accounts listed, all of them unchecked.
+    pub async fn scan_network(
+        &self,
+        did: &Did,
+        connections: &[Connection],
+        max_accounts: usize,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<NetworkScan> {
+        let mut scan = NetworkScan {
+            did: did.as_str().to_string(),
+            ..NetworkScan::default()
+        };
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for &connection in connections {
+            progress.progress(
+                format!("Listing {}...", connection.name().to_lowercase()),
+                if connection == Connection::Follows {
+                    5
+                } else {
+                    20
+                },
+            );
+            let (actors, truncated) = self
+                .list_connections(did, connection, max_accounts, progress)
+                .await?;
+            scan.truncated |= truncated;
+            match connection {
+                Connection::Follows => scan.follows = actors.len(),
+                Connection::Followers => scan.followers = actors.len(),
+            }
+
+            for (did, user) in actors {
+                let position = *index.entry(did.clone()).or_insert_with(|| {
+                    scan.accounts.push(ConnectedAccount {
+                        did,
+                        handle: None,
+                        display_name: None,
+                        followed: false,
+                        follower: false,
+                        labels: Vec::new(),
+                    });
+                    scan.accounts.len() - 1
+                });
+                let account = &mut scan.accounts[position];
+                match connection {
+                    Connection::Follows => account.followed = true,
+                    Connection::Followers => account.follower = true,
+                }
+                if let Some(user) = user {
+                    account.handle = Some(user.handle);
+                    account.display_name = user.display_name;
+                }
+            }
+        }
+
+        // Follow records only have DIDs
+        let missing: Vec<String> = scan
+            .accounts
+            .iter()
+            .filter(|account| account.handle.is_none())
+            .map(|account| account.did.clone())
+            .collect();
+        if !missing.is_empty() {
+            progress.progress(format!("Looking up {} handles...", missing.len()), 35);
+            match self.lookup_profiles(&missing).await {
+                Ok(users) => {
+                    for user in users {
+                        if let Some(&position) = index.get(&user.did) {
+                            scan.accounts[position].handle = Some(user.handle);
+                            scan.accounts[position].display_name = user.display_name;
+                        }
+                    }
+                }
+                // Keep the accounts listed so far, none of them checked
+                Err(e) => {
+                    scan.unchecked = scan.accounts.len();
+                    scan.interruption = Some(e.to_string());
+                    return Ok(scan);
+                }
+            }
+        }
+
+        let dids: Vec<String> = scan
+            .accounts
+            .iter()
+            .map(|account| account.did.clone())
+            .collect();
+        let labeling = self
+            .label_subjects(&dids, 40, progress, |_, labels| {
+                for label in labels {
+                    if let Some(&position) = index.get(&label.uri) {
+                        scan.accounts[position].labels.push(label);
+                    }
+                }
+            })
+            .await;
+        scan.unchecked = dids.len() - labeling.checked;
+        scan.expired_labels = labeling.expired;
+        scan.interruption = labeling.interruption;
+        progress.progress("Network scan complete".to_string(), 100);
+        Ok(scan)
+    }
+
+    /// Fetch a list (`at://<did>/app.bsky.graph.list/<rkey>`) or starter pack
+    /// (`.../app.bsky.graph.starterpack/<rkey>`) and query labels on it and every member
+    ///
+    /// Members are read from the owner's `app.bsky.graph.listitem` records, which still
+    /// point at taken down accounts the AppView leaves out, and stop after `max_members`.
+    /// Cancelling while the members' handles are looked up keeps the members listed, all of
+    /// them unchecked.
+    pub async fn inspect_list(
+        &self,
+        uri: &str,
+        max_members: usize,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<ListInspection> {
+        let parts: Vec<&str> = uri
+            .strip_prefix("at://")
+            .unwrap_or_default()
+            .splitn(3, '/')
+            .collect();
+        let [owner, collection, rkey] = parts[..] else {
+            return Err(Error::InvalidAtUri(uri.to_string()));
+        };
+        if collection != "app.bsky.graph.list" && collection != "app.bsky.graph.starterpack" {
+            return Err(Error::UnrecognizedInput(format!(
+                "{} is not a list or starter pack",
+                uri
+            )));
+        }
+        let owner = Did::new(owner.to_string());
+
+        progress.progress(format!("Fetching {}...", uri), 5);
+        let fetch = self.posts.get_record(&owner, collection, rkey);
+        let record = self.abort.run(fetch).await.ok_or(Error::Cancelled)??;
+        let text = |field: &str| {
+            record
+                .value
+                .get(field)
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+        let (kind, list_uri) = match collection {
+            "app.bsky.graph.list" => (
+                ListKind::from_purpose(&text("purpose").unwrap_or_default()),
+                uri.to_string(),
+            ),
+            _ => (
+                ListKind::StarterPack,
+                text("list")
+                    .ok_or_else(|| Error::Parse(format!("Starter pack {} has no list", uri)))?,
+            ),
+        };
+
+        let mut inspection = ListInspection {
+            uri: uri.to_string(),
+            kind,
+            name: text("name").unwrap_or_default(),
+            description: text("description").filter(|d| !d.is_empty()),
+            labels: record.self_labels(),
+            members: Vec::new(),
+            truncated: false,
+            unchecked: 0,
+            expired_labels: 0,
+            interruption: None,
+            list_uri,
+        };
+
+        let (members, truncated) = self
+            .list_members(&inspection.list_uri, max_members, progress)
+            .await?;
+        inspection.truncated = truncated;
+        inspection.members = members
+            .into_iter()
+            .map(|(did, user)| ListMember {
+                did,
+                handle: user.as_ref().map(|user| user.handle.clone()),
+                display_name: user.and_then(|user| user.display_name),
+                labels: Vec::new(),
+            })
+            .collect();
+        let index: HashMap<String, usize> = inspection
+            .members
+            .iter()
+            .enumerate()
+            .map(|(position, member)| (member.did.clone(), position))
+            .collect();
+
+        // List item records only have DIDs
+        let missing: Vec<String> = inspection
+            .members
+            .iter()
+            .filter(|member| member.handle.is_none())
+            .map(|member| member.did.clone())
+            .collect();
+        if !missing.is_empty() {
+            progress.progress(format!("Looking up {} handles...", missing.len()), 35);
+            match self.lookup_profiles(&missing).await {
+                Ok(users) => {
+                    for user in users {
+                        if let Some(&position) = index.get(&user.did) {
+                            inspection.members[position].handle = Some(user.handle);
+                            inspection.members[position].display_name = user.display_name;
+                        }
+                    }
+                }
+                // Keep the members listed so far, none of them checked
+                Err(e) => {
+                    inspection.unchecked = inspection.members.len();
+                    inspection.interruption = Some(e.to_string());
+                    return Ok(inspection);
+                }
+            }
+        }
+
+        let mut subjects = vec![inspection.uri.clone()];
+        if inspection.list_uri != inspection.uri {
+            subjects.push(inspection.list_uri.clone());
+        }
+        subjects.extend(inspection.members.iter().map(|member| member.did.clone()));
+
+        let mut checked_members = 0;
+        let labeling = self
+            .label_subjects(&subjects, 40, progress, |chunk, labels| {
+                checked_members += chunk.iter().filter(|s| index.contains_key(*s)).count();
+                for label in labels {
+                    match index.get(&label.uri) {
+                        Some(&position) => inspection.members[position].labels.push(label),
+                        None => inspection.labels.push(label),
+                    }
+                }
+            })
+            .await;
+        inspection.unchecked = inspection.members.len() - checked_members;
+        inspection.expired_labels = labeling.expired;
+        inspection.interruption = labeling.interruption;
+        progress.progress("List inspection complete".to_string(), 100);
+        Ok(inspection)
+    }
+
+    /// Fetch the thread around a post (`at://<did>/app.bsky.feed.post/<rkey>`) and query
+    /// labels on every post in it and every author
+    ///
+    /// A failed or cancelled label query keeps the thread with what was labeled so far.
+    pub async fn analyze_thread(
+        &self,
+        uri: &str,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<PostThread> {
+        progress.progress("Fetching thread...".to_string(), 5);
+        let fetch = self.appview.get_post_thread(uri);
+        let mut thread = self.abort.run(fetch).await.ok_or(Error::Cancelled)??;
+
+        // Deleted posts can still carry labels, so every URI is queried
+        let mut subjects: Vec<String> = thread.posts.iter().map(|post| post.uri.clone()).collect();
+        subjects.extend(thread.authors());
+        let index: HashMap<String, usize> = thread
+            .posts
+            .iter()
+            .enumerate()
+            .map(|(position, post)| (post.uri.clone(), position))
+            .collect();
+
+        let labeling = self
+            .label_subjects(&subjects, 20, progress, |_, labels| {
+                for label in labels {
+                    match index.get(&label.uri) {
+                        Some(&position) => thread.posts[position].labels.push(label),
+                        None => thread
+                            .author_labels
+                            .entry(label.uri.clone())
+                            .or_default()
+                            .push(label),
+                    }
+                }
+            })
+            .await;
+        thread.unchecked = subjects.len() - labeling.checked;
+        thread.expired_labels = labeling.expired;
+        thread.interruption = labeling.interruption;
+        progress.progress("Thread analysis complete".to_string(), 100);
+        Ok(thread)
+    }
+
+    /// DIDs of one kind of connection, with the profile if the listing had it, and whether
+    /// there were more than `max_accounts`
+    async fn list_connections(
+        &self,
+        did: &Did,
+        connection: Connection,
+        max_accounts: usize,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<Listing> {
+        let list = match connection {
+            Connection::Follows => ActorList::Follows(did.to_string()),
+            Connection::Followers => ActorList::Followers(did.to_string()),
+        };
+        if connection == Connection::Followers {
+            return self.list_actors(&list, max_accounts, progress).await;
+        }
+
+        match self.list_follow_records(did, max_accounts, progress).await {
+            Ok(follows) => Ok(follows),
+            Err(Error::Cancelled) => Err(Error::Cancelled),
+            // The PDS may refuse (e.g. for a suspended account); the AppView still lists
+            // the follows it has profiles for
+            Err(e) => {
+                log::warn!("Failed to list follow records, using the AppView: {}", e);
+                self.list_actors(&list, max_accounts, progress).await
+            }
+        }
+    }
+
+    /// Subjects of the account's follow records, newest first
+    async fn list_follow_records(
+        &self,
+        did: &Did,
+        max_accounts: usize,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<Listing> {
+        let mut follows = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let fetch = self.posts.list_collection(
+                did,
+                "app.bsky.graph.follow",
+                Some(LIST_PAGE_SIZE),
+                cursor,
+            );
+            let page = self.abort.run(fetch).await.ok_or(Error::Cancelled)??;
+            let empty = page.records.is_empty();
+            follows.extend(
+                page.records
+                    .iter()
+                    .filter_map(|record| record.value.get("subject")?.as_str())
+                    .map(|subject| (subject.to_string(), None)),
+            );
+            progress.progress(format!("Listed {} follows...", follows.len()), 10);
+
+            if follows.len() >= max_accounts {
+                let truncated = follows.len() > max_accounts || page.cursor.is_some();
+                follows.truncate(max_accounts);
+                return Ok((follows, truncated));
+            }
+            match page.cursor {
+                Some(c) if !c.is_empty() && !empty => cursor = Some(c),
+                _ => return Ok((follows, false)),
+            }
+        }
+    }
+
+    /// Members of a list with whether there were more than `max_members`, from the owner's
+    /// list item records or, if the PDS refuses, the AppView
+    async fn list_members(
+        &self,
+        list_uri: &str,
+        max_members: usize,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<Listing> {
+        let owner = list_uri
+            .strip_prefix("at://")
+            .and_then(|rest| rest.split('/').next())
+            .map(|owner| Did::new(owner.to_string()))
+            .ok_or_else(|| Error::InvalidAtUri(list_uri.to_string()))?;
+
+        match self
+            .list_item_records(&owner, list_uri, max_members, progress)
+            .await
+        {
+            Ok(members) => Ok(members),
+            Err(Error::Cancelled) => Err(Error::Cancelled),
+            Err(e) => {
+                log::warn!("Failed to list list items, using the AppView: {}", e);
+                let list = ActorList::ListMembers(list_uri.to_string());
+                self.list_actors(&list, max_members, progress).await
+            }
+        }
+    }
+
+    /// Subjects of the owner's list item records for `list_uri`, newest first and each once
+    async fn list_item_records(
+        &self,
+        owner: &Did,
+        list_uri: &str,
+        max_members: usize,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<Listing> {
+        let mut seen = HashSet::new();
+        let mut members = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let fetch = self.posts.list_collection(
+                owner,
+                "app.bsky.graph.listitem",
+                Some(LIST_PAGE_SIZE),
+                cursor,
+            );
+            let page = self.abort.run(fetch).await.ok_or(Error::Cancelled)??;
+            let empty = page.records.is_empty();
+            // The owner's items for all of their lists share the collection
+            members.extend(
+                page.records
+                    .iter()
+                    .filter(|record| {
+                        record.value.get("list").and_then(|l| l.as_str()) == Some(list_uri)
+                    })
+                    .filter_map(|record| record.value.get("subject")?.as_str())
+                    .filter(|subject| seen.insert(subject.to_string()))
+                    .map(|subject| (subject.to_string(), None)),
+            );
+            progress.progress(format!("Listed {} members...", members.len()), 15);
+
+            if members.len() >= max_members {
+                let truncated = members.len() > max_members || page.cursor.is_some();
+                members.truncate(max_members);
+                return Ok((members, truncated));
+            }
+            match page.cursor {
+                Some(c) if !c.is_empty() && !empty => cursor = Some(c),
+                _ => return Ok((members, false)),
+            }
+        }
+    }
+
+    /// Accounts on an AppView list, up to `max_accounts`
+    async fn list_actors(
+        &self,
+        list: &ActorList,
+        max_accounts: usize,
+        progress: &mut impl ProgressReporter,
+    ) -> Result<Listing> {
+        let mut actors = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let fetch = self.appview.actor_page(list, cursor.as_deref());
+            let (users, next) = self.abort.run(fetch).await.ok_or(Error::Cancelled)??;
+            let empty = users.is_empty();
+            actors.extend(users.into_iter().map(|user| (user.did.clone(), Some(user))));
+            progress.progress(format!("Listed {} accounts...", actors.len()), 15);
+
+            if actors.len() >= max_accounts {
+                let truncated = actors.len() > max_accounts || next.is_some();
+                actors.truncate(max_accounts);
+                return Ok((actors, truncated));
+            }
+            match next {
+                Some(c) if !c.is_empty() && !empty => cursor = Some(c),
+                _ => return Ok((actors, false)),
+            }
+        }
+    }
+
+    /// Whether each record URI is still in the repository
+    ///
+    /// One getRepo download answers for every record; if that fails, each record is
+    /// looked up with getRecord.
+    async fn record_statuses(
+        &self,
+        did: &Did,
+        uris: &[String],
+    ) -> Result<HashMap<String, RecordStatus>> {
+        if uris.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let fetched = self.abort.run(self.posts.fetch_repo(did)).await;
+        match fetched.ok_or(Error::Cancelled)? {
+            Ok(repo) => {
+                let keys: HashSet<String> =
+                    repo.entries()?.into_iter().map(|(key, _)| key).collect();
+                let prefix = format!("at://{}/", did);
+                Ok(uris
+                    .iter()
+                    .map(|uri| {
+                        let present = uri
+                            .strip_prefix(&prefix)
+                            .is_some_and(|key| keys.contains(key));
+                        let status = if present {
+                            RecordStatus::Present
+                        } else {
+                            RecordStatus::Deleted
+                        };
+                        (uri.clone(), status)
+                    })
+                    .collect())
+            }
+            Err(e) => {
+                log::warn!(
+                    "Repository download failed, checking records one by one: {}",
+                    e
+                );
+                let lookups = stream::iter(uris)
+                    .map(|uri| async move { (uri.clone(), self.record_status(did, uri).await) })
+                    .buffer_unordered(self.concurrency)
+                    .collect::<Vec<_>>();
+                self.abort
+                    .run(lookups)
+                    .await
+                    .map(|statuses| statuses.into_iter().collect())
+                    .ok_or(Error::Cancelled)
+            }
+        }
+    }
+
+    async fn record_status(&self, did: &Did, uri: &str) -> RecordStatus {
+        let path = uri.strip_prefix("at://").unwrap_or(uri);
+        let (collection, rkey) = match path.splitn(3, '/').collect::<Vec<_>>()[..] {
+            [_, collection, rkey] => (collection, rkey),
+            _ => return RecordStatus::Unknown,
+        };
+
+        match self.posts.get_record(did, collection, rkey).await {
+            Ok(_) => RecordStatus::Present,
+            Err(Error::RecordNotFound(_)) => RecordStatus::Deleted,
+            Err(e) => {
+                log::warn!("Couldn't check {}: {}", uri, e);
+                RecordStatus::Unknown
+            }
+        }
+    }
+
+    /// Records in scope from a repository, newest first
+    fn scoped_records(&self, repo: &Repository) -> Result<Vec<AtRecord>> {
+        let mut records = Vec::new();
+        for &collection in &self.scope.collections {
+            let in_collection = repo.records_in(collection.nsid())?;
+            records.extend(in_collection.into_iter().map(|record| (collection, record)));
+        }
+        Ok(self.scope.select(records))
+    }
+
+    /// Page through `listRecords` for every collection in scope, newest first
+    async fn list_scoped_records(&self, did: &Did) -> Result<Vec<AtRecord>> {
+        let mut records = Vec::new();
+
+        for &collection in &self.scope.collections {
+            let mut matched = 0;
+            let mut cursor = None;
+
+            loop {
+                let page = self
+                    .posts
+                    .list_collection(did, collection.nsid(), Some(LIST_PAGE_SIZE), cursor)
+                    .await?;
+                if page.records.is_empty() {
+                    break;
+                }
+
+                // Pages run newest first, so once a page reaches past the window the rest do too
+                let past_window = page
+                    .records
+                    .last()
+                    .is_some_and(|record| self.scope.is_before_window(record));
+                matched += page
+                    .records
+                    .iter()
+                    .filter(|record| self.scope.matches(collection, record))
+                    .count();
+                let enough = self.scope.max_records.is_some_and(|max| matched >= max);
+                records.extend(page.records.into_iter().map(|record| (collection, record)));
+
+                match page.cursor {
+                    Some(c) if !c.is_empty() && !past_window && !enough => cursor = Some(c),
+                    _ => break,
+                }
+            }
+        }
+
+        Ok(self.scope.select(records))
+    }
+
+    /// Query labels on `subjects` in concurrent batches of [`LABEL_BATCH_SIZE`], passing
+    /// each answered batch and its labels in effect (or all of them, with the scope's
+    /// `include_expired`) to `on_labels`
+    ///
+    /// Progress runs from `start` to 95% across the batches. Cancellation or an
+    /// authentication failure stops querying; other failed batches are left unchecked.
+    async fn label_subjects(
+        &self,
+        subjects: &[String],
+        start: u8,
+        progress: &mut impl ProgressReporter,
+        mut on_labels: impl FnMut(&[String], Vec<Label>),
+    ) -> Labeling {
+        let now = chrono::Utc::now();
+        let total_batches = subjects.len().div_ceil(LABEL_BATCH_SIZE);
+        let mut batches = stream::iter(subjects.chunks(LABEL_BATCH_SIZE))
+            .map(|chunk| async move { (chunk, self.query_batch(chunk).await) })
+            .buffer_unordered(self.concurrency);
+        let mut labeling = Labeling::default();
+        let mut batches_done = 0;
+
+        while labeling.interruption.is_none() {
+            let (chunk, batch) = match self.abort.run(batches.next()).await {
+                Some(Some(done)) => done,
+                Some(None) => break,
+                None => {
+                    labeling.interruption = Some(Error::Cancelled.to_string());
+                    break;
+                }
+            };
+            batches_done += 1;
+
+            match batch {
+                Ok(labels) => {
+                    labeling.checked += chunk.len();
+                    let (labels, expired) = in_effect(&labels, self.scope.include_expired, now);
+                    labeling.expired += expired;
+                    on_labels(chunk, labels);
+                }
+                Err(e) => labeling.interruption = stop_reason(e),
+            }
+
+            progress.progress(
+                format!(
+                    "Querying mod.bsky.app: batch {}/{}...",
+                    batches_done, total_batches
+                ),
+                start + ((batches_done as f32 / total_batches as f32) * (95 - start) as f32) as u8,
+            );
+        }
+
+        labeling
+    }
+
+    /// Profiles of `dids` from the AppView; handles are a nicety, so failed lookups are
+    /// only logged
+    async fn lookup_profiles(&self, dids: &[String]) -> Result<Vec<UserInfo>> {
+        let lookups = stream::iter(dids.chunks(crate::appview::MAX_PROFILES_PER_REQUEST))
+            .map(|chunk| self.appview.get_profiles(chunk))
+            .buffer_unordered(self.concurrency)
+            .collect::<Vec<_>>();
+
+        let mut profiles = Vec::new();
+        for result in self.abort.run(lookups).await.ok_or(Error::Cancelled)? {
+            match result {
+                Ok(found) => profiles.extend(found),
+                Err(e) => log::warn!("Failed to look up profiles: {}", e),
+            }
+        }
+        Ok(profiles)
+    }
+
+    /// Query one batch of subjects
+    async fn query_batch(&self, subjects: &[String]) -> Result<Vec<Label>> {
+        let collection = self.labeler.query_labels(subjects).await?;
+        for label in &collection.labels {
+            log::info!("  Label: {} on {}", label.val, label.uri);
+        }
+        Ok(collection.labels)
+    }
+
+    /// Fetch a post's like and repost counts, its likers and reposters (up to
+    /// [`Self::with_engagement_accounts`] each), and with [`Self::with_engagement_labels`]
+    /// their account labels
+    ///
+    /// Failures are kept in [`PostWithLabels::engagement_error`] rather than passed off as
+    /// a post nobody engaged with.
+    async fn add_engagement(&self, post: &mut PostWithLabels) {
+        let likes = ActorList::Likes(post.uri.clone());
+        let reposts = ActorList::RepostedBy(post.uri.clone());
+        // Nobody watches these listings' progress
+        let (mut quiet_likes, mut quiet_reposts) = (|_: AnalysisEvent| {}, |_: AnalysisEvent| {});
+        let (counts, likers, reposters) = futures::join!(
+            self.appview.get_post_counts(&post.uri),
+            self.list_actors(&likes, self.engagement_accounts, &mut quiet_likes),
+            self.list_actors(&reposts, self.engagement_accounts, &mut quiet_reposts)
+        );
+
+        let mut errors = Vec::new();
+        let mut users = |listed: Result<Listing>, what| {
+            let (actors, truncated) = listed.unwrap_or_else(|e| {
+                log::warn!("Failed to fetch {} for {}: {}", what, post.uri, e);
+                errors.push(format!("couldn't list {}: {}", what, e));
+                (Vec::new(), false)
+            });
+            post.engagement_truncated |= truncated;
+            actors
+                .into_iter()
+                .filter_map(|(_, user)| user)
+                .collect::<Vec<_>>()
+        };
+        post.likers = users(likers, "likes");
+        post.reposters = users(reposters, "reposts");
+
+        match counts {
+            Ok((likes, reposts)) => {
+                post.like_count = likes;
+                post.repost_count = reposts;
+            }
+            Err(e) => {
+                log::warn!("Failed to fetch counts for {}: {}", post.uri, e);
+                errors.insert(0, format!("couldn't fetch counts: {}", e));
+                // The listed accounts are a lower bound, better than showing none
+                post.like_count = post.likers.len();
+                post.repost_count = post.reposters.len();
+            }
+        }
+
+        if self.label_engagement {
+            let (labels, failure) = self.engagement_labels(post).await;
+            if let Some(failure) = failure {
+                log::warn!("{} on {}", failure, post.uri);
+                errors.push(failure);
+            }
+            post.engagement_labels = Some(labels);
+        }
+        if !errors.is_empty() {
+            post.engagement_error = Some(errors.join("; "));
+        }
+    }
+
+    /// Account labels on a post's likers and reposters, in batches of [`LABEL_BATCH_SIZE`]
+    ///
+    /// A failed batch doesn't discard the others: the labels found come back with a note
+    /// of how many accounts couldn't be checked, if any.
+    pub async fn engagement_labels(&self, post: &PostWithLabels) -> (Vec<Label>, Option<String>) {
+        let mut dids: Vec<String> = post
+            .likers
+            .iter()
+            .chain(&post.reposters)
+            .map(|user| user.did.clone())
+            .collect();
+        dids.sort();
+        dids.dedup();
+
+        let mut labels = Vec::new();
+        // Nobody watches this labeling's progress
+        let mut quiet = |_: AnalysisEvent| {};
+        let labeling = self
+            .label_subjects(&dids, 0, &mut quiet, |_, found| {
+                labels.extend(
+                    found
+                        .into_iter()
+                        .filter(|label| dids.binary_search(&label.uri).is_ok()),
+                )
+            })
+            .await;
+
+        let unchecked = dids.len() - labeling.checked;
+        let failure = (unchecked > 0).then(|| {
+            let note = format!(
+                "{} of {} account(s) could not be checked for labels",
+                unchecked,
+                dids.len()
+            );
+            match labeling.interruption {
+                Some(reason) => format!("{} ({})", note, reason),
+                None => note,
+            }
+        });
+        (labels, failure)
+    }
+}
+
+/// What came of querying labels on a list of subjects
+#[derive(Default)]
+struct Labeling {
+    /// Subjects whose batch was answered
+    checked: usize,
+    /// Expired labels left out
+    expired: usize,
+    /// Why querying stopped before every batch was answered
+    interruption: Option<String>,
+}
+
+/// Posts checked so far and their labels (labeler-applied and self-labels)
+#[derive(Default)]
+struct Tally {
+    checked: Vec<AtRecord>,
+    /// Labels in effect (or all of them, with `include_expired`)
+    labels: Vec<Label>,
+    include_expired: bool,
+    now: chrono::DateTime<chrono::Utc>,
+    /// Expired labels left out so far, account labels included
+    expired: usize,
+}
+
+impl Tally {
+    /// Add a checked batch, returning its labeled posts with their labels
+    fn add<'a>(
+        &mut self,
+        posts: Vec<&'a AtRecord>,
+        labels: &[Label],
+    ) -> Vec<(&'a AtRecord, Vec<Label>)> {
+        let uris: HashSet<&str> = posts.iter().map(|post| post.uri.as_str()).collect();
+        let in_batch: Vec<Label> = labels
+            .iter()
+            .filter(|label| uris.contains(label.uri.as_str()))
+            .cloned()
+            .collect();
+        let (mut found, expired) = in_effect(&in_batch, self.include_expired, self.now);
+        self.expired += expired;
+        found.extend(posts.iter().flat_map(|post| post.self_labels()));
+
+        let mut labels_by_uri: HashMap<&str, Vec<Label>> = HashMap::new();
+        for label in &found {
+            labels_by_uri
+                .entry(label.uri.as_str())
+                .or_default()
+                .push(label.clone());
+        }
+        let labeled = posts
+            .iter()
+            .filter_map(|post| Some((*post, labels_by_uri.remove(post.uri.as_str())?)))
+            .collect();
+
+        self.checked.extend(posts.into_iter().cloned());
+        self.labels.extend(found);
+        labeled
+    }
+
+    /// Report running stats and newly labeled posts
+    fn report(
+        &self,
+        found: Vec<(&AtRecord, Vec<Label>)>,
+        account_labels: &[Label],
+        display: impl Fn(&AtRecord, Vec<Label>) -> PostWithLabels,
+        progress: &mut impl ProgressReporter,
+    ) {
+        progress.report(AnalysisEvent::Stats(
+            self.stats(&self.checked, account_labels),
+        ));
+
+        for (post, labels) in found {
+            progress.report(AnalysisEvent::PostFound(display(post, labels)));
+        }
+    }
+
+    fn stats(&self, posts: &[AtRecord], account_labels: &[Label]) -> BulkAnalysisStats {
+        let mut stats = compute_stats(posts, account_labels.to_vec(), &self.labels);
+        stats.expired_labels = self.expired;
+        stats
+    }
+}
+
+/// Label values with the number of label sets (accounts, posts) carrying each, most
+/// common first
+pub(crate) fn count_label_values<'a>(
+    label_sets: impl IntoIterator<Item = &'a [Label]>,
+) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for labels in label_sets {
+        let values: HashSet<&str> = labels.iter().map(|label| label.val.as_str()).collect();
+        for val in values {
+            *counts.entry(val).or_default() += 1;
+        }
+    }
+
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(val, count)| (val.to_string(), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Labels still in effect at `now` (all of them with `include_expired`), and how many
+/// expired ones were left out
+fn in_effect(
+    labels: &[Label],
+    include_expired: bool,
+    now: chrono::DateTime<chrono::Utc>,
+) -> (Vec<Label>, usize) {
+    if include_expired {
+        return (labels.to_vec(), 0);
+    }
+    let active: Vec<Label> = labels
+        .iter()
+        .filter(|label| !label.is_expired_at(now))
+        .cloned()
+        .collect();
+    let expired = labels.len() - active.len();
+    (active, expired)
+}
+
+/// Reason to stop the analysis for a failed label query
+///
+/// Only authentication failures stop it; anything else is logged and retried on resume.
+fn stop_reason(error: Error) -> Option<String> {
+    match error {
+        Error::AuthenticationRequired(_) => Some(error.to_string()),
+        e => {
+            log::error!("Failed to query labels: {}", e);
+            None
+        }
+    }
+}
+
+/// Check a repository against the current signing key of the account it should belong to
+///
+/// Returns the verified commit CID, or `None` if the commit is for another account, the
+/// key can't be resolved, or the signature doesn't hold (e.g. the key was rotated after an
+/// old export was made).
+pub async fn verify_repo(repo: &Repository, did: &Did) -> Option<String> {
+    if repo.did() != *did {
+        log::warn!("Repository commit is for {}, expected {}", repo.did(), did);
+        return None;
+    }
+
+    let key = match resolve_signing_key(did).await {
+        Ok(key) => key,
+        Err(e) => {
+            log::warn!("Could not resolve signing key for {}: {}", did, e);
+            return None;
+        }
+    };
+
+    match repo.verify(&key) {
+        Ok(()) => Some(repo.commit_cid().to_string()),
+        Err(e) => {
+            log::warn!("Repository for {} failed verification: {}", did, e);
+            None
+        }
+    }
+}
+
+/// Statistics over `labels` (post labels, including self-labels) for the given posts
+///
+/// Only externally applied, non-negated post labels count towards the label totals;
+/// account labels are reported separately.
+pub fn compute_stats(
+    posts: &[AtRecord],
+    account_labels: Vec<Label>,
+    labels: &[Label],
+) -> BulkAnalysisStats {
+    let mut labeled_uris: HashSet<&str> = HashSet::new();
+    let mut self_labeled_uris: HashSet<&str> = HashSet::new();
+    let mut labels_by_category: HashMap<LabelCategory, usize> = HashMap::new();
+    let mut label_value_counts: HashMap<String, usize> = HashMap::new();
+
+    // Only count post URIs (not account-level DIDs)
+    for label in labels
+        .iter()
+        .filter(|l| !l.neg && l.uri.starts_with("at://"))
+    {
+        if label.self_applied {
+            self_labeled_uris.insert(&label.uri);
+            continue;
+        }
+
+        labeled_uris.insert(&label.uri);
+        *labels_by_category.entry(label.category()).or_insert(0) += 1;
+        *label_value_counts.entry(label.val.clone()).or_insert(0) += 1;
+    }
+
+    // Sort label values by count
+    let mut top_label_values: Vec<(String, usize)> = label_value_counts.into_iter().collect();
+    top_label_values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    BulkAnalysisStats {
+        total_posts: posts.len(),
+        posts_with_labels: labeled_uris.len(),
+        self_labeled_posts: self_labeled_uris.len(),
+        labels_by_category,
+        top_label_values,
+        account_labels,
+        expired_labels: 0,
+    }
+}
+
+/// Posts to display with their labels: every labeled post, plus a sample of the most
+/// recent unlabeled ones when the account itself is labeled (e.g. banned)
+pub fn select_posts<'a>(
+    posts: &'a [AtRecord],
+    labels: &[Label],
+    account_is_labeled: bool,
+) -> Vec<(&'a AtRecord, Vec<Label>)> {
+    let mut labels_by_uri: HashMap<&str, Vec<Label>> = HashMap::new();
+    for label in labels {
+        labels_by_uri
+            .entry(label.uri.as_str())
+            .or_default()
+            .push(label.clone());
+    }
+
+    let mut selected = Vec::new();
+    for post in posts {
+        let post_labels = labels_by_uri.remove(post.uri.as_str()).unwrap_or_default();
+
+        // Show post if: has labels OR (account is moderated AND we haven't shown 10 yet)
+        if !post_labels.is_empty()
+            || (account_is_labeled && selected.len() < MODERATED_ACCOUNT_SAMPLE)
+        {
+            selected.push((post, post_labels));
+        }
+    }
+
+    selected
+}
+
+/// Build the display record for a post, without engagement
+///
+/// Media URLs are only filled in when the author's PDS endpoint is known.
+pub fn to_post_with_labels(
+    post: &AtRecord,
+    labels: Vec<Label>,
+    did: &Did,
+    pds_endpoint: Option<&str>,
+) -> PostWithLabels {
+    let field = |name: &str| {
+        post.value
+            .get(name)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    // Lists and profiles have a name instead of text
+    let text = ["text", "name", "displayName"]
+        .into_iter()
+        .map(field)
+        .find(|text| !text.is_empty())
+        .unwrap_or_default();
+    let collection = match post.value.get("$type").and_then(|t| t.as_str()) {
+        Some(nsid) => nsid.to_string(),
+        None => post.uri.split('/').nth(3).unwrap_or_default().to_string(),
+    };
+
+    let (image_urls, video_url) = pds_endpoint
+        .map(|pds| media_urls(post, did, pds))
+        .unwrap_or_default();
+
+    PostWithLabels {
+        uri: post.uri.clone(),
+        collection,
+        text,
+        labels,
+        created_at: field("createdAt"),
+        has_media: !image_urls.is_empty() || video_url.is_some(),
+        image_urls,
+        video_url,
+        like_count: 0,
+        repost_count: 0,
+        likers: Vec::new(),
+        reposters: Vec::new(),
+        verified_commit: None,
+        engagement_truncated: false,
+        engagement_error: None,
+        engagement_labels: None,
+    }
+}
+
+/// Sort posts by number of labels (most labeled first), then by recency
+pub fn sort_labeled_posts(posts: &mut [PostWithLabels]) {
+    posts.sort_by(|a, b| {
+        b.labels
+            .len()
+            .cmp(&a.labels.len())
+            .then_with(|| b.created_at.cmp(&a.created_at))
+    });
+}
+
+fn is_post(uri: &str) -> bool {
+    uri.split('/').nth(3) == Some("app.bsky.feed.post")
+}
+
+fn has_embed(post: &AtRecord) -> bool {
+    post.value.get("embed").is_some()
+}
+
+/// Image and video blob URLs from a post's embed, served by the author's PDS
+pub fn media_urls(post: &AtRecord, did: &Did, pds_endpoint: &str) -> (Vec<String>, Option<String>) {
+    let blob_url = |blob: &serde_json::Value| {
+        blob.get("ref")
+            .and_then(|r| r.get("$link"))
+            .and_then(|l| l.as_str())
+            .map(|cid| {
+                format!(
+                    "{}/xrpc/com.atproto.sync.getBlob?did={}&cid={}",
+                    pds_endpoint,
+                    did.as_str(),
+                    cid
+                )
+            })
+    };
+    let images = |embed: &serde_json::Value| -> Vec<String> {
+        embed
+            .get("images")
+            .and_then(|i| i.as_array())
+            .map(|images| {
+                images
+                    .iter()
+                    .filter_map(|img| img.get("image").and_then(blob_url))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let Some(embed) = post.value.get("embed") else {
+        return (Vec::new(), None);
+    };
+
+    match embed.get("$type").and_then(|t| t.as_str()) {
+        Some("app.bsky.embed.images") => (images(embed), None),
+        Some("app.bsky.embed.video") => (Vec::new(), embed.get("video").and_then(blob_url)),
+        // Posts with both a quoted record and media
+        Some("app.bsky.embed.recordWithMedia") => match embed.get("media") {
+            Some(media) => match media.get("$type").and_then(|t| t.as_str()) {
+                Some("app.bsky.embed.images") => (images(media), None),
+                Some("app.bsky.embed.video") => (Vec::new(), media.get("video").and_then(blob_url)),
+                _ => (Vec::new(), None),
+            },
+            None => (Vec::new(), None),
+        },
+        _ => (Vec::new(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LabelsResponse, ListRecordsResponse, ScopeCollection};
+
+    const DID: &str = "did:plc:4qqizocrnriintskkh6trnzv";
+    const NEWEST: &str = "at://did:plc:4qqizocrnriintskkh6trnzv/app.bsky.feed.post/3lbxq2zmv7k2a";
+    const SELF_LABELED: &str =
+        "at://did:plc:4qqizocrnriintskkh6trnzv/app.bsky.feed.post/3lbxp7tlw4c2b";
+    const REPLY: &str = "at://did:plc:4qqizocrnriintskkh6trnzv/app.bsky.feed.post/3lbxn4hqsyk2c";
+    const SPAM: &str = "at://did:plc:4qqizocrnriintskkh6trnzv/app.bsky.feed.post/3lbxkcjh5ek2d";
+
+    /// A recorded `listRecords` page, newest first
+    fn posts() -> Vec<AtRecord> {
+        let page: ListRecordsResponse =
+            serde_json::from_str(include_str!("../tests/fixtures/list_records.json")).unwrap();
+        page.records
+    }
+
+    /// A recorded `queryLabels` response for those posts and their author
+    fn labels() -> Vec<Label> {
+        let response: LabelsResponse =
+            serde_json::from_str(include_str!("../tests/fixtures/query_labels.json")).unwrap();
+        response.labels
+    }
+
+    fn now() -> chrono::DateTime<chrono::Utc> {
+        "2026-03-10T00:00:00Z".parse().unwrap()
+    }
+
+    /// Labels a run would tally: post labels in effect plus the posts' self-labels
+    fn tallied(posts: &[AtRecord]) -> (Vec<Label>, usize) {
+        let on_posts: Vec<Label> = labels()
+            .into_iter()
+            .filter(|label| label.uri.starts_with("at://"))
+            .collect();
+        let (mut found, expired) = in_effect(&on_posts, false, now());
+        found.extend(posts.iter().flat_map(|post| post.self_labels()));
+        (found, expired)
+    }
+
+    fn account_labels() -> Vec<Label> {
+        labels()
+            .into_iter()
+            .filter(|label| label.uri == DID)
+            .collect()
+    }
+
+    fn uris(records: &[AtRecord]) -> Vec<&str> {
+        records.iter().map(|record| record.uri.as_str()).collect()
+    }
+
+    #[test]
+    fn fixtures_parse() {
+        assert_eq!(posts().len(), 4);
+        assert_eq!(labels().len(), 6);
+        assert!(labels()[4].neg);
+        assert_eq!(labels()[2].exp.as_deref(), Some("2026-03-09T12:00:00.000Z"));
+    }
+
+    #[test]
+    fn compute_stats_counts_labeler_labels_per_post() {
+        let posts = posts();
+        let (labels, expired) = tallied(&posts);
+        assert_eq!(expired, 1);
+
+        let stats = compute_stats(&posts, account_labels(), &labels);
+        assert_eq!(stats.total_posts, 4);
+        // The spam post and the newest post; the negation and self-label don't count
+        assert_eq!(stats.posts_with_labels, 2);
+        assert_eq!(stats.self_labeled_posts, 1);
+        assert_eq!(
+            stats.top_label_values,
+            vec![("spam".to_string(), 2), ("sexual".to_string(), 1)]
+        );
+        assert_eq!(stats.labels_by_category.get(&LabelCategory::Spam), Some(&2));
+        assert_eq!(
+            stats.labels_by_category.get(&LabelCategory::AdultContent),
+            Some(&1)
+        );
+        assert_eq!(stats.account_labels.len(), 1);
+    }
+
+    #[test]
+    fn select_posts_keeps_labeled_posts_in_order() {
+        let posts = posts();
+        let (labels, _) = tallied(&posts);
+
+        let selected = select_posts(&posts, &labels, false);
+        let selected: Vec<(&str, usize)> = selected
+            .iter()
+            .map(|(post, labels)| (post.uri.as_str(), labels.len()))
+            .collect();
+        assert_eq!(selected, vec![(NEWEST, 2), (SELF_LABELED, 1), (SPAM, 2)]);
+    }
+
+    #[test]
+    fn select_posts_samples_unlabeled_posts_of_labeled_accounts() {
+        let posts = posts();
+        let (labels, _) = tallied(&posts);
+
+        let selected = select_posts(&posts, &labels, true);
+        assert_eq!(selected.len(), 4);
+        assert_eq!(selected[2].0.uri, REPLY);
+        assert!(selected[2].1.is_empty());
+    }
+
+    #[test]
+    fn scope_filters_replies_and_dates() {
+        let records = || {
+            posts()
+                .into_iter()
+                .map(|post| (ScopeCollection::Posts, post))
+                .collect::<Vec<_>>()
+        };
+
+        let all = AnalysisScope::default().select(records());
+        assert_eq!(uris(&all), vec![NEWEST, SELF_LABELED, REPLY, SPAM]);
+
+        let top_level = AnalysisScope {
+            include_replies: false,
+            ..AnalysisScope::default()
+        };
+        assert_eq!(
+            uris(&top_level.select(records())),
+            vec![NEWEST, SELF_LABELED, SPAM]
+        );
+
+        let window = AnalysisScope {
+            since: Some("2026-03-02T00:00:00Z".parse().unwrap()),
+            until: Some("2026-03-04T00:00:00Z".parse().unwrap()),
+            ..AnalysisScope::default()
+        };
+        assert_eq!(uris(&window.select(records())), vec![SELF_LABELED, REPLY]);
+
+        let newest = AnalysisScope {
+            max_records: Some(1),
+            ..AnalysisScope::default()
+        };
+        assert_eq!(uris(&newest.select(records())), vec![NEWEST]);
+    }
+
+    #[test]
+    fn scope_replaces_likes_with_the_liked_post() {
+        let like = AtRecord {
+            uri: format!("at://{}/app.bsky.feed.like/3lby2abcdek2e", DID),
+            cid: String::new(),
+            value: serde_json::json!({
+                "$type": "app.bsky.feed.like",
+                "subject": { "uri": SPAM, "cid": "bafyreifqmm2b2lzt6nhkgbtiuhdoycw3uunb3vbmfmdzhy4wpv5ifrd55e" },
+                "createdAt": "2026-03-05T00:00:00.000Z"
+            }),
+        };
+        let mut records: Vec<(ScopeCollection, AtRecord)> = posts()
+            .into_iter()
+            .map(|post| (ScopeCollection::Posts, post))
+            .collect();
+        records.push((ScopeCollection::Likes, like));
+
+        // The like is newest, so the post it points at moves to the front, once
+        let selected = AnalysisScope::default().select(records);
+        assert_eq!(uris(&selected), vec![SPAM, NEWEST, SELF_LABELED, REPLY]);
+        assert_eq!(selected[0].value["$type"], "app.bsky.feed.like");
+    }
+
+    #[test]
+    fn to_post_with_labels_reads_the_record() {
+        let did = Did::new(DID.to_string());
+        let post = &posts()[3];
+
+        let labeled = to_post_with_labels(post, labels()[..2].to_vec(), &did, None);
+        assert_eq!(labeled.uri, SPAM);
+        assert_eq!(labeled.collection, "app.bsky.feed.post");
+        assert_eq!(labeled.text, "Free followers, click the link in bio");
+        assert_eq!(labeled.created_at, "2026-03-01T08:00:00.000Z");
+        assert_eq!(labeled.labels.len(), 2);
+        assert!(!labeled.has_media);
+        assert_eq!(labeled.like_count, 0);
+    }
+
+    #[test]
+    fn to_post_with_labels_links_media_on_the_pds() {
+        let did = Did::new(DID.to_string());
+        let mut post = posts()[0].clone();
+        post.value["embed"] = serde_json::json!({
+            "$type": "app.bsky.embed.images",
+            "images": [{
+                "alt": "",
+                "image": {
+                    "$type": "blob",
+                    "ref": { "$link": "bafkreibabalobzn6cd366ukcsjycp4yymjymgfxcv6xczmlgpemzkz3cfa" },
+                    "mimeType": "image/jpeg",
+                    "size": 734102
+                }
+            }]
+        });
+
+        let labeled = to_post_with_labels(&post, Vec::new(), &did, None);
+        assert!(!labeled.has_media);
+
+        let labeled = to_post_with_labels(&post, Vec::new(), &did, Some("https://pds.example"));
+        assert!(labeled.has_media);
+        assert_eq!(
+            labeled.image_urls,
+            vec![format!(
+                "https://pds.example/xrpc/com.atproto.sync.getBlob?did={}&cid=bafkreibabalobzn6cd366ukcsjycp4yymjymgfxcv6xczmlgpemzkz3cfa",
+                DID
+            )]
+        );
+    }
+
+    #[test]
+    fn sort_labeled_posts_puts_most_labeled_then_newest_first() {
+        let did = Did::new(DID.to_string());
+        let posts = posts();
+        let (labels, _) = tallied(&posts);
+        let mut labeled: Vec<PostWithLabels> = select_posts(&posts, &labels, true)
+            .into_iter()
+            .map(|(post, labels)| to_post_with_labels(post, labels, &did, None))
+            .collect();
+
+        sort_labeled_posts(&mut labeled);
+        let order: Vec<&str> = labeled.iter().map(|post| post.uri.as_str()).collect();
+        assert_eq!(order, vec![NEWEST, SPAM, SELF_LABELED, REPLY]);
+    }
+
+    #[tokio::test]
+    async fn checkpoint_resumes_without_querying_again() {
+        let did = Did::new(DID.to_string());
+        let checkpoint = AnalysisCheckpoint {
+            account_labels: Some(account_labels()),
+            queried_uris: uris(&posts()).into_iter().map(String::from).collect(),
+            labels: labels()[..5].to_vec(),
+            ..AnalysisCheckpoint::new(&did, &AnalysisScope::default())
+        };
+        // Every query is already answered, so nothing goes out to the network
+        let analyzer = BulkAnalyzer::new(None)
+            .with_engagement(false)
+            .with_checkpoint(checkpoint);
+
+        let analysis = analyzer
+            .analyze_posts(&did, posts(), None, &mut |_| {})
+            .await
+            .unwrap();
+        assert!(analysis.complete);
+        assert_eq!(analysis.interruption, None);
+        assert_eq!(analysis.pending_posts, 0);
+        assert_eq!(analysis.stats.total_posts, 4);
+        assert_eq!(analysis.stats.posts_with_labels, 2);
+        assert_eq!(analysis.stats.self_labeled_posts, 1);
+        assert_eq!(analysis.stats.expired_labels, 1);
+        assert_eq!(analysis.labeled_posts.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn interrupted_resume_reports_only_checked_posts() {
+        let did = Did::new(DID.to_string());
+        let checkpoint = AnalysisCheckpoint {
+            account_labels: Some(Vec::new()),
+            queried_uris: vec![NEWEST.to_string(), SELF_LABELED.to_string()],
+            labels: labels()[3..5].to_vec(),
+            ..AnalysisCheckpoint::new(&did, &AnalysisScope::default())
+        };
+        let abort = AbortHandle::new();
+        abort.abort();
+        let analyzer = BulkAnalyzer::new(None)
+            .with_engagement(false)
+            .with_abort(abort)
+            .with_checkpoint(checkpoint);
+
+        let analysis = analyzer
+            .analyze_posts(&did, posts(), None, &mut |_| {})
+            .await
+            .unwrap();
+        assert!(!analysis.complete);
+        assert_eq!(analysis.interruption, Some(Error::Cancelled.to_string()));
+        assert_eq!(analysis.pending_posts, 2);
+        assert_eq!(analysis.stats.total_posts, 2);
+        assert_eq!(analysis.stats.posts_with_labels, 1);
+        assert_eq!(analysis.checkpoint.queried_uris.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn cancelled_network_scan_stops_before_the_first_page() {
+        let abort = AbortHandle::new();
+        abort.abort();
+        let analyzer = BulkAnalyzer::new(None).with_abort(abort);
+
+        let scan = analyzer
+            .scan_network(
+                &Did::new(DID.to_string()),
+                &[Connection::Follows, Connection::Followers],
+                DEFAULT_MAX_CONNECTIONS,
+                &mut |_| {},
+            )
+            .await;
+        assert!(matches!(scan, Err(Error::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn cancelled_list_inspection_stops_before_the_list_record() {
+        let abort = AbortHandle::new();
+        abort.abort();
+        let analyzer = BulkAnalyzer::new(None).with_abort(abort);
+
+        let mut events = Vec::new();
+        let inspection = analyzer
+            .inspect_list(
+                &format!("at://{}/app.bsky.graph.list/3kxyz2abc3d2e", DID),
+                DEFAULT_MAX_LIST_MEMBERS,
+                &mut |event| events.push(event),
+            )
+            .await;
+        assert!(matches!(inspection, Err(Error::Cancelled)));
+        assert_eq!(events.len(), 1, "nothing past the list fetch is reported");
+    }
+
+    #[tokio::test]
+    async fn cancelled_engagement_labels_report_the_unchecked_accounts() {
+        let abort = AbortHandle::new();
+        abort.abort();
+        let analyzer = BulkAnalyzer::new(None).with_abort(abort);
+        let liker = |did: &str| UserInfo {
+            did: did.to_string(),
+            handle: format!("{}.test", &did[8..]),
+            display_name: None,
+        };
+        let mut post =
+            to_post_with_labels(&posts()[0], Vec::new(), &Did::new(DID.to_string()), None);
+        post.likers = vec![liker("did:plc:alice"), liker("did:plc:bob")];
+        // Someone who liked and reposted is checked once
+        post.reposters = vec![liker("did:plc:bob")];
+
+        let (labels, failure) = analyzer.engagement_labels(&post).await;
+        assert!(labels.is_empty());
+        let failure = failure.unwrap();
+        assert!(
+            failure.starts_with("2 of 2 account(s) could not be checked"),
+            "{}",
+            failure
+        );
+    }
+
+    #[tokio::test]
+    async fn checkpoint_for_another_account_is_ignored() {
+        let did = Did::new(DID.to_string());
+        let checkpoint = AnalysisCheckpoint {
+            account_labels: Some(Vec::new()),
+            queried_uris: vec![NEWEST.to_string()],
+            ..AnalysisCheckpoint::new(
+                &Did::new("did:plc:someoneelse".to_string()),
+                &AnalysisScope::default(),
+            )
+        };
+        let abort = AbortHandle::new();
+        abort.abort();
+        let analyzer = BulkAnalyzer::new(None)
+            .with_engagement(false)
+            .with_abort(abort)
+            .with_checkpoint(checkpoint);
+
+        let analysis = analyzer
+            .analyze_posts(&did, posts(), None, &mut |_| {})
+            .await
+            .unwrap();
+        assert_eq!(analysis.checkpoint.did, DID);
+        assert!(analysis.checkpoint.queried_uris.is_empty());
+        assert_eq!(analysis.pending_posts, 4);
+    }
+
+    #[tokio::test]
+    async fn verify_repo_rejects_another_accounts_repository() {
+        let repo = Repository::from_car(include_bytes!("../tests/fixtures/repo.car")).unwrap();
+        let other = Did::new("did:plc:someoneelse".to_string());
+        assert_eq!(verify_repo(&repo, &other).await, None);
+    }
+
+    #[test]
+    fn checkpoint_goes_stale() {
+        let did = Did::new(DID.to_string());
+        let scope = AnalysisScope::default();
+        let checkpoint = AnalysisCheckpoint::new(&did, &scope);
+        let now = checkpoint.created_at;
+
+        assert_eq!(checkpoint.stale_reason(&did, &scope, now), None);
+        let hours = |h| now + chrono::Duration::hours(h);
+        assert_eq!(
+            checkpoint.stale_reason(&did, &scope, hours(CHECKPOINT_TTL_HOURS - 1)),
+            None
+        );
+        assert!(checkpoint
+            .stale_reason(&did, &scope, hours(CHECKPOINT_TTL_HOURS))
+            .is_some());
+
+        let replies_only = AnalysisScope {
+            include_replies: false,
+            ..scope.clone()
+        };
+        assert!(checkpoint.stale_reason(&did, &replies_only, now).is_some());
+        let other = Did::new("did:plc:someoneelse".to_string());
+        assert!(checkpoint.stale_reason(&other, &scope, now).is_some());
+    }
+
+    #[test]
+    fn checkpoint_without_a_start_time_is_stale() {
+        let did = Did::new(DID.to_string());
+        let saved = format!(r#"{{"did":"{}","queried_uris":[],"labels":[]}}"#, DID);
+        let checkpoint: AnalysisCheckpoint = serde_json::from_str(&saved).unwrap();
+        assert!(checkpoint
+            .stale_reason(&did, &AnalysisScope::default(), chrono::Utc::now())
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn checkpoint_for_another_scope_is_ignored() {
+        let did = Did::new(DID.to_string());
+        let checkpoint = AnalysisCheckpoint {
+            account_labels: Some(Vec::new()),
+            queried_uris: vec![NEWEST.to_string()],
+            ..AnalysisCheckpoint::new(&did, &AnalysisScope::default())
+        };
+        let abort = AbortHandle::new();
+        abort.abort();
+        let analyzer = BulkAnalyzer::new(None)
+            .with_engagement(false)
+            .with_abort(abort)
+            .with_scope(AnalysisScope {
+                include_replies: false,
+                ..AnalysisScope::default()
+            })
+            .with_checkpoint(checkpoint);
+
+        let analysis = analyzer
+            .analyze_posts(&did, posts(), None, &mut |_| {})
+            .await
+            .unwrap();
+        assert!(analysis.checkpoint.queried_uris.is_empty());
+        assert!(!analysis.checkpoint.scope.include_replies);
+    }
+}
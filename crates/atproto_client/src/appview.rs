@@ -0,0 +1,264 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Public AppView queries: accounts that liked or reposted a post, an account's follows
+//! and followers, list members, profiles, post counts and post threads
+
+use crate::{Error, PostThread, Result, UserInfo};
+
+/// Bluesky's unauthenticated AppView
+const PUBLIC_APPVIEW_URL: &str = "https://public.api.bsky.app";
+
+/// Accounts per page of an actor list
+const ACTOR_PAGE_SIZE: &str = "100";
+
+/// Most levels of replies and parents getPostThread returns
+const MAX_THREAD_LEVELS: &str = "1000";
+
+/// Most actors per getProfiles request
+pub const MAX_PROFILES_PER_REQUEST: usize = 25;
+
+/// An account list the AppView serves a page at a time
+///
+/// The AppView leaves out accounts that are taken down or deactivated, so these lists
+/// can be shorter than the records behind them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActorList {
+    /// Accounts an actor follows (`app.bsky.graph.getFollows`)
+    Follows(String),
+    /// Accounts following an actor (`app.bsky.graph.getFollowers`)
+    Followers(String),
+    /// Accounts that liked a post (`app.bsky.feed.getLikes`)
+    Likes(String),
+    /// Accounts that reposted a post (`app.bsky.feed.getRepostedBy`)
+    RepostedBy(String),
+    /// Members of a list (`app.bsky.graph.getList`)
+    ListMembers(String),
+}
+
+impl ActorList {
+    /// (XRPC method, query parameter, response field)
+    fn endpoint(&self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            ActorList::Follows(_) => ("app.bsky.graph.getFollows", "actor", "follows"),
+            ActorList::Followers(_) => ("app.bsky.graph.getFollowers", "actor", "followers"),
+            ActorList::Likes(_) => ("app.bsky.feed.getLikes", "uri", "likes"),
+            ActorList::RepostedBy(_) => ("app.bsky.feed.getRepostedBy", "uri", "repostedBy"),
+            ActorList::ListMembers(_) => ("app.bsky.graph.getList", "list", "items"),
+        }
+    }
+
+    fn subject(&self) -> &str {
+        match self {
+            ActorList::Follows(subject)
+            | ActorList::Followers(subject)
+            | ActorList::Likes(subject)
+            | ActorList::RepostedBy(subject)
+            | ActorList::ListMembers(subject) => subject,
+        }
+    }
+}
+
+/// Client for the Bluesky AppView's `app.bsky.*` read endpoints
+#[derive(Clone)]
+pub struct AppViewClient {
+    client: reqwest::Client,
+    appview_url: String,
+}
+
+impl AppViewClient {
+    /// Create a client for the public AppView
+    pub fn new() -> Self {
+        Self::with_url(PUBLIC_APPVIEW_URL.to_string())
+    }
+
+    /// Create a client for a custom AppView URL
+    pub fn with_url(appview_url: String) -> Self {
+        Self {
+            client: reqwest::Client::builder().build().unwrap_or_default(),
+            appview_url,
+        }
+    }
+
+    /// One page of an actor list, with the cursor of the next page if there is one
+    pub async fn actor_page(
+        &self,
+        list: &ActorList,
+        cursor: Option<&str>,
+    ) -> Result<(Vec<UserInfo>, Option<String>)> {
+        let (method, param, field) = list.endpoint();
+        let mut params = vec![(param, list.subject()), ("limit", ACTOR_PAGE_SIZE)];
+        params.extend(cursor.map(|cursor| ("cursor", cursor)));
+        let json = self.get(method, &params).await?;
+        Ok(actor_page(&json, field))
+    }
+
+    /// Profiles of up to [`MAX_PROFILES_PER_REQUEST`] accounts; unknown or taken down
+    /// accounts are left out
+    pub async fn get_profiles(&self, dids: &[String]) -> Result<Vec<UserInfo>> {
+        let params: Vec<(&str, &str)> = dids
+            .iter()
+            .take(MAX_PROFILES_PER_REQUEST)
+            .map(|did| ("actors", did.as_str()))
+            .collect();
+        let json = self.get("app.bsky.actor.getProfiles", &params).await?;
+
+        Ok(json
+            .get("profiles")
+            .and_then(|p| p.as_array())
+            .map(|profiles| profiles.iter().filter_map(UserInfo::from_profile).collect())
+            .unwrap_or_default())
+    }
+
+    /// A post's like and repost counts as the AppView reports them
+    ///
+    /// These count every like and repost the AppView indexed, including ones by accounts
+    /// [`ActorList::Likes`] and [`ActorList::RepostedBy`] leave out.
+    pub async fn get_post_counts(&self, uri: &str) -> Result<(usize, usize)> {
+        let json = self.get("app.bsky.feed.getPosts", &[("uris", uri)]).await?;
+        let post = json
+            .get("posts")
+            .and_then(|p| p.as_array())
+            .and_then(|posts| posts.first())
+            .ok_or_else(|| Error::RecordNotFound(format!("{} is not on the AppView", uri)))?;
+
+        Ok(post_counts(post))
+    }
+
+    /// A post's thread: every parent up to the root and every reply below it
+    pub async fn get_post_thread(&self, uri: &str) -> Result<PostThread> {
+        let params = [
+            ("uri", uri),
+            ("depth", MAX_THREAD_LEVELS),
+            ("parentHeight", MAX_THREAD_LEVELS),
+        ];
+        let json = self.get("app.bsky.feed.getPostThread", &params).await?;
+        let thread = json.get("thread").ok_or_else(|| {
+            Error::Parse(format!("No thread in getPostThread response for {}", uri))
+        })?;
+
+        Ok(PostThread::from_view(uri, thread))
+    }
+
+    async fn get(&self, method: &str, params: &[(&str, &str)]) -> Result<serde_json::Value> {
+        let query: String = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params)
+            .finish();
+        let url = format!("{}/xrpc/{}?{}", self.appview_url, method, query);
+
+        log::debug!("Querying AppView: {}", url);
+
+        let response = self.client.get(&url).send().await.map_err(Error::Network)?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(Error::LabelerUnavailable(format!(
+                "AppView {} failed (HTTP {}): {}",
+                method, status, error_text
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Parse(format!("Failed to parse {} response: {}", method, e)))
+    }
+}
+
+/// The accounts in one page of an actor list response, and the next page's cursor
+fn actor_page(json: &serde_json::Value, field: &str) -> (Vec<UserInfo>, Option<String>) {
+    let actors = json
+        .get(field)
+        .and_then(|a| a.as_array())
+        .map(|items| {
+            items
+                .iter()
+                // Likes and list items wrap the profile along with the like or item
+                .filter_map(|item| {
+                    let profile = item.get("actor").or_else(|| item.get("subject"));
+                    UserInfo::from_profile(profile.unwrap_or(item))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let cursor = json
+        .get("cursor")
+        .and_then(|c| c.as_str())
+        .map(str::to_string);
+    (actors, cursor)
+}
+
+/// (likeCount, repostCount) of a `postView`, 0 when missing
+fn post_counts(post: &serde_json::Value) -> (usize, usize) {
+    let count = |field: &str| post.get(field).and_then(|c| c.as_u64()).unwrap_or(0) as usize;
+    (count("likeCount"), count("repostCount"))
+}
+
+impl Default for AppViewClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(json: &str) -> serde_json::Value {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn handles(actors: &[UserInfo]) -> Vec<&str> {
+        actors.iter().map(|actor| actor.handle.as_str()).collect()
+    }
+
+    #[test]
+    fn likes_wrap_the_profile_in_actor() {
+        let list = ActorList::Likes(String::new());
+        let (_, _, field) = list.endpoint();
+        let (actors, cursor) = actor_page(
+            &fixture(include_str!("../tests/fixtures/get_likes.json")),
+            field,
+        );
+
+        assert_eq!(handles(&actors), ["alice.bsky.social", "bob.example.com"]);
+        assert_eq!(actors[0].display_name.as_deref(), Some("Alice"));
+        assert_eq!(actors[1].display_name, None);
+        assert_eq!(cursor.as_deref(), Some("1772700000000::bafyreig"));
+    }
+
+    #[test]
+    fn list_items_wrap_the_profile_in_subject() {
+        let list = ActorList::ListMembers(String::new());
+        let (_, _, field) = list.endpoint();
+        let (actors, cursor) = actor_page(
+            &fixture(include_str!("../tests/fixtures/get_list.json")),
+            field,
+        );
+
+        assert_eq!(handles(&actors), ["carol.example.com", "dave.bsky.social"]);
+        assert_eq!(actors[0].did, "did:plc:carolcarolcarolcarolcaro");
+        // The last page has no cursor
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn follows_are_bare_profiles() {
+        let list = ActorList::Follows(String::new());
+        let (_, _, field) = list.endpoint();
+        let (actors, cursor) = actor_page(
+            &fixture(include_str!("../tests/fixtures/get_follows.json")),
+            field,
+        );
+
+        // The profile without a handle is left out
+        assert_eq!(handles(&actors), ["erin.bsky.social", "frank.bsky.social"]);
+        assert_eq!(cursor.as_deref(), Some("3lbxa2b3c4d2e"));
+    }
+
+    #[test]
+    fn post_counts_default_to_zero() {
+        let post =
+            serde_json::json!({ "uri": "at://did:plc:x/app.bsky.feed.post/1", "likeCount": 12 });
+        assert_eq!(post_counts(&post), (12, 0));
+    }
+}
@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! CARv1 (Content Addressable aRchive) parsing
+
+use std::collections::HashMap;
+
+use crate::encoding::read_varint;
+use crate::{dagcbor, Cid, Error, Result};
+
+/// A parsed CARv1 file: root CIDs plus every block keyed by its CID
+#[derive(Debug, Clone)]
+pub struct CarFile {
+    pub roots: Vec<Cid>,
+    pub blocks: HashMap<Cid, Vec<u8>>,
+}
+
+impl CarFile {
+    /// Parse a CARv1 byte stream
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let (header_len, n) = read_varint(bytes)
+            .map_err(|e| Error::Parse(format!("Invalid CAR header length: {}", e)))?;
+        let mut pos = n;
+
+        let header_end = section_end(bytes, pos, header_len)?;
+        let header = dagcbor::decode(&bytes[pos..header_end])
+            .map_err(|e| Error::Parse(format!("Invalid CAR header: {}", e)))?;
+        pos = header_end;
+
+        match header.get("version").and_then(|v| v.as_integer()) {
+            Some(1) => {}
+            Some(2) => {
+                return Err(Error::Parse(
+                    "CARv2 files are not supported, export as CARv1".to_string(),
+                ))
+            }
+            other => {
+                return Err(Error::Parse(format!(
+                    "Unsupported CAR version: {:?}",
+                    other
+                )))
+            }
+        }
+
+        let roots = header
+            .get("roots")
+            .and_then(|r| r.as_list())
+            .map(|roots| roots.iter().filter_map(|r| r.as_link().cloned()).collect())
+            .unwrap_or_default();
+
+        let mut blocks = HashMap::new();
+
+        while pos < bytes.len() {
+            let (section_len, n) = read_varint(&bytes[pos..])?;
+            pos += n;
+
+            let end = section_end(bytes, pos, section_len)?;
+            let (cid, cid_len) = Cid::read(&bytes[pos..end])?;
+            blocks.insert(cid, bytes[pos + cid_len..end].to_vec());
+            pos = end;
+        }
+
+        log::debug!("Parsed CAR with {} blocks", blocks.len());
+
+        Ok(Self { roots, blocks })
+    }
+
+    /// Look up a block by CID
+    pub fn get(&self, cid: &Cid) -> Option<&[u8]> {
+        self.blocks.get(cid).map(Vec::as_slice)
+    }
+}
+
+fn section_end(bytes: &[u8], start: usize, len: u64) -> Result<usize> {
+    usize::try_from(len)
+        .ok()
+        .and_then(|len| start.checked_add(len))
+        .filter(|&end| end <= bytes.len())
+        .ok_or_else(|| Error::Parse("Truncated CAR section".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ipld;
+
+    /// A repository export: signed commit, two MST nodes and three records
+    const REPO_CAR: &[u8] = include_bytes!("../tests/fixtures/repo.car");
+
+    const COMMIT: &str = "bafyreibuat76coipgcc3uoplexziiiwg7r76r42gii56od35apbwy7iysu";
+
+    /// A CAR consisting of just a header
+    fn header_only(header: &Ipld) -> Vec<u8> {
+        let header = dagcbor::encode(header);
+        let mut car = vec![header.len() as u8];
+        car.extend_from_slice(&header);
+        car
+    }
+
+    #[test]
+    fn parses_recorded_car() {
+        let car = CarFile::parse(REPO_CAR).unwrap();
+        let commit: Cid = COMMIT.parse().unwrap();
+
+        assert_eq!(car.roots, vec![commit.clone()]);
+        assert_eq!(car.blocks.len(), 6);
+        assert!(car.get(&commit).is_some());
+        for (cid, block) in &car.blocks {
+            cid.verify_block(block).unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        let version = |v| {
+            Ipld::Map(
+                [
+                    ("version".to_string(), Ipld::Integer(v)),
+                    ("roots".to_string(), Ipld::List(Vec::new())),
+                ]
+                .into(),
+            )
+        };
+        assert!(CarFile::parse(&header_only(&version(1))).is_ok());
+
+        let err = CarFile::parse(&header_only(&version(2))).unwrap_err();
+        assert!(err.to_string().contains("CARv2"));
+        assert!(CarFile::parse(&header_only(&version(3))).is_err());
+        assert!(CarFile::parse(&header_only(&Ipld::Map(Default::default()))).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_sections() {
+        assert!(CarFile::parse(&[]).is_err());
+        // Header length past the end of the input
+        assert!(CarFile::parse(&[0x7f, 0xa0]).is_err());
+
+        // A block section claiming more bytes than are left
+        let mut car = REPO_CAR.to_vec();
+        car.truncate(car.len() - 1);
+        assert!(CarFile::parse(&car).is_err());
+
+        // An empty block section has no CID
+        let mut car = REPO_CAR.to_vec();
+        car.push(0x00);
+        assert!(CarFile::parse(&car).is_err());
+    }
+
+    #[test]
+    fn truncated_input_never_panics() {
+        for len in 0..REPO_CAR.len() {
+            let _ = CarFile::parse(&REPO_CAR[..len]);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Content identifiers (CIDv1) for repository blocks
+
+use sha2::{Digest, Sha256};
+
+use crate::encoding::{base32_decode, base32_encode, read_varint};
+use crate::{Error, Result};
+
+/// Multihash code for SHA-256, the only hash ATproto repositories use
+const SHA2_256: u64 = 0x12;
+
+/// A binary CIDv1, displayed in its canonical base32 (`b...`) string form
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cid(Vec<u8>);
+
+impl Cid {
+    /// Parse a CID occupying exactly the given bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (cid, len) = Self::read(bytes)?;
+        if len != bytes.len() {
+            return Err(Error::Parse(format!(
+                "Trailing bytes after CID ({} of {} used)",
+                len,
+                bytes.len()
+            )));
+        }
+        Ok(cid)
+    }
+
+    /// Parse a CID from the start of `data`, returning it and the number of bytes consumed
+    pub(crate) fn read(data: &[u8]) -> Result<(Self, usize)> {
+        // CIDv0 is a bare sha2-256 multihash; ATproto only uses CIDv1
+        if data.starts_with(&[0x12, 0x20]) {
+            return Err(Error::Parse("CIDv0 is not supported".to_string()));
+        }
+
+        let mut pos = 0;
+        let (version, n) = read_varint(data)?;
+        pos += n;
+        if version != 1 {
+            return Err(Error::Parse(format!("Unsupported CID version {}", version)));
+        }
+
+        let (_codec, n) = read_varint(&data[pos..])?;
+        pos += n;
+        let (_hash_code, n) = read_varint(&data[pos..])?;
+        pos += n;
+        let (digest_len, n) = read_varint(&data[pos..])?;
+        pos += n;
+
+        let end = pos
+            .checked_add(digest_len as usize)
+            .filter(|&end| end <= data.len())
+            .ok_or_else(|| Error::Parse("Truncated CID digest".to_string()))?;
+
+        Ok((Self(data[..end].to_vec()), end))
+    }
+
+    /// Check that `block` hashes to this CID
+    pub fn verify_block(&self, block: &[u8]) -> Result<()> {
+        if self.hash_code() != SHA2_256 {
+            return Err(Error::Verification(format!(
+                "Unsupported hash function 0x{:x} in {}",
+                self.hash_code(),
+                self
+            )));
+        }
+
+        if Sha256::digest(block)[..] != *self.digest() {
+            return Err(Error::Verification(format!(
+                "Block content does not match CID {}",
+                self
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// The binary CID
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Content multicodec (e.g. [`DAG_CBOR_CODEC`])
+    pub fn codec(&self) -> u64 {
+        self.fields().0
+    }
+
+    /// Multihash function code (e.g. [`SHA2_256`])
+    pub fn hash_code(&self) -> u64 {
+        self.fields().1
+    }
+
+    /// Raw hash digest
+    pub fn digest(&self) -> &[u8] {
+        &self.0[self.fields().2..]
+    }
+
+    /// (codec, hash code, digest offset); the bytes were validated on construction
+    fn fields(&self) -> (u64, u64, usize) {
+        let mut pos = read_varint(&self.0).map(|(_, n)| n).unwrap_or(0);
+        let (codec, n) = read_varint(&self.0[pos..]).unwrap_or((0, 0));
+        pos += n;
+        let (hash_code, n) = read_varint(&self.0[pos..]).unwrap_or((0, 0));
+        pos += n;
+        let (_, n) = read_varint(&self.0[pos..]).unwrap_or((0, 0));
+        (codec, hash_code, pos + n)
+    }
+}
+
+impl std::fmt::Display for Cid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "b{}", base32_encode(&self.0))
+    }
+}
+
+impl std::fmt::Debug for Cid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cid({})", self)
+    }
+}
+
+impl std::str::FromStr for Cid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let encoded = s
+            .strip_prefix('b')
+            .ok_or_else(|| Error::Parse(format!("Unsupported CID multibase: {}", s)))?;
+        Self::from_bytes(&base32_decode(encoded)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// CID of the DAG-CBOR encoding of an empty map (`0xa0`)
+    const EMPTY_MAP: &str = "bafyreigbtj4x7ip5legnfznufuopl4sg4knzc2cof6duas4b3q2fy6swua";
+
+    /// DAG-CBOR codec
+    const DAG_CBOR: u64 = 0x71;
+
+    #[test]
+    fn string_and_bytes_round_trip() {
+        let cid: Cid = EMPTY_MAP.parse().unwrap();
+        assert_eq!(cid.to_string(), EMPTY_MAP);
+        assert_eq!(cid.as_bytes().len(), 36);
+        assert_eq!(cid.as_bytes()[..4], [0x01, 0x71, 0x12, 0x20]);
+        assert_eq!(Cid::from_bytes(cid.as_bytes()).unwrap(), cid);
+
+        assert_eq!(cid.codec(), DAG_CBOR);
+        assert_eq!(cid.hash_code(), SHA2_256);
+        assert_eq!(cid.digest().len(), 32);
+    }
+
+    #[test]
+    fn verify_block_checks_the_digest() {
+        let cid: Cid = EMPTY_MAP.parse().unwrap();
+        assert!(cid.verify_block(&[0xa0]).is_ok());
+        assert!(cid.verify_block(&[0xa1, 0x61, 0x61, 0x01]).is_err());
+    }
+
+    #[test]
+    fn read_reports_bytes_consumed() {
+        let cid: Cid = EMPTY_MAP.parse().unwrap();
+        let mut data = cid.as_bytes().to_vec();
+        data.extend_from_slice(&[0xa0]);
+
+        assert_eq!(Cid::read(&data).unwrap(), (cid, 36));
+        assert!(Cid::from_bytes(&data).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_cids() {
+        let cid: Cid = EMPTY_MAP.parse().unwrap();
+        let bytes = cid.as_bytes();
+
+        // CIDv0 strings and other multibases
+        assert!("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n"
+            .parse::<Cid>()
+            .is_err());
+        assert!("zb2rhe5P4gXftAwvA4eXQ5HJwsER2owDyS9sKaQRRVQPn93bA"
+            .parse::<Cid>()
+            .is_err());
+        assert!("b".parse::<Cid>().is_err());
+        assert!("bafyrei1".parse::<Cid>().is_err());
+
+        // A bare sha2-256 multihash (CIDv0), a future version, and a short digest
+        assert!(Cid::from_bytes(&bytes[2..]).is_err());
+        let mut version_2 = bytes.to_vec();
+        version_2[0] = 0x02;
+        assert!(Cid::from_bytes(&version_2).is_err());
+        for len in 0..bytes.len() {
+            assert!(Cid::from_bytes(&bytes[..len]).is_err(), "{} bytes", len);
+        }
+    }
+}
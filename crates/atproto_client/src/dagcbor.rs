@@ -0,0 +1,475 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Minimal DAG-CBOR codec for repository blocks
+
+use std::collections::BTreeMap;
+
+use crate::encoding::base64_encode;
+use crate::{Cid, Error, Result};
+
+/// CBOR tag used by DAG-CBOR for CID links
+const CID_TAG: u64 = 42;
+
+/// Nesting limit, well above anything found in real records
+const MAX_DEPTH: usize = 128;
+
+/// A decoded DAG-CBOR value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ipld {
+    Null,
+    Bool(bool),
+    Integer(i128),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    List(Vec<Ipld>),
+    Map(BTreeMap<String, Ipld>),
+    Link(Cid),
+}
+
+impl Ipld {
+    /// Look up a key if this is a map
+    pub fn get(&self, key: &str) -> Option<&Ipld> {
+        match self {
+            Self::Map(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_link(&self) -> Option<&Cid> {
+        match self {
+            Self::Link(cid) => Some(cid),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Self::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Ipld]> {
+        match self {
+            Self::List(l) => Some(l),
+            _ => None,
+        }
+    }
+
+    /// Convert to the ATproto JSON data model (`{"$link"}` for CIDs, `{"$bytes"}` for bytes)
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value;
+
+        match self {
+            Self::Null => Value::Null,
+            Self::Bool(b) => Value::Bool(*b),
+            Self::Integer(i) => i64::try_from(*i)
+                .map(Value::from)
+                .unwrap_or_else(|_| Value::from(*i as f64)),
+            Self::Float(f) => serde_json::Number::from_f64(*f)
+                .map(Value::Number)
+                .unwrap_or(Value::Null),
+            Self::String(s) => Value::String(s.clone()),
+            Self::Bytes(b) => serde_json::json!({ "$bytes": base64_encode(b) }),
+            Self::List(l) => Value::Array(l.iter().map(Ipld::to_json).collect()),
+            Self::Map(m) => {
+                Value::Object(m.iter().map(|(k, v)| (k.clone(), v.to_json())).collect())
+            }
+            Self::Link(cid) => serde_json::json!({ "$link": cid.to_string() }),
+        }
+    }
+}
+
+/// Decode a single DAG-CBOR block
+pub fn decode(bytes: &[u8]) -> Result<Ipld> {
+    let mut decoder = Decoder {
+        data: bytes,
+        pos: 0,
+    };
+    let value = decoder.value(0)?;
+
+    if decoder.pos != bytes.len() {
+        return Err(Error::Parse(format!(
+            "Trailing data after DAG-CBOR value ({} of {} bytes used)",
+            decoder.pos,
+            bytes.len()
+        )));
+    }
+
+    Ok(value)
+}
+
+/// Encode a value in canonical DAG-CBOR form (shortest integers, length-first key order)
+///
+/// Needed to reproduce the exact bytes a commit signature was computed over.
+pub fn encode(value: &Ipld) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_into(value, &mut out);
+    out
+}
+
+fn encode_head(major: u8, arg: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    if arg < 24 {
+        out.push(major | arg as u8);
+    } else if arg <= u64::from(u8::MAX) {
+        out.extend_from_slice(&[major | 24, arg as u8]);
+    } else if arg <= u64::from(u16::MAX) {
+        out.push(major | 25);
+        out.extend_from_slice(&(arg as u16).to_be_bytes());
+    } else if arg <= u64::from(u32::MAX) {
+        out.push(major | 26);
+        out.extend_from_slice(&(arg as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&arg.to_be_bytes());
+    }
+}
+
+fn encode_into(value: &Ipld, out: &mut Vec<u8>) {
+    match value {
+        Ipld::Null => out.push(0xf6),
+        Ipld::Bool(false) => out.push(0xf4),
+        Ipld::Bool(true) => out.push(0xf5),
+        Ipld::Integer(i) if *i >= 0 => encode_head(0, *i as u64, out),
+        Ipld::Integer(i) => encode_head(1, (-1 - *i) as u64, out),
+        Ipld::Float(f) => {
+            // DAG-CBOR always uses 64-bit floats
+            out.push(0xfb);
+            out.extend_from_slice(&f.to_bits().to_be_bytes());
+        }
+        Ipld::String(s) => {
+            encode_head(3, s.len() as u64, out);
+            out.extend_from_slice(s.as_bytes());
+        }
+        Ipld::Bytes(b) => {
+            encode_head(2, b.len() as u64, out);
+            out.extend_from_slice(b);
+        }
+        Ipld::List(items) => {
+            encode_head(4, items.len() as u64, out);
+            for item in items {
+                encode_into(item, out);
+            }
+        }
+        Ipld::Map(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+            encode_head(5, entries.len() as u64, out);
+            for (key, value) in entries {
+                encode_head(3, key.len() as u64, out);
+                out.extend_from_slice(key.as_bytes());
+                encode_into(value, out);
+            }
+        }
+        Ipld::Link(cid) => {
+            encode_head(6, CID_TAG, out);
+            encode_head(2, cid.as_bytes().len() as u64 + 1, out);
+            out.push(0);
+            out.extend_from_slice(cid.as_bytes());
+        }
+    }
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Decoder<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| Error::Parse("Unexpected end of DAG-CBOR data".to_string()))?;
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    /// Read an initial byte and its argument: (major type, additional info, argument)
+    fn head(&mut self) -> Result<(u8, u8, u64)> {
+        let initial = self.take(1)?[0];
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+
+        let arg = match info {
+            0..=23 => u64::from(info),
+            24 => u64::from(self.take(1)?[0]),
+            25 => u64::from(u16::from_be_bytes(
+                self.take(2)?.try_into().unwrap_or_default(),
+            )),
+            26 => u64::from(u32::from_be_bytes(
+                self.take(4)?.try_into().unwrap_or_default(),
+            )),
+            27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap_or_default()),
+            31 => {
+                return Err(Error::Parse(
+                    "Indefinite-length items are not allowed in DAG-CBOR".to_string(),
+                ))
+            }
+            _ => {
+                return Err(Error::Parse(format!(
+                    "Reserved CBOR additional info {}",
+                    info
+                )))
+            }
+        };
+
+        Ok((major, info, arg))
+    }
+
+    fn length(&self, arg: u64) -> Result<usize> {
+        // Every element needs at least one byte, so longer lengths are necessarily bogus
+        usize::try_from(arg)
+            .ok()
+            .filter(|&len| len <= self.data.len() - self.pos)
+            .ok_or_else(|| Error::Parse(format!("DAG-CBOR length {} exceeds input", arg)))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Ipld> {
+        if depth > MAX_DEPTH {
+            return Err(Error::Parse("DAG-CBOR nesting too deep".to_string()));
+        }
+
+        let (major, info, arg) = self.head()?;
+
+        match major {
+            0 => Ok(Ipld::Integer(i128::from(arg))),
+            1 => Ok(Ipld::Integer(-1 - i128::from(arg))),
+            2 => {
+                let len = self.length(arg)?;
+                Ok(Ipld::Bytes(self.take(len)?.to_vec()))
+            }
+            3 => {
+                let len = self.length(arg)?;
+                let bytes = self.take(len)?;
+                String::from_utf8(bytes.to_vec())
+                    .map(Ipld::String)
+                    .map_err(|_| Error::Parse("Invalid UTF-8 in DAG-CBOR string".to_string()))
+            }
+            4 => {
+                let len = self.length(arg)?;
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(self.value(depth + 1)?);
+                }
+                Ok(Ipld::List(items))
+            }
+            5 => {
+                let len = self.length(arg)?;
+                let mut map = BTreeMap::new();
+                for _ in 0..len {
+                    let key = match self.value(depth + 1)? {
+                        Ipld::String(key) => key,
+                        _ => {
+                            return Err(Error::Parse(
+                                "DAG-CBOR map keys must be strings".to_string(),
+                            ))
+                        }
+                    };
+                    let value = self.value(depth + 1)?;
+                    if map.insert(key, value).is_some() {
+                        return Err(Error::Parse("Duplicate DAG-CBOR map key".to_string()));
+                    }
+                }
+                Ok(Ipld::Map(map))
+            }
+            6 => {
+                if arg != CID_TAG {
+                    return Err(Error::Parse(format!("Unsupported CBOR tag {}", arg)));
+                }
+                match self.value(depth + 1)? {
+                    // Binary CIDs carry the multibase identity prefix 0x00
+                    Ipld::Bytes(bytes) if bytes.first() == Some(&0) => {
+                        Ok(Ipld::Link(Cid::from_bytes(&bytes[1..])?))
+                    }
+                    _ => Err(Error::Parse("Malformed CID link".to_string())),
+                }
+            }
+            _ => match info {
+                20 => Ok(Ipld::Bool(false)),
+                21 => Ok(Ipld::Bool(true)),
+                22 | 23 => Ok(Ipld::Null),
+                25 => Ok(Ipld::Float(f16_to_f64(arg as u16))),
+                26 => Ok(Ipld::Float(f64::from(f32::from_bits(arg as u32)))),
+                27 => Ok(Ipld::Float(f64::from_bits(arg))),
+                _ => Err(Error::Parse(format!(
+                    "Unsupported CBOR simple value {}",
+                    info
+                ))),
+            },
+        }
+    }
+}
+
+fn f16_to_f64(half: u16) -> f64 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = i32::from((half >> 10) & 0x1f);
+    let mantissa = f64::from(half & 0x3ff);
+
+    match exponent {
+        0 => sign * mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => sign * f64::INFINITY,
+        31 => f64::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: &[(&str, Ipld)]) -> Ipld {
+        Ipld::Map(
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn encodes_known_vectors() {
+        assert_eq!(encode(&Ipld::Integer(0)), [0x00]);
+        assert_eq!(encode(&Ipld::Integer(23)), [0x17]);
+        assert_eq!(encode(&Ipld::Integer(24)), [0x18, 0x18]);
+        assert_eq!(encode(&Ipld::Integer(256)), [0x19, 0x01, 0x00]);
+        assert_eq!(encode(&Ipld::Integer(-1)), [0x20]);
+        assert_eq!(encode(&Ipld::Integer(-25)), [0x38, 0x18]);
+        assert_eq!(encode(&Ipld::Bool(true)), [0xf5]);
+        assert_eq!(encode(&Ipld::Null), [0xf6]);
+        assert_eq!(
+            encode(&Ipld::Float(1.5)),
+            [0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            encode(&map(&[("a", Ipld::Integer(1))])),
+            [0xa1, 0x61, 0x61, 0x01]
+        );
+    }
+
+    #[test]
+    fn encodes_map_keys_shortest_first() {
+        let value = map(&[
+            ("bb", Ipld::Integer(1)),
+            ("c", Ipld::Integer(2)),
+            ("a", Ipld::Integer(3)),
+        ]);
+        assert_eq!(
+            encode(&value),
+            [0xa3, 0x61, 0x61, 0x03, 0x61, 0x63, 0x02, 0x62, 0x62, 0x62, 0x01]
+        );
+    }
+
+    #[test]
+    fn round_trips_every_kind() {
+        let cid: Cid = "bafyreigbtj4x7ip5legnfznufuopl4sg4knzc2cof6duas4b3q2fy6swua"
+            .parse()
+            .unwrap();
+        let value = map(&[
+            ("null", Ipld::Null),
+            ("bool", Ipld::Bool(false)),
+            ("small", Ipld::Integer(-7)),
+            ("large", Ipld::Integer(i128::from(u64::MAX))),
+            ("float", Ipld::Float(-0.25)),
+            ("text", Ipld::String("héllo".to_string())),
+            ("bytes", Ipld::Bytes(vec![0, 1, 2, 0xff])),
+            (
+                "list",
+                Ipld::List(vec![Ipld::Integer(1), Ipld::String(String::new())]),
+            ),
+            ("link", Ipld::Link(cid)),
+        ]);
+
+        let bytes = encode(&value);
+        assert_eq!(decode(&bytes).unwrap(), value);
+        assert_eq!(encode(&decode(&bytes).unwrap()), bytes);
+    }
+
+    #[test]
+    fn decodes_shorter_floats() {
+        assert_eq!(decode(&[0xf9, 0x3e, 0x00]).unwrap(), Ipld::Float(1.5));
+        assert_eq!(
+            decode(&[0xfa, 0x3f, 0xc0, 0x00, 0x00]).unwrap(),
+            Ipld::Float(1.5)
+        );
+    }
+
+    #[test]
+    fn converts_to_atproto_json() {
+        let cid: Cid = "bafyreigbtj4x7ip5legnfznufuopl4sg4knzc2cof6duas4b3q2fy6swua"
+            .parse()
+            .unwrap();
+        let value = map(&[
+            ("link", Ipld::Link(cid)),
+            ("sig", Ipld::Bytes(b"foo".to_vec())),
+            ("n", Ipld::Integer(3)),
+        ]);
+        assert_eq!(
+            value.to_json(),
+            serde_json::json!({
+                "link": { "$link": "bafyreigbtj4x7ip5legnfznufuopl4sg4knzc2cof6duas4b3q2fy6swua" },
+                "sig": { "$bytes": "Zm9v" },
+                "n": 3
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        let mut too_deep = vec![0x81; MAX_DEPTH + 2];
+        too_deep.push(0x00);
+
+        let cases: [(&str, &[u8]); 14] = [
+            ("empty", &[]),
+            ("truncated string", &[0x63, 0x61, 0x62]),
+            ("truncated argument", &[0x19, 0x01]),
+            ("indefinite list", &[0x9f, 0x01, 0xff]),
+            ("reserved info", &[0x1c]),
+            ("trailing data", &[0x01, 0x02]),
+            ("integer key", &[0xa1, 0x01, 0x02]),
+            ("duplicate key", &[0xa2, 0x61, 0x61, 0x01, 0x61, 0x61, 0x02]),
+            ("other tag", &[0xc1, 0x00]),
+            ("link without prefix", &[0xd8, 0x2a, 0x42, 0x01, 0x71]),
+            ("link to garbage", &[0xd8, 0x2a, 0x42, 0x00, 0x01]),
+            ("invalid UTF-8", &[0x62, 0xff, 0xfe]),
+            (
+                "huge length",
+                &[0x5b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            ),
+            ("too deep", &too_deep),
+        ];
+        for (name, bytes) in cases {
+            assert!(decode(bytes).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn rejects_every_truncation() {
+        let value = map(&[
+            ("text", Ipld::String("hello".to_string())),
+            ("list", Ipld::List(vec![Ipld::Integer(1000), Ipld::Null])),
+        ]);
+        let bytes = encode(&value);
+        for len in 0..bytes.len() {
+            assert!(decode(&bytes[..len]).is_err(), "{} bytes", len);
+        }
+    }
+}
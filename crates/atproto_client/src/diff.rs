@@ -0,0 +1,295 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Changes between two looks at the same subject's labels
+//!
+//! Labels that went away are told apart by why: negated by their labeler, past their
+//! `exp`, or simply no longer returned (e.g. a restricted label queried without auth).
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::Label;
+
+/// (src, uri, val): one labeler's label on one subject
+type LabelKey<'a> = (&'a str, &'a str, &'a str);
+
+/// How a label changed between two snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelChangeKind {
+    Added,
+    Negated,
+    Expired,
+    Removed,
+}
+
+impl LabelChangeKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LabelChangeKind::Added => "Added",
+            LabelChangeKind::Negated => "Negated",
+            LabelChangeKind::Expired => "Expired",
+            LabelChangeKind::Removed => "Removed",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            LabelChangeKind::Added => "➕",
+            LabelChangeKind::Negated => "↩️",
+            LabelChangeKind::Expired => "⌛",
+            LabelChangeKind::Removed => "➖",
+        }
+    }
+}
+
+/// One label that appeared or went away
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelChange {
+    pub kind: LabelChangeKind,
+    /// The new label for additions, the label that went away otherwise
+    pub label: Label,
+}
+
+/// Every label change between two snapshots, additions first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LabelDiff {
+    pub changes: Vec<LabelChange>,
+}
+
+impl LabelDiff {
+    /// Changes from the labels in effect at `previous_at` to those in effect at `now`
+    ///
+    /// `negations` are the negation labels returned alongside `current`, as found by
+    /// [`LabelCollection::raw_labels`](crate::LabelCollection::raw_labels).
+    pub fn between(
+        previous: &[Label],
+        previous_at: DateTime<Utc>,
+        current: &[Label],
+        negations: &[Label],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let before = in_effect(previous, previous_at);
+        let after = in_effect(current, now);
+        let negated: HashMap<LabelKey, &Label> = negations
+            .iter()
+            .filter(|label| label.neg)
+            .map(|label| (key(label), label))
+            .collect();
+
+        let mut changes: Vec<LabelChange> = after
+            .iter()
+            .filter(|(key, _)| !before.contains_key(*key))
+            .map(|(_, label)| LabelChange {
+                kind: LabelChangeKind::Added,
+                label: (*label).clone(),
+            })
+            .collect();
+
+        for (key, label) in &before {
+            if after.contains_key(key) {
+                continue;
+            }
+            let kind = if negated
+                .get(key)
+                .is_some_and(|negation| created_at(negation) >= created_at(label))
+            {
+                LabelChangeKind::Negated
+            } else if label.is_expired_at(now)
+                || current
+                    .iter()
+                    .any(|other| self::key(other) == *key && other.is_expired_at(now))
+            {
+                LabelChangeKind::Expired
+            } else {
+                LabelChangeKind::Removed
+            };
+            changes.push(LabelChange {
+                kind,
+                label: (*label).clone(),
+            });
+        }
+
+        changes.sort_by(|a, b| {
+            (a.kind, &a.label.val, &a.label.uri).cmp(&(b.kind, &b.label.val, &b.label.uri))
+        });
+        LabelDiff { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Changes of one kind
+    pub fn of_kind(&self, kind: LabelChangeKind) -> impl Iterator<Item = &LabelChange> {
+        self.changes
+            .iter()
+            .filter(move |change| change.kind == kind)
+    }
+
+    /// e.g. "2 added, 1 negated", or "no changes"
+    pub fn summary(&self) -> String {
+        let parts: Vec<String> = [
+            LabelChangeKind::Added,
+            LabelChangeKind::Negated,
+            LabelChangeKind::Expired,
+            LabelChangeKind::Removed,
+        ]
+        .into_iter()
+        .filter_map(|kind| {
+            let count = self.of_kind(kind).count();
+            (count > 0).then(|| format!("{} {}", count, kind.name().to_lowercase()))
+        })
+        .collect();
+
+        if parts.is_empty() {
+            "no changes".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Labels in effect at `at`, one per labeler, subject and value
+fn in_effect(labels: &[Label], at: DateTime<Utc>) -> HashMap<LabelKey<'_>, &Label> {
+    labels
+        .iter()
+        .filter(|label| !label.neg && !label.is_expired_at(at))
+        .map(|label| (key(label), label))
+        .collect()
+}
+
+fn key(label: &Label) -> LabelKey<'_> {
+    (&label.src, &label.uri, &label.val)
+}
+
+fn created_at(label: &Label) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&label.cts)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = "did:plc:ar7c4by46qjdydhdevvrndac";
+    const DID: &str = "did:plc:4qqizocrnriintskkh6trnzv";
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        timestamp.parse().unwrap()
+    }
+
+    fn label(val: &str, cts: &str, exp: Option<&str>, neg: bool) -> Label {
+        Label {
+            val: val.to_string(),
+            uri: DID.to_string(),
+            cid: None,
+            src: SRC.to_string(),
+            cts: cts.to_string(),
+            exp: exp.map(str::to_string),
+            neg,
+            self_applied: false,
+            provenance: None,
+        }
+    }
+
+    fn kinds(diff: &LabelDiff) -> Vec<(LabelChangeKind, &str)> {
+        diff.changes
+            .iter()
+            .map(|change| (change.kind, change.label.val.as_str()))
+            .collect()
+    }
+
+    const BEFORE: &str = "2026-03-01T00:00:00Z";
+    const NOW: &str = "2026-03-10T00:00:00Z";
+
+    #[test]
+    fn new_label_is_added() {
+        let spam = label("spam", "2026-03-05T00:00:00Z", None, false);
+        let diff = LabelDiff::between(&[], at(BEFORE), &[spam], &[], at(NOW));
+        assert_eq!(kinds(&diff), [(LabelChangeKind::Added, "spam")]);
+        assert_eq!(diff.summary(), "1 added");
+    }
+
+    #[test]
+    fn unchanged_label_is_no_change() {
+        let spam = label("spam", "2026-02-01T00:00:00Z", None, false);
+        let diff = LabelDiff::between(
+            std::slice::from_ref(&spam),
+            at(BEFORE),
+            std::slice::from_ref(&spam),
+            &[],
+            at(NOW),
+        );
+        assert!(diff.is_empty());
+        assert_eq!(diff.summary(), "no changes");
+    }
+
+    #[test]
+    fn later_negation_is_negated() {
+        let spam = label("spam", "2026-02-01T00:00:00Z", None, false);
+        let negation = label("spam", "2026-03-05T00:00:00Z", None, true);
+        let diff = LabelDiff::between(&[spam], at(BEFORE), &[], &[negation], at(NOW));
+        assert_eq!(kinds(&diff), [(LabelChangeKind::Negated, "spam")]);
+    }
+
+    #[test]
+    fn older_negation_does_not_count() {
+        let spam = label("spam", "2026-02-01T00:00:00Z", None, false);
+        let stale = label("spam", "2026-01-01T00:00:00Z", None, true);
+        let diff = LabelDiff::between(&[spam], at(BEFORE), &[], &[stale], at(NOW));
+        assert_eq!(kinds(&diff), [(LabelChangeKind::Removed, "spam")]);
+    }
+
+    #[test]
+    fn passed_expiry_is_expired() {
+        let spam = label(
+            "spam",
+            "2026-02-01T00:00:00Z",
+            Some("2026-03-05T00:00:00Z"),
+            false,
+        );
+        // Still returned by the labeler, but no longer in effect
+        let diff = LabelDiff::between(
+            std::slice::from_ref(&spam),
+            at(BEFORE),
+            std::slice::from_ref(&spam),
+            &[],
+            at(NOW),
+        );
+        assert_eq!(kinds(&diff), [(LabelChangeKind::Expired, "spam")]);
+
+        // Or no longer returned at all
+        let diff = LabelDiff::between(&[spam], at(BEFORE), &[], &[], at(NOW));
+        assert_eq!(kinds(&diff), [(LabelChangeKind::Expired, "spam")]);
+    }
+
+    #[test]
+    fn missing_label_is_removed() {
+        let porn = label("porn", "2026-02-01T00:00:00Z", None, false);
+        let diff = LabelDiff::between(&[porn], at(BEFORE), &[], &[], at(NOW));
+        assert_eq!(kinds(&diff), [(LabelChangeKind::Removed, "porn")]);
+    }
+
+    #[test]
+    fn changes_are_sorted_additions_first() {
+        let before = [
+            label("porn", "2026-02-01T00:00:00Z", None, false),
+            label("spam", "2026-02-01T00:00:00Z", None, false),
+        ];
+        let current = [label("rude", "2026-03-05T00:00:00Z", None, false)];
+        let negations = [label("spam", "2026-03-05T00:00:00Z", None, true)];
+        let diff = LabelDiff::between(&before, at(BEFORE), &current, &negations, at(NOW));
+        assert_eq!(
+            kinds(&diff),
+            [
+                (LabelChangeKind::Added, "rude"),
+                (LabelChangeKind::Negated, "spam"),
+                (LabelChangeKind::Removed, "porn"),
+            ]
+        );
+        assert_eq!(diff.summary(), "1 added, 1 negated, 1 removed");
+    }
+}
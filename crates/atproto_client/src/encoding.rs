@@ -0,0 +1,195 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Small binary encodings used by repository data (varints, base32, base58, base64)
+
+use crate::{Error, Result};
+
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Read an unsigned LEB128 varint, returning the value and the number of bytes consumed
+pub(crate) fn read_varint(data: &[u8]) -> Result<(u64, usize)> {
+    let mut value: u64 = 0;
+
+    for (i, byte) in data.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+
+    Err(Error::Parse("Truncated or oversized varint".to_string()))
+}
+
+/// RFC 4648 base32, lowercase, no padding (the multibase `b` encoding)
+pub(crate) fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &byte in data {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    out
+}
+
+/// Decode RFC 4648 base32 (case-insensitive, padding optional)
+pub(crate) fn base32_decode(input: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in input.trim_end_matches('=').bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_lowercase())
+            .ok_or_else(|| Error::Parse(format!("Invalid base32 character '{}'", c as char)))?;
+
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Decode base58btc (the multibase `z` encoding used for DID document keys)
+pub(crate) fn base58_decode(input: &str) -> Result<Vec<u8>> {
+    // Little-endian base-256 accumulator
+    let mut bytes: Vec<u8> = Vec::with_capacity(input.len());
+
+    for c in input.bytes() {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or_else(|| Error::Parse(format!("Invalid base58 character '{}'", c as char)))?
+            as u32;
+
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    // Each leading '1' encodes a leading zero byte
+    let zeros = input.bytes().take_while(|&c| c == b'1').count();
+    bytes.resize(bytes.len() + zeros, 0);
+    bytes.reverse();
+
+    Ok(bytes)
+}
+
+/// Standard base64 without padding, as used for `$bytes` in ATproto JSON
+pub(crate) fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+
+        for i in 0..=chunk.len() {
+            out.push(BASE64_ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_varint_decodes_leb128() {
+        assert_eq!(read_varint(&[0x01]).unwrap(), (1, 1));
+        assert_eq!(read_varint(&[0x7f, 0xff]).unwrap(), (127, 1));
+        assert_eq!(read_varint(&[0xac, 0x02]).unwrap(), (300, 2));
+        assert_eq!(read_varint(&[0xe7, 0x01]).unwrap(), (0xe7, 2));
+    }
+
+    #[test]
+    fn read_varint_rejects_truncated_and_oversized() {
+        assert!(read_varint(&[]).is_err());
+        assert!(read_varint(&[0x80]).is_err());
+        assert!(read_varint(&[0xff; 11]).is_err());
+    }
+
+    #[test]
+    fn base32_matches_rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "my"),
+            ("fo", "mzxq"),
+            ("foo", "mzxw6"),
+            ("foob", "mzxw6yq"),
+            ("fooba", "mzxw6ytb"),
+            ("foobar", "mzxw6ytboi"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(base32_encode(plain.as_bytes()), encoded);
+            assert_eq!(base32_decode(encoded).unwrap(), plain.as_bytes());
+        }
+        assert_eq!(base32_decode("MZXW6YQ=").unwrap(), b"foob");
+    }
+
+    #[test]
+    fn base32_rejects_invalid_characters() {
+        assert!(base32_decode("mzxw1").is_err());
+        assert!(base32_decode("mz xw").is_err());
+    }
+
+    #[test]
+    fn base58_decodes_known_vectors() {
+        assert_eq!(base58_decode("2NEpo7TZRRrLZSi2U").unwrap(), b"Hello World!");
+        assert_eq!(base58_decode("112").unwrap(), [0, 0, 1]);
+        assert_eq!(base58_decode("").unwrap(), b"");
+    }
+
+    #[test]
+    fn base58_rejects_invalid_characters() {
+        // 0, O, I and l are left out of the alphabet
+        for input in ["0", "2NEpo7TZRRrLZSi2O", "Il"] {
+            assert!(base58_decode(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn base64_matches_rfc4648_vectors_unpadded() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg"),
+            ("fo", "Zm8"),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg"),
+            ("fooba", "Zm9vYmE"),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(base64_encode(plain.as_bytes()), encoded);
+        }
+        assert_eq!(base64_encode(&[0xfb, 0xff]), "+/8");
+    }
+}
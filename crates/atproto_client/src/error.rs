@@ -14,6 +14,9 @@ pub enum Error {
     #[error("Invalid AT-URI format: {0}")]
     InvalidAtUri(String),
 
+    #[error("Unrecognized input: {0}")]
+    UnrecognizedInput(String),
+
     #[error("Network error: {0}")]
     Network(#[from] reqwest::Error),
 
@@ -29,9 +32,20 @@ pub enum Error {
     #[error("Rate limited: retry after {0:?} seconds")]
     RateLimited(Option<u64>),
 
+    /// The PDS answered with the XRPC error `RecordNotFound`: the record is deleted or
+    /// never existed
+    #[error("Record not found: {0}")]
+    RecordNotFound(String),
+
     #[error("Parse error: {0}")]
     Parse(String),
 
+    #[error("Verification failed: {0}")]
+    Verification(String),
+
+    #[error("Cancelled")]
+    Cancelled,
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 }
@@ -0,0 +1,672 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Downloadable reports: bulk analyses as JSON, CSV or a self-contained HTML page, single
+//! checks as JSON or Markdown with the raw labeler responses as evidence, and batch checks
+//! as CSV
+//!
+//! Everything is rendered in memory, so the web UI can offer downloads without a server.
+
+use std::fmt::Write;
+
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::{BulkAnalysis, Label, LabelCollection, PostWithLabels, Result, SubjectCheck};
+
+/// Columns of the CSV export, one row per (subject, label)
+pub const CSV_HEADER: &str =
+    "subject,collection,created_at,text,val,category,src,cts,exp,neg,self_applied,likes,reposts";
+
+/// Columns of the batch check CSV export, one row per (subject, label)
+pub const CHECKS_CSV_HEADER: &str = "input,subject,status,val,category,src,cts,exp,error";
+
+/// The account, stats, and every labeled post with its full label objects
+pub fn to_json(did: &str, analysis: &BulkAnalysis) -> Result<String> {
+    #[derive(Serialize)]
+    struct Report<'a> {
+        did: &'a str,
+        generated_at: String,
+        #[serde(flatten)]
+        analysis: &'a BulkAnalysis,
+    }
+
+    let report = Report {
+        did,
+        generated_at: Utc::now().to_rfc3339(),
+        analysis,
+    };
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+/// One row per (post, label); account labels use the DID as subject, and posts shown
+/// without labels get a single row with empty label columns
+pub fn to_csv(did: &str, analysis: &BulkAnalysis) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+
+    for label in &analysis.stats.account_labels {
+        let _ = writeln!(csv, "{}", csv_row(did, "", "", "", Some(label), None));
+    }
+
+    for post in &analysis.labeled_posts {
+        let row = |label| {
+            csv_row(
+                &post.uri,
+                &post.collection,
+                &post.created_at,
+                &post.text,
+                label,
+                Some(post),
+            )
+        };
+        if post.labels.is_empty() {
+            let _ = writeln!(csv, "{}", row(None));
+        }
+        for label in &post.labels {
+            let _ = writeln!(csv, "{}", row(Some(label)));
+        }
+    }
+
+    csv
+}
+
+/// Labels in the columns of [`CSV_HEADER`], one row each with the labeled URI as subject
+pub fn labels_to_csv<'a>(labels: impl IntoIterator<Item = &'a Label>) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+    for label in labels {
+        let _ = writeln!(csv, "{}", label_csv_row(label));
+    }
+    csv
+}
+
+/// One label in the columns of [`CSV_HEADER`], without a line break, for reports that
+/// append columns of their own
+pub fn label_csv_row(label: &Label) -> String {
+    csv_row(&label.uri, "", "", "", Some(label), None)
+}
+
+/// Standalone HTML page (inline styles, no scripts or external assets) for attaching to
+/// tickets
+pub fn to_html(did: &str, analysis: &BulkAnalysis) -> String {
+    let stats = &analysis.stats;
+    let generated_at = Utc::now().to_rfc3339();
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>at-peek report: {did}</title>
+<style>{style}</style>
+</head>
+<body>
+<h1>Label report for <code>{did}</code></h1>
+<p class="meta">Generated {generated_at} by at-peek</p>
+"#,
+        did = escape(did),
+        style = REPORT_STYLE,
+        generated_at = escape(&generated_at),
+    );
+
+    if let Some(reason) = &analysis.interruption {
+        let _ = writeln!(
+            html,
+            r#"<p class="warning">Partial results: the analysis stopped early ({}); {} records were not checked.</p>"#,
+            escape(reason),
+            analysis.pending_posts
+        );
+    }
+
+    let _ = write!(
+        html,
+        r#"<h2>Summary</h2>
+<table>
+<tr><th>Records analyzed</th><td>{}</td></tr>
+<tr><th>Externally labeled</th><td>{}</td></tr>
+<tr><th>Self-labeled</th><td>{}</td></tr>
+</table>
+"#,
+        stats.total_posts, stats.posts_with_labels, stats.self_labeled_posts
+    );
+
+    if !stats.account_labels.is_empty() {
+        html.push_str("<h2>Account labels</h2>\n");
+        labels_table(&mut html, &stats.account_labels);
+    }
+
+    if !stats.labels_by_category.is_empty() {
+        let mut categories: Vec<_> = stats.labels_by_category.iter().collect();
+        categories.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.name().cmp(b.0.name())));
+
+        html.push_str("<h2>Labels by category</h2>\n<table>\n");
+        for (category, count) in categories {
+            let _ = writeln!(
+                html,
+                "<tr><th>{} {}</th><td>{}</td></tr>",
+                category.icon(),
+                escape(category.name()),
+                count
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    if !stats.top_label_values.is_empty() {
+        html.push_str("<h2>Label values</h2>\n<table>\n");
+        for (value, count) in &stats.top_label_values {
+            let _ = writeln!(
+                html,
+                "<tr><th><code>{}</code></th><td>{}</td></tr>",
+                escape(value),
+                count
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    let _ = writeln!(
+        html,
+        "<h2>Labeled posts ({})</h2>",
+        analysis.labeled_posts.len()
+    );
+    for post in &analysis.labeled_posts {
+        post_section(&mut html, post);
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// A single check: the subject, every label with its provenance, and the raw responses
+pub fn labels_to_json(subject: &str, collection: &LabelCollection) -> Result<String> {
+    #[derive(Serialize)]
+    struct Report<'a> {
+        subject: &'a str,
+        generated_at: String,
+        #[serde(flatten)]
+        collection: &'a LabelCollection,
+    }
+
+    let report = Report {
+        subject,
+        generated_at: Utc::now().to_rfc3339(),
+        collection,
+    };
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+/// A batch check, in input order; subjects without labels get one row with no label
+///
+/// The `input` column is echoed from an imported file, so it is guarded against formulas
+/// like every other field.
+pub fn checks_to_csv(checks: &[SubjectCheck]) -> String {
+    let mut csv = format!("{}\n", CHECKS_CSV_HEADER);
+
+    for check in checks {
+        let subject = [
+            csv_field(&check.input),
+            csv_field(check.subject.as_deref().unwrap_or_default()),
+            csv_field(check.status().name()),
+        ]
+        .join(",");
+        let error = csv_field(check.error.as_deref().unwrap_or_default());
+
+        if check.labels.is_empty() {
+            let _ = writeln!(csv, "{},,,,,,{}", subject, error);
+        }
+        for label in &check.labels {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                subject,
+                csv_field(&label.val),
+                csv_field(label.category().name()),
+                csv_field(&label.src),
+                csv_field(&label.cts),
+                csv_field(label.exp.as_deref().unwrap_or_default()),
+                error
+            );
+        }
+    }
+
+    csv
+}
+
+/// A single check as Markdown for support tickets, raw responses in code blocks
+pub fn labels_to_markdown(subject: &str, collection: &LabelCollection) -> String {
+    let mut md = String::new();
+
+    let _ = writeln!(md, "# Label check for `{}`\n", subject);
+    let _ = writeln!(md, "- Queried: {}", collection.query_timestamp.to_rfc3339());
+    let _ = writeln!(md, "- Generated: {} by at-peek\n", Utc::now().to_rfc3339());
+
+    let _ = writeln!(md, "## Labels ({})\n", collection.labels.len());
+    if collection.labels.is_empty() {
+        md.push_str("No labels found.\n");
+    } else {
+        md.push_str(
+            "| Label | Category | Source | Created | Expires | Answered by | Authenticated |\n",
+        );
+        md.push_str("|---|---|---|---|---|---|---|\n");
+        for label in &collection.labels {
+            let (endpoint, authenticated) = match &label.provenance {
+                Some(p) => (
+                    p.endpoint.as_str(),
+                    if p.authenticated { "yes" } else { "no" },
+                ),
+                None => ("unknown", "unknown"),
+            };
+            let source = if label.self_applied {
+                format!("{} (self-label)", label.src)
+            } else {
+                label.src.clone()
+            };
+            let _ = writeln!(
+                md,
+                "| `{}` | {} | {} | {} | {} | {} | {} |",
+                table_cell(&label.val),
+                label.category().name(),
+                table_cell(&source),
+                table_cell(&label.cts),
+                table_cell(label.exp.as_deref().unwrap_or("never")),
+                table_cell(endpoint),
+                authenticated
+            );
+        }
+    }
+
+    if !collection.responses.is_empty() {
+        md.push_str("\n## Raw labeler responses\n");
+        for response in &collection.responses {
+            let body = serde_json::to_string_pretty(&response.body).unwrap_or_default();
+            let fence = code_fence(&body);
+            let _ = write!(
+                md,
+                "\n### `GET {}`\n\n- Status: {}\n- Authenticated: {}\n- Queried: {}\n\n{}json\n{}\n{}\n",
+                response.url,
+                response.status,
+                if response.authenticated { "yes" } else { "no" },
+                response.queried_at.to_rfc3339(),
+                fence,
+                body,
+                fence
+            );
+        }
+    }
+
+    md
+}
+
+const REPORT_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:60rem;margin:2rem auto;padding:0 1rem;color:#111}\
+h1{font-size:1.5rem}h2{font-size:1.2rem;margin-top:2rem}\
+table{border-collapse:collapse;margin:.5rem 0}th,td{border:1px solid #ddd;padding:.3rem .6rem;text-align:left;vertical-align:top}\
+th{background:#f5f5f5}.meta{color:#666}.warning{background:#fef3c7;border-left:4px solid #f59e0b;padding:.5rem 1rem}\
+.post{border:1px solid #ddd;border-radius:.5rem;padding:.75rem 1rem;margin:.75rem 0}.post p{white-space:pre-wrap}";
+
+fn post_section(html: &mut String, post: &PostWithLabels) {
+    let _ = write!(
+        html,
+        r#"<div class="post">
+<div class="meta"><a href="{url}">{uri}</a> · {created_at}</div>
+"#,
+        url = escape(&web_url(&post.uri)),
+        uri = escape(&post.uri),
+        created_at = escape(&post.created_at),
+    );
+
+    if !post.text.is_empty() {
+        let _ = writeln!(html, "<p>{}</p>", escape(&post.text));
+    }
+    if post.like_count > 0 || post.repost_count > 0 {
+        let _ = writeln!(
+            html,
+            r#"<div class="meta">{} like(s), {} repost(s)</div>"#,
+            post.like_count, post.repost_count
+        );
+    }
+    if let Some(error) = &post.engagement_error {
+        let _ = writeln!(
+            html,
+            r#"<div class="meta">Engagement is incomplete: {}</div>"#,
+            escape(error)
+        );
+    }
+    if let Some(commit) = &post.verified_commit {
+        let _ = writeln!(
+            html,
+            r#"<div class="meta">Verified against signed commit <code>{}</code></div>"#,
+            escape(commit)
+        );
+    }
+    if !post.labels.is_empty() {
+        labels_table(html, &post.labels);
+    }
+
+    html.push_str("</div>\n");
+}
+
+fn labels_table(html: &mut String, labels: &[Label]) {
+    html.push_str(
+        "<table>\n<tr><th>Label</th><th>Category</th><th>Source</th><th>Created</th><th>Expires</th></tr>\n",
+    );
+    for label in labels {
+        let source = if label.self_applied {
+            "self-label by author".to_string()
+        } else {
+            label.src.clone()
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td><code>{}{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            if label.neg { "¬" } else { "" },
+            escape(&label.val),
+            escape(label.category().name()),
+            escape(&source),
+            escape(&label.cts),
+            escape(label.exp.as_deref().unwrap_or("never")),
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn csv_row(
+    subject: &str,
+    collection: &str,
+    created_at: &str,
+    text: &str,
+    label: Option<&Label>,
+    post: Option<&PostWithLabels>,
+) -> String {
+    let label_fields = match label {
+        Some(label) => [
+            csv_field(&label.val),
+            csv_field(label.category().name()),
+            csv_field(&label.src),
+            csv_field(&label.cts),
+            csv_field(label.exp.as_deref().unwrap_or_default()),
+            label.neg.to_string(),
+            label.self_applied.to_string(),
+        ]
+        .join(","),
+        None => ",,,,,,".to_string(),
+    };
+    let engagement = match post {
+        Some(post) => format!("{},{}", post.like_count, post.repost_count),
+        None => ",".to_string(),
+    };
+
+    format!(
+        "{},{},{},{},{},{}",
+        csv_field(subject),
+        csv_field(collection),
+        csv_field(created_at),
+        csv_field(text),
+        label_fields,
+        engagement
+    )
+}
+
+/// Quote a CSV field if it contains a delimiter, quote, or line break (RFC 4180)
+///
+/// Post text, handles and label values come from other users, so a field a spreadsheet
+/// would read as a formula (starting with `=`, `+`, `-`, `@`, a tab or CR) is prefixed
+/// with `'` and quoted.
+pub fn csv_field(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("\"'{}\"", value.replace('"', "\"\""))
+    } else if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Keep a value from breaking out of its Markdown table cell
+fn table_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+/// A backtick fence longer than any backtick run in `body`
+fn code_fence(body: &str) -> String {
+    let longest_run = body
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    "`".repeat(longest_run.max(2) + 1)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// bsky.app link for a post, or the account for other records
+fn web_url(uri: &str) -> String {
+    let mut parts = uri.trim_start_matches("at://").splitn(3, '/');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(did), Some("app.bsky.feed.post"), Some(rkey)) => {
+            format!("https://bsky.app/profile/{}/post/{}", did, rkey)
+        }
+        (Some(did), _, _) => format!("https://bsky.app/profile/{}", did),
+        _ => uri.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BulkAnalysisStats, LabelProvenance, LabelerResponse};
+
+    fn post(text: &str, labels: Vec<Label>) -> PostWithLabels {
+        PostWithLabels {
+            uri: "at://did:plc:alice/app.bsky.feed.post/3k2a".to_string(),
+            collection: "app.bsky.feed.post".to_string(),
+            text: text.to_string(),
+            labels,
+            created_at: "2026-03-01T12:00:00Z".to_string(),
+            has_media: false,
+            image_urls: Vec::new(),
+            video_url: None,
+            like_count: 2,
+            repost_count: 1,
+            likers: Vec::new(),
+            reposters: Vec::new(),
+            verified_commit: None,
+            engagement_truncated: false,
+            engagement_error: None,
+            engagement_labels: None,
+        }
+    }
+
+    fn label(val: &str) -> Label {
+        Label {
+            val: val.to_string(),
+            uri: "at://did:plc:alice/app.bsky.feed.post/3k2a".to_string(),
+            cid: None,
+            src: "did:plc:ar7c4by46qjdydhdevvrndac".to_string(),
+            cts: "2026-03-01T13:00:00Z".to_string(),
+            exp: None,
+            neg: false,
+            self_applied: false,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn csv_field_quotes_delimiters() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn csv_field_neutralizes_formulas() {
+        for value in ["=1+1", "+1", "-1", "@SUM(A1)", "\tx", "\rx"] {
+            let field = csv_field(value);
+            assert!(field.starts_with("\"'"), "{:?} became {:?}", value, field);
+            assert!(field.ends_with('"'));
+        }
+    }
+
+    #[test]
+    fn to_csv_neutralizes_formula_in_post_text() {
+        let analysis = BulkAnalysis {
+            stats: BulkAnalysisStats::default(),
+            labeled_posts: vec![post(
+                "=HYPERLINK(\"https://evil.example\",\"click\")",
+                vec![label("spam")],
+            )],
+            ..BulkAnalysis::default()
+        };
+
+        let csv = to_csv("did:plc:alice", &analysis);
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.contains(",\"'=HYPERLINK(\"\"https://evil.example\"\",\"\"click\"\")\",spam,"));
+        assert!(!row.contains(",=HYPERLINK"));
+    }
+
+    #[test]
+    fn checks_to_csv_neutralizes_formula_in_input() {
+        let checks = [SubjectCheck {
+            input: "=cmd|' /C calc'!A0".to_string(),
+            subject: None,
+            labels: Vec::new(),
+            expired_labels: 0,
+            error: Some("not a handle".to_string()),
+        }];
+
+        let csv = checks_to_csv(&checks);
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.starts_with("\"'=cmd|' /C calc'!A0\",,Failed,"));
+    }
+
+    #[test]
+    fn labels_to_csv_uses_export_columns() {
+        let csv = labels_to_csv(&[label("spam")]);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(CSV_HEADER));
+        let row = lines.next().unwrap();
+        assert_eq!(row.split(',').count(), CSV_HEADER.split(',').count());
+        assert!(row.starts_with("at://did:plc:alice/app.bsky.feed.post/3k2a,,,,spam,"));
+        assert_eq!(lines.next(), None);
+    }
+
+    /// One recorded `queryLabels` response, answered without a token
+    fn collection(labels: Vec<Label>) -> LabelCollection {
+        LabelCollection {
+            labels,
+            labeler_did: "did:plc:ar7c4by46qjdydhdevvrndac".to_string(),
+            query_timestamp: "2026-03-10T00:00:00Z".parse().unwrap(),
+            responses: vec![LabelerResponse {
+                url: "https://mod.bsky.app/xrpc/com.atproto.label.queryLabels?uriPatterns=did%3Aplc%3Aalice"
+                    .to_string(),
+                authenticated: false,
+                queried_at: "2026-03-10T00:00:00Z".parse().unwrap(),
+                status: 200,
+                body: serde_json::from_str(include_str!("../tests/fixtures/query_labels.json"))
+                    .unwrap(),
+            }],
+        }
+    }
+
+    #[test]
+    fn table_cell_escapes_pipes_and_newlines() {
+        assert_eq!(table_cell("spam"), "spam");
+        assert_eq!(table_cell("a|b"), "a\\|b");
+        assert_eq!(table_cell("two\nlines"), "two lines");
+    }
+
+    #[test]
+    fn code_fence_outruns_every_backtick_run() {
+        assert_eq!(code_fence("{}"), "```");
+        assert_eq!(code_fence("``"), "```");
+        assert_eq!(code_fence("a ``` b ` c"), "````");
+        assert_eq!(code_fence("`````"), "``````");
+    }
+
+    #[test]
+    fn labels_to_markdown_lists_labels_and_raw_responses() {
+        let mut self_label = label("porn");
+        self_label.self_applied = true;
+        self_label.provenance = Some(LabelProvenance {
+            endpoint: "at://did:plc:alice/app.bsky.feed.post/3k2a".to_string(),
+            authenticated: true,
+            queried_at: "2026-03-10T00:00:00Z".parse().unwrap(),
+        });
+        let mut piped = label("a|b");
+        piped.exp = Some("2026-04-01T00:00:00Z".to_string());
+
+        let md = labels_to_markdown("did:plc:alice", &collection(vec![self_label, piped]));
+        assert!(md.starts_with("# Label check for `did:plc:alice`\n"));
+        assert!(md.contains("## Labels (2)\n"));
+        assert!(md.contains(
+            "| `porn` | Adult Content | did:plc:ar7c4by46qjdydhdevvrndac (self-label) | 2026-03-01T13:00:00Z | never | at://did:plc:alice/app.bsky.feed.post/3k2a | yes |"
+        ));
+        assert!(md.contains("| `a\\|b` |"));
+        assert!(md.contains("| 2026-04-01T00:00:00Z | unknown | unknown |"));
+        assert!(md.contains("### `GET https://mod.bsky.app/xrpc/com.atproto.label.queryLabels"));
+        assert!(md.contains("- Status: 200\n- Authenticated: no\n"));
+        assert!(md.contains("```json\n{\n"));
+    }
+
+    #[test]
+    fn labels_to_markdown_without_labels() {
+        let mut empty = collection(Vec::new());
+        empty.responses.clear();
+
+        let md = labels_to_markdown("did:plc:alice", &empty);
+        assert!(md.contains("## Labels (0)\n\nNo labels found.\n"));
+        assert!(!md.contains("Raw labeler responses"));
+    }
+
+    #[test]
+    fn labels_to_json_flattens_the_collection() {
+        let json = labels_to_json("did:plc:alice", &collection(vec![label("spam")])).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(report["subject"], "did:plc:alice");
+        assert!(report["generated_at"].is_string());
+        assert_eq!(report["labeler_did"], "did:plc:ar7c4by46qjdydhdevvrndac");
+        assert_eq!(report["labels"][0]["val"], "spam");
+        assert_eq!(report["responses"][0]["status"], 200);
+        assert_eq!(report["responses"][0]["body"]["cursor"], "5912");
+    }
+
+    #[test]
+    fn raw_labels_reparse_every_response() {
+        let raw = collection(Vec::new()).raw_labels();
+
+        let vals: Vec<&str> = raw.iter().map(|label| label.val.as_str()).collect();
+        assert_eq!(
+            vals,
+            ["spam", "sexual", "graphic-media", "spam", "porn", "!warn"]
+        );
+        // Negations are kept, unlike in the checked labels
+        assert!(raw[4].neg);
+        for label in &raw {
+            let provenance = label.provenance.as_ref().unwrap();
+            assert_eq!(provenance.endpoint, "https://mod.bsky.app");
+            assert!(!provenance.authenticated);
+        }
+    }
+
+    #[test]
+    fn raw_labels_skip_unreadable_bodies() {
+        let mut collection = collection(Vec::new());
+        collection.responses[0].status = 502;
+        collection.responses[0].body = serde_json::json!({ "error": "UpstreamFailure" });
+        assert!(collection.raw_labels().is_empty());
+    }
+}
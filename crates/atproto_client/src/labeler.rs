@@ -1,7 +1,12 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 //! Labeler service client for querying moderation labels
 
-use crate::{Did, Error, Label, LabelCollection, LabelsResponse, Result};
+use crate::{
+    Did, Error, Label, LabelCollection, LabelProvenance, LabelerResponse, LabelsResponse, Result,
+};
+
+/// Largest `limit` queryLabels accepts per page
+const MAX_PAGE_SIZE: usize = 250;
 
 /// Client for querying labels from a labeler service
 #[derive(Clone)]
@@ -60,20 +65,90 @@ impl LabelerClient {
     /// Query labels for multiple subjects (DIDs or AT-URIs)
     pub async fn query_labels(&self, subjects: &[String]) -> Result<LabelCollection> {
         if subjects.is_empty() {
-            return Ok(LabelCollection {
-                labels: Vec::new(),
-                labeler_did: self.labeler_url.clone(),
-                query_timestamp: chrono::Utc::now(),
-            });
+            return Ok(self.collection(Vec::new(), Vec::new()));
+        }
+
+        let (labels_response, raw) = self.fetch_labels_page(subjects, None, None).await?;
+
+        Ok(self.collection(labels_response.labels, vec![raw]))
+    }
+
+    /// Query every label matching the given patterns, following cursors
+    ///
+    /// Patterns may end in `*` (e.g. `at://did:plc:abc/*`, or just `*` for everything the
+    /// labeler has emitted). Stops once `max_labels` raw labels have been read.
+    pub async fn query_all_labels(
+        &self,
+        patterns: &[String],
+        max_labels: usize,
+    ) -> Result<LabelCollection> {
+        let mut labels = Vec::new();
+        let mut responses = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        while labels.len() < max_labels {
+            let page_size = (max_labels - labels.len()).min(MAX_PAGE_SIZE);
+            let (page, raw) = self
+                .fetch_labels_page(patterns, cursor.as_deref(), Some(page_size))
+                .await?;
+
+            let page_len = page.labels.len();
+            labels.extend(page.labels);
+            responses.push(raw);
+
+            log::info!("Fetched {} labels so far", labels.len());
+
+            match page.cursor {
+                Some(next) if page_len > 0 => cursor = Some(next),
+                _ => break,
+            }
         }
 
+        labels.truncate(max_labels);
+
+        Ok(self.collection(labels, responses))
+    }
+
+    /// Wrap raw labels from this labeler, dropping negations
+    fn collection(&self, labels: Vec<Label>, responses: Vec<LabelerResponse>) -> LabelCollection {
+        // Filter out negated labels
+        let active_labels: Vec<Label> = labels.into_iter().filter(|label| !label.neg).collect();
+
+        log::info!(
+            "Found {} active labels after filtering",
+            active_labels.len()
+        );
+
+        LabelCollection {
+            labels: active_labels,
+            labeler_did: self.labeler_url.clone(),
+            query_timestamp: chrono::Utc::now(),
+            responses,
+        }
+    }
+
+    /// One `com.atproto.label.queryLabels` request, with its raw response
+    ///
+    /// Every label is stamped with this labeler as its provenance.
+    async fn fetch_labels_page(
+        &self,
+        subjects: &[String],
+        cursor: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<(LabelsResponse, LabelerResponse)> {
         // Build URL with multiple uriPatterns query parameters
         // Note: Each URI must be a separate query parameter, not comma-separated!
-        let encoded_patterns: Vec<String> = subjects
+        let mut params: Vec<String> = subjects
             .iter()
             .map(|s| format!("uriPatterns={}", urlencoding::encode(s)))
             .collect();
-        let query_string = encoded_patterns.join("&");
+        if let Some(limit) = limit {
+            params.push(format!("limit={}", limit));
+        }
+        if let Some(cursor) = cursor {
+            params.push(format!("cursor={}", urlencoding::encode(cursor)));
+        }
+        let query_string = params.join("&");
         let url = format!(
             "{}/xrpc/com.atproto.label.queryLabels?{}",
             self.labeler_url, query_string
@@ -88,6 +163,7 @@ impl LabelerClient {
             request = request.header("Authorization", format!("Bearer {}", token));
         }
 
+        let queried_at = chrono::Utc::now();
         let response = request.send().await.map_err(Error::Network)?;
 
         let status = response.status();
@@ -141,7 +217,7 @@ impl LabelerClient {
             &response_text[..response_text.len().min(500)]
         );
 
-        let labels_response: LabelsResponse =
+        let mut labels_response: LabelsResponse =
             serde_json::from_str(&response_text).map_err(|e| {
                 Error::Parse(format!(
                     "Failed to parse label response: {}. Response: {}",
@@ -165,23 +241,24 @@ impl LabelerClient {
             );
         }
 
-        // Filter out negated labels
-        let active_labels: Vec<Label> = labels_response
-            .labels
-            .into_iter()
-            .filter(|label| !label.neg)
-            .collect();
+        let authenticated = self.auth_token.is_some();
+        for label in &mut labels_response.labels {
+            label.provenance = Some(LabelProvenance {
+                endpoint: self.labeler_url.clone(),
+                authenticated,
+                queried_at,
+            });
+        }
 
-        log::info!(
-            "Found {} active labels after filtering",
-            active_labels.len()
-        );
+        let raw = LabelerResponse {
+            url,
+            authenticated,
+            queried_at,
+            status: status.as_u16(),
+            body: serde_json::from_str(&response_text)?,
+        };
 
-        Ok(LabelCollection {
-            labels: active_labels,
-            labeler_did: self.labeler_url.clone(),
-            query_timestamp: chrono::Utc::now(),
-        })
+        Ok((labels_response, raw))
     }
 }
 
@@ -3,20 +3,65 @@
 
 #![forbid(unsafe_code)]
 
+#[cfg(all(feature = "native", target_arch = "wasm32"))]
+compile_error!("the `native` feature needs a native target; use `wasm` for wasm32 builds");
+
+mod abort;
+pub mod analysis;
+mod appview;
 mod auth;
+mod car;
+mod cid;
+mod dagcbor;
+mod diff;
+mod encoding;
 mod error;
+pub mod export;
 mod labeler;
 mod posts;
+mod repo;
 mod resolver;
+mod scope;
+mod self_labels;
+mod snapshot;
+mod subject;
+mod thread;
+mod timeline;
 mod types;
+mod verify;
 
 // Public API exports (used by web UI)
+pub use abort::AbortHandle;
+pub use analysis::{
+    AccountSweep, AnalysisCheckpoint, AnalysisEvent, BulkAnalysis, BulkAnalysisStats, BulkAnalyzer,
+    CheckStatus, ConnectedAccount, Connection, ListInspection, ListKind, ListMember, NetworkScan,
+    PostWithLabels, ProgressReporter, RecordStatus, SubjectCheck, SweptLabel,
+};
+pub use appview::{ActorList, AppViewClient};
 pub use auth::create_session;
+pub use car::CarFile;
+pub use cid::Cid;
+pub use dagcbor::Ipld;
+pub use diff::{LabelChange, LabelChangeKind, LabelDiff};
 pub use error::Error; // Export Error for error checking in UI
 pub use labeler::LabelerClient;
 pub use posts::PostClient;
-pub use resolver::{resolve_did, resolve_handle};
-pub use types::{AtRecord, Did, Handle, Label, LabelCategory, LabelCollection};
+pub use repo::{Commit, Repository};
+pub use resolver::{
+    resolve_did, resolve_did_document, resolve_handle, resolve_labeler_endpoint,
+    resolve_signing_key,
+};
+pub use scope::{AnalysisScope, ScopeCollection};
+pub use self_labels::extract_self_labels;
+pub use snapshot::{RecordSnapshot, Snapshot, SnapshotDiff, SnapshotKind};
+pub use subject::Subject;
+pub use thread::{PostThread, ThreadPost};
+pub use timeline::{LabelSpan, LabelTimeline, TimelineEvent, TimelineEventKind};
+pub use types::{
+    AtRecord, Did, Handle, Label, LabelCategory, LabelCollection, LabelProvenance, LabelerResponse,
+    UserInfo,
+};
+pub use verify::{verify_record_proof, SigningKey, VerifiedRecord};
 
 // Internal types (not exported, only used internally)
 pub(crate) use error::Result;
@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 //! Post fetching from ATproto PDS
 
-use crate::{resolver, AtRecord, Did, Error, ListRecordsResponse, Result};
+use crate::{
+    resolver, verify_record_proof, AtRecord, CarFile, Did, Error, ListRecordsResponse, Repository,
+    Result, VerifiedRecord,
+};
 
 /// Client for fetching posts from a PDS
 #[derive(Clone)]
@@ -24,14 +27,27 @@ impl PostClient {
         did: &Did,
         limit: Option<u32>,
         cursor: Option<String>,
+    ) -> Result<ListRecordsResponse> {
+        self.list_collection(did, "app.bsky.feed.post", limit, cursor)
+            .await
+    }
+
+    /// Fetch one page of any collection's records, newest first
+    pub async fn list_collection(
+        &self,
+        did: &Did,
+        collection: &str,
+        limit: Option<u32>,
+        cursor: Option<String>,
     ) -> Result<ListRecordsResponse> {
         // Resolve DID to PDS endpoint
         let pds_url = resolver::resolve_did(did).await?;
 
         let mut url = format!(
-            "{}/xrpc/com.atproto.repo.listRecords?repo={}&collection=app.bsky.feed.post",
+            "{}/xrpc/com.atproto.repo.listRecords?repo={}&collection={}",
             pds_url,
-            urlencoding::encode(did.as_str())
+            urlencoding::encode(did.as_str()),
+            urlencoding::encode(collection)
         );
 
         if let Some(lim) = limit {
@@ -42,7 +58,7 @@ impl PostClient {
             url.push_str(&format!("&cursor={}", urlencoding::encode(&cur)));
         }
 
-        log::debug!("Fetching posts from PDS: {}", url);
+        log::debug!("Fetching {} records from PDS: {}", collection, url);
 
         let response = self.client.get(&url).send().await.map_err(Error::Network)?;
 
@@ -54,52 +70,7 @@ impl PostClient {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
 
-            // Provide user-friendly messages for common PDS errors
-            let error_message = match status.as_u16() {
-                400 => format!(
-                    "Bad request to PDS (HTTP 400). The repository may not exist or be inaccessible. {}",
-                    if !error_text.is_empty() {
-                        format!("Details: {}", error_text)
-                    } else {
-                        String::new()
-                    }
-                ),
-                403 => format!(
-                    "Access forbidden (HTTP 403). This account may be suspended, banned, or have restricted access. \
-                    The account's posts cannot be retrieved. {}",
-                    if !error_text.is_empty() {
-                        format!("Details: {}", error_text)
-                    } else {
-                        String::new()
-                    }
-                ),
-                404 => format!(
-                    "Repository not found (HTTP 404). This account may have been deleted, deactivated, \
-                    or the PDS endpoint may be incorrect. {}",
-                    if !error_text.is_empty() {
-                        format!("Details: {}", error_text)
-                    } else {
-                        String::new()
-                    }
-                ),
-                410 => "Account has been permanently deleted (HTTP 410)".to_string(),
-                500..=599 => format!(
-                    "PDS server error (HTTP {}). The Personal Data Server is experiencing issues. \
-                    Try again later.",
-                    status
-                ),
-                _ => format!(
-                    "Failed to fetch posts from PDS (HTTP {}). {}",
-                    status,
-                    if !error_text.is_empty() {
-                        format!("Details: {}", error_text)
-                    } else {
-                        "The repository may be unavailable.".to_string()
-                    }
-                ),
-            };
-
-            return Err(Error::LabelerUnavailable(error_message));
+            return Err(pds_error(status, &error_text));
         }
 
         let records_response: ListRecordsResponse = response
@@ -107,11 +78,151 @@ impl PostClient {
             .await
             .map_err(|e| Error::Parse(format!("Failed to parse records response: {}", e)))?;
 
-        log::info!("Fetched {} posts from PDS", records_response.records.len());
+        log::info!(
+            "Fetched {} {} records from PDS",
+            records_response.records.len(),
+            collection
+        );
 
         Ok(records_response)
     }
 
+    /// Fetch a single record (e.g. a post or the `app.bsky.actor.profile/self` profile)
+    pub async fn get_record(&self, did: &Did, collection: &str, rkey: &str) -> Result<AtRecord> {
+        let pds_url = resolver::resolve_did(did).await?;
+
+        let url = format!(
+            "{}/xrpc/com.atproto.repo.getRecord?repo={}&collection={}&rkey={}",
+            pds_url,
+            urlencoding::encode(did.as_str()),
+            urlencoding::encode(collection),
+            urlencoding::encode(rkey)
+        );
+
+        log::debug!("Fetching record from PDS: {}", url);
+
+        let response = self.client.get(&url).send().await.map_err(Error::Network)?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+
+            return Err(pds_error(status, &error_text));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Parse(format!("Failed to parse record response: {}", e)))
+    }
+
+    /// Download the whole repository as a CAR file via `com.atproto.sync.getRepo`
+    ///
+    /// One request returns every record in every collection, so this is much cheaper
+    /// than paging `listRecords` for large accounts.
+    pub async fn fetch_repo(&self, did: &Did) -> Result<Repository> {
+        let pds_url = resolver::resolve_did(did).await?;
+
+        let url = format!(
+            "{}/xrpc/com.atproto.sync.getRepo?did={}",
+            pds_url,
+            urlencoding::encode(did.as_str())
+        );
+
+        log::debug!("Downloading repository from PDS: {}", url);
+
+        let response = self.client.get(&url).send().await.map_err(Error::Network)?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+
+            return Err(pds_error(status, &error_text));
+        }
+
+        let bytes = response.bytes().await.map_err(Error::Network)?;
+
+        log::info!("Downloaded {} byte repository CAR for {}", bytes.len(), did);
+
+        let repo = Repository::from_car(&bytes)?;
+
+        if repo.did() != *did {
+            return Err(Error::Verification(format!(
+                "Repository commit is for {}, expected {}",
+                repo.commit().did,
+                did
+            )));
+        }
+
+        Ok(repo)
+    }
+
+    /// Fetch a record together with its MST inclusion proof via `com.atproto.sync.getRecord`
+    pub async fn fetch_record_proof(
+        &self,
+        did: &Did,
+        collection: &str,
+        rkey: &str,
+    ) -> Result<CarFile> {
+        let pds_url = resolver::resolve_did(did).await?;
+
+        let url = format!(
+            "{}/xrpc/com.atproto.sync.getRecord?did={}&collection={}&rkey={}",
+            pds_url,
+            urlencoding::encode(did.as_str()),
+            urlencoding::encode(collection),
+            urlencoding::encode(rkey)
+        );
+
+        log::debug!("Fetching record proof from PDS: {}", url);
+
+        let response = self.client.get(&url).send().await.map_err(Error::Network)?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+
+            return Err(pds_error(status, &error_text));
+        }
+
+        let bytes = response.bytes().await.map_err(Error::Network)?;
+
+        CarFile::parse(&bytes)
+    }
+
+    /// Fetch and verify a record's inclusion proof against the account's signing key
+    pub async fn verify_record(
+        &self,
+        did: &Did,
+        collection: &str,
+        rkey: &str,
+    ) -> Result<VerifiedRecord> {
+        let proof = self.fetch_record_proof(did, collection, rkey).await?;
+        let key = resolver::resolve_signing_key(did).await?;
+
+        let verified = verify_record_proof(&proof, did, collection, rkey, &key)?;
+
+        log::info!(
+            "Verified {} against signed commit {}",
+            verified.record.uri,
+            verified.commit_cid
+        );
+
+        Ok(verified)
+    }
+
     /// Fetch up to N posts for a given DID directly from their PDS
     pub async fn fetch_posts(&self, did: &Did, max_posts: usize) -> Result<Vec<AtRecord>> {
         let mut all_posts = Vec::new();
@@ -145,8 +256,104 @@ impl Default for PostClient {
     }
 }
 
+/// The error for a failed PDS request, typed by the XRPC `error` name where it matters
+fn pds_error(status: reqwest::StatusCode, error_text: &str) -> Error {
+    let body: Option<serde_json::Value> = serde_json::from_str(error_text).ok();
+    let field = |name: &str| {
+        body.as_ref()
+            .and_then(|b| b.get(name))
+            .and_then(|v| v.as_str())
+    };
+
+    match field("error") {
+        Some("RecordNotFound") => Error::RecordNotFound(
+            field("message")
+                .unwrap_or("the PDS has no such record")
+                .to_string(),
+        ),
+        _ => Error::LabelerUnavailable(pds_error_message(status, error_text)),
+    }
+}
+
+/// Provide user-friendly messages for common PDS errors
+fn pds_error_message(status: reqwest::StatusCode, error_text: &str) -> String {
+    match status.as_u16() {
+        400 => format!(
+            "Bad request to PDS (HTTP 400). The repository may not exist or be inaccessible. {}",
+            if !error_text.is_empty() {
+                format!("Details: {}", error_text)
+            } else {
+                String::new()
+            }
+        ),
+        403 => format!(
+            "Access forbidden (HTTP 403). This account may be suspended, banned, or have restricted access. \
+            The account's posts cannot be retrieved. {}",
+            if !error_text.is_empty() {
+                format!("Details: {}", error_text)
+            } else {
+                String::new()
+            }
+        ),
+        404 => format!(
+            "Repository not found (HTTP 404). This account may have been deleted, deactivated, \
+            or the PDS endpoint may be incorrect. {}",
+            if !error_text.is_empty() {
+                format!("Details: {}", error_text)
+            } else {
+                String::new()
+            }
+        ),
+        410 => "Account has been permanently deleted (HTTP 410)".to_string(),
+        500..=599 => format!(
+            "PDS server error (HTTP {}). The Personal Data Server is experiencing issues. \
+            Try again later.",
+            status
+        ),
+        _ => format!(
+            "Failed to fetch posts from PDS (HTTP {}). {}",
+            status,
+            if !error_text.is_empty() {
+                format!("Details: {}", error_text)
+            } else {
+                "The repository may be unavailable.".to_string()
+            }
+        ),
+    }
+}
+
 mod urlencoding {
     pub fn encode(s: &str) -> String {
         url::form_urlencoded::byte_serialize(s.as_bytes()).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_not_found_is_typed() {
+        let body = r#"{"error":"RecordNotFound","message":"Could not locate record: at://did:plc:4qqizocrnriintskkh6trnzv/app.bsky.feed.post/3lbxkcjh5ek2d"}"#;
+        match pds_error(reqwest::StatusCode::BAD_REQUEST, body) {
+            Error::RecordNotFound(message) => assert!(message.starts_with("Could not locate")),
+            other => panic!("expected RecordNotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn other_errors_keep_the_status_message() {
+        let body = r#"{"error":"InvalidRequest","message":"Could not find repo: did:plc:gone"}"#;
+        assert!(matches!(
+            pds_error(reqwest::StatusCode::BAD_REQUEST, body),
+            Error::LabelerUnavailable(message) if message.contains("HTTP 400")
+        ));
+
+        // A message that merely mentions the name isn't the XRPC error
+        let body = "upstream said RecordNotFound";
+        assert!(matches!(
+            pds_error(reqwest::StatusCode::BAD_GATEWAY, body),
+            Error::LabelerUnavailable(_)
+        ));
+    }
+}
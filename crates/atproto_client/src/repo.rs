@@ -0,0 +1,415 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Repository snapshots: signed commit plus the Merkle Search Tree of records
+
+use std::collections::HashMap;
+
+use crate::dagcbor::{self, Ipld};
+use crate::{AtRecord, CarFile, Cid, Did, Error, Result, SigningKey};
+
+/// A repository commit object (the CAR root)
+#[derive(Debug, Clone)]
+pub struct Commit {
+    /// DID of the repository owner
+    pub did: String,
+
+    /// Repo format version (3 for current repos)
+    pub version: i64,
+
+    /// Root of the Merkle Search Tree holding all records
+    pub data: Cid,
+
+    /// Revision TID (absent on version 2 commits)
+    pub rev: Option<String>,
+
+    /// Previous commit, if linked
+    pub prev: Option<Cid>,
+
+    /// Signature over the DAG-CBOR encoding of the commit without `sig`
+    pub sig: Vec<u8>,
+
+    /// The signed bytes: canonical DAG-CBOR of the commit with `sig` removed
+    unsigned_bytes: Vec<u8>,
+}
+
+impl Commit {
+    pub(crate) fn from_ipld(value: &Ipld) -> Result<Self> {
+        let field = |name: &str| {
+            value
+                .get(name)
+                .ok_or_else(|| Error::Parse(format!("Commit is missing '{}'", name)))
+        };
+
+        Ok(Self {
+            did: field("did")?
+                .as_str()
+                .ok_or_else(|| Error::Parse("Commit 'did' is not a string".to_string()))?
+                .to_string(),
+            version: field("version")?
+                .as_integer()
+                .and_then(|v| i64::try_from(v).ok())
+                .ok_or_else(|| Error::Parse("Commit 'version' is not an integer".to_string()))?,
+            data: field("data")?
+                .as_link()
+                .cloned()
+                .ok_or_else(|| Error::Parse("Commit 'data' is not a CID link".to_string()))?,
+            rev: value
+                .get("rev")
+                .and_then(|r| r.as_str())
+                .map(str::to_string),
+            prev: value.get("prev").and_then(|p| p.as_link()).cloned(),
+            sig: field("sig")?
+                .as_bytes()
+                .ok_or_else(|| Error::Parse("Commit 'sig' is not bytes".to_string()))?
+                .to_vec(),
+            unsigned_bytes: match value {
+                Ipld::Map(map) => {
+                    let mut unsigned = map.clone();
+                    unsigned.remove("sig");
+                    dagcbor::encode(&Ipld::Map(unsigned))
+                }
+                _ => Vec::new(),
+            },
+        })
+    }
+
+    /// Check the commit signature against the repository's `#atproto` signing key
+    pub fn verify_signature(&self, key: &SigningKey) -> Result<()> {
+        key.verify(&self.unsigned_bytes, &self.sig)
+    }
+}
+
+/// A full repository export, as returned by `com.atproto.sync.getRepo` or a settings export
+#[derive(Debug, Clone)]
+pub struct Repository {
+    commit: Commit,
+    commit_cid: Cid,
+    blocks: HashMap<Cid, Vec<u8>>,
+}
+
+impl Repository {
+    /// Parse a repository CAR file
+    pub fn from_car(bytes: &[u8]) -> Result<Self> {
+        Self::from_car_file(CarFile::parse(bytes)?)
+    }
+
+    /// Build a repository from an already parsed CAR file
+    pub fn from_car_file(car: CarFile) -> Result<Self> {
+        let commit_cid = car
+            .roots
+            .first()
+            .cloned()
+            .ok_or_else(|| Error::Parse("CAR file has no root commit".to_string()))?;
+
+        let commit_block = car
+            .get(&commit_cid)
+            .ok_or_else(|| Error::Parse(format!("Commit block {} missing from CAR", commit_cid)))?;
+        let commit = Commit::from_ipld(&dagcbor::decode(commit_block)?)?;
+
+        log::info!(
+            "Loaded repo {} (rev {}) with {} blocks",
+            commit.did,
+            commit.rev.as_deref().unwrap_or("?"),
+            car.blocks.len()
+        );
+
+        Ok(Self {
+            commit,
+            commit_cid,
+            blocks: car.blocks,
+        })
+    }
+
+    /// DID of the repository owner, as stated in the commit
+    pub fn did(&self) -> Did {
+        Did::new(self.commit.did.clone())
+    }
+
+    pub fn commit(&self) -> &Commit {
+        &self.commit
+    }
+
+    pub fn commit_cid(&self) -> &Cid {
+        &self.commit_cid
+    }
+
+    /// Raw block bytes by CID
+    pub fn block(&self, cid: &Cid) -> Option<&[u8]> {
+        self.blocks.get(cid).map(Vec::as_slice)
+    }
+
+    /// Verify the commit signature and that every block matches its CID
+    ///
+    /// After this succeeds, every record returned by [`Repository::records`] is covered
+    /// by the signature of the account's signing key.
+    pub fn verify(&self, key: &SigningKey) -> Result<()> {
+        self.commit.verify_signature(key)?;
+
+        for (cid, block) in &self.blocks {
+            cid.verify_block(block)?;
+        }
+
+        log::info!(
+            "Verified commit {} and {} blocks for {}",
+            self.commit_cid,
+            self.blocks.len(),
+            self.commit.did
+        );
+
+        Ok(())
+    }
+
+    /// Every `collection/rkey` key in the repo with the CID of its record, in key order
+    pub fn entries(&self) -> Result<Vec<(String, Cid)>> {
+        let mut entries = Vec::new();
+        walk_mst(&self.blocks, &self.commit.data, &mut entries, 0)?;
+        Ok(entries)
+    }
+
+    /// Distinct collection NSIDs present in the repo
+    pub fn collections(&self) -> Result<Vec<String>> {
+        let mut collections: Vec<String> = self
+            .entries()?
+            .into_iter()
+            .filter_map(|(key, _)| key.split_once('/').map(|(c, _)| c.to_string()))
+            .collect();
+        collections.dedup();
+        Ok(collections)
+    }
+
+    /// Every record in every collection, oldest rkey first within each collection
+    pub fn records(&self) -> Result<Vec<AtRecord>> {
+        self.records_matching(|_| true)
+    }
+
+    /// Records of a single collection (e.g. `app.bsky.feed.post`)
+    pub fn records_in(&self, collection: &str) -> Result<Vec<AtRecord>> {
+        let prefix = format!("{}/", collection);
+        self.records_matching(|key| key.starts_with(&prefix))
+    }
+
+    fn records_matching(&self, filter: impl Fn(&str) -> bool) -> Result<Vec<AtRecord>> {
+        let did = self.commit.did.as_str();
+        let mut records = Vec::new();
+
+        for (key, cid) in self.entries()? {
+            if !filter(&key) {
+                continue;
+            }
+
+            let Some(block) = self.blocks.get(&cid) else {
+                log::warn!("Record block {} for {} missing from CAR", cid, key);
+                continue;
+            };
+
+            match dagcbor::decode(block) {
+                Ok(value) => records.push(AtRecord {
+                    uri: format!("at://{}/{}", did, key),
+                    cid: cid.to_string(),
+                    value: value.to_json(),
+                }),
+                Err(e) => log::warn!("Skipping undecodable record {}: {}", key, e),
+            }
+        }
+
+        Ok(records)
+    }
+}
+
+/// MST depth is logarithmic in record count; anything deeper is malformed or hostile
+const MAX_MST_DEPTH: usize = 64;
+
+/// In-order walk of an MST node: left subtree, then each entry followed by its right subtree
+pub(crate) fn walk_mst(
+    blocks: &HashMap<Cid, Vec<u8>>,
+    node_cid: &Cid,
+    out: &mut Vec<(String, Cid)>,
+    depth: usize,
+) -> Result<()> {
+    if depth > MAX_MST_DEPTH {
+        return Err(Error::Parse("MST is too deep".to_string()));
+    }
+
+    let block = blocks
+        .get(node_cid)
+        .ok_or_else(|| Error::Parse(format!("MST node {} missing from CAR", node_cid)))?;
+    let node = dagcbor::decode(block)?;
+
+    if let Some(left) = node.get("l").and_then(|l| l.as_link()) {
+        walk_mst(blocks, left, out, depth + 1)?;
+    }
+
+    let mut last_key: Vec<u8> = Vec::new();
+
+    for entry in node.get("e").and_then(|e| e.as_list()).unwrap_or_default() {
+        let (key, value) = mst_entry(entry, &last_key)?;
+
+        out.push((
+            String::from_utf8(key.clone())
+                .map_err(|_| Error::Parse("MST key is not UTF-8".to_string()))?,
+            value,
+        ));
+
+        if let Some(right) = entry.get("t").and_then(|t| t.as_link()) {
+            walk_mst(blocks, right, out, depth + 1)?;
+        }
+
+        last_key = key;
+    }
+
+    Ok(())
+}
+
+/// Decode one MST entry, expanding its prefix-compressed key against the previous key
+pub(crate) fn mst_entry(entry: &Ipld, last_key: &[u8]) -> Result<(Vec<u8>, Cid)> {
+    let prefix_len = entry
+        .get("p")
+        .and_then(|p| p.as_integer())
+        .and_then(|p| usize::try_from(p).ok())
+        .ok_or_else(|| Error::Parse("MST entry has no prefix length".to_string()))?;
+    let suffix = entry
+        .get("k")
+        .and_then(|k| k.as_bytes())
+        .ok_or_else(|| Error::Parse("MST entry has no key".to_string()))?;
+    let value = entry
+        .get("v")
+        .and_then(|v| v.as_link())
+        .cloned()
+        .ok_or_else(|| Error::Parse("MST entry has no value CID".to_string()))?;
+
+    if prefix_len > last_key.len() {
+        return Err(Error::Parse(
+            "MST key prefix longer than previous key".to_string(),
+        ));
+    }
+
+    let mut key = last_key[..prefix_len].to_vec();
+    key.extend_from_slice(suffix);
+
+    Ok((key, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A repository export: signed commit, two MST nodes and three records
+    const REPO_CAR: &[u8] = include_bytes!("../tests/fixtures/repo.car");
+
+    const DID: &str = "did:plc:4qqizocrnriintskkh6trnzv";
+    const COMMIT: &str = "bafyreibuat76coipgcc3uoplexziiiwg7r76r42gii56od35apbwy7iysu";
+
+    #[test]
+    fn loads_recorded_repository() {
+        let repo = Repository::from_car(REPO_CAR).unwrap();
+
+        assert_eq!(repo.did(), Did::new(DID.to_string()));
+        assert_eq!(repo.commit_cid().to_string(), COMMIT);
+        assert_eq!(repo.commit().version, 3);
+        assert_eq!(repo.commit().rev.as_deref(), Some("3lby2abcdek2e"));
+        assert_eq!(repo.commit().prev, None);
+        assert_eq!(repo.commit().sig.len(), 64);
+    }
+
+    #[test]
+    fn walks_the_mst_in_key_order() {
+        let repo = Repository::from_car(REPO_CAR).unwrap();
+
+        let keys: Vec<String> = repo
+            .entries()
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                "app.bsky.actor.profile/self",
+                "app.bsky.feed.post/3lbxkcjh5ek2d",
+                "app.bsky.feed.post/3lbxq2zmv7k2a",
+            ]
+        );
+        assert_eq!(
+            repo.collections().unwrap(),
+            ["app.bsky.actor.profile", "app.bsky.feed.post"]
+        );
+    }
+
+    #[test]
+    fn reads_records_as_json() {
+        let repo = Repository::from_car(REPO_CAR).unwrap();
+        assert_eq!(repo.records().unwrap().len(), 3);
+
+        let posts = repo.records_in("app.bsky.feed.post").unwrap();
+        assert_eq!(posts.len(), 2);
+        assert_eq!(
+            posts[0].uri,
+            format!("at://{}/app.bsky.feed.post/3lbxkcjh5ek2d", DID)
+        );
+        assert_eq!(
+            posts[0].value["text"],
+            "Free followers, click the link in bio"
+        );
+        assert_eq!(posts[0].value["langs"], serde_json::json!(["en"]));
+
+        let block = repo.block(&posts[0].cid.parse().unwrap()).unwrap();
+        assert_eq!(dagcbor::decode(block).unwrap().to_json(), posts[0].value);
+    }
+
+    #[test]
+    fn commit_re_encodes_canonically() {
+        let repo = Repository::from_car(REPO_CAR).unwrap();
+        let block = repo.block(repo.commit_cid()).unwrap();
+        assert_eq!(dagcbor::encode(&dagcbor::decode(block).unwrap()), block);
+    }
+
+    #[test]
+    fn rejects_cars_without_a_usable_commit() {
+        let mut car = CarFile::parse(REPO_CAR).unwrap();
+        let commit: Cid = COMMIT.parse().unwrap();
+
+        let mut no_roots = car.clone();
+        no_roots.roots.clear();
+        assert!(Repository::from_car_file(no_roots).is_err());
+
+        let mut not_a_commit = car.clone();
+        not_a_commit.blocks.insert(commit.clone(), vec![0xa0]);
+        assert!(Repository::from_car_file(not_a_commit).is_err());
+
+        car.blocks.remove(&commit);
+        assert!(Repository::from_car_file(car).is_err());
+    }
+
+    #[test]
+    fn missing_mst_node_is_an_error() {
+        let mut car = CarFile::parse(REPO_CAR).unwrap();
+        let repo = Repository::from_car_file(car.clone()).unwrap();
+        car.blocks.remove(&repo.commit().data);
+
+        let repo = Repository::from_car_file(car).unwrap();
+        assert!(repo.entries().is_err());
+        assert!(repo.records().is_err());
+    }
+
+    #[test]
+    fn mst_entry_expands_prefixes() {
+        let cid: Cid = COMMIT.parse().unwrap();
+        let entry = |prefix: i128, suffix: &[u8]| {
+            Ipld::Map(
+                [
+                    ("p".to_string(), Ipld::Integer(prefix)),
+                    ("k".to_string(), Ipld::Bytes(suffix.to_vec())),
+                    ("v".to_string(), Ipld::Link(cid.clone())),
+                ]
+                .into(),
+            )
+        };
+
+        let (key, value) = mst_entry(&entry(19, b"3lbxq"), b"app.bsky.feed.post/3lbxk").unwrap();
+        assert_eq!(key, b"app.bsky.feed.post/3lbxq");
+        assert_eq!(value, cid);
+
+        assert!(mst_entry(&entry(5, b"x"), b"app").is_err());
+        assert!(mst_entry(&entry(-1, b"x"), b"app").is_err());
+        assert!(mst_entry(&Ipld::Map(Default::default()), b"").is_err());
+    }
+}
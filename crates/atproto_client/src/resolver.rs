@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 //! Handle to DID resolution
 
-use crate::{Did, Error, Handle, Result};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::{Did, Error, Handle, Result, SigningKey};
 
 /// Resolve a Bluesky handle to a DID via DNS or .well-known endpoint
 pub async fn resolve_handle(handle: &Handle) -> Result<Did> {
@@ -13,44 +16,9 @@ pub async fn resolve_handle(handle: &Handle) -> Result<Did> {
     }
 
     // Try DNS TXT record lookup first (more reliable for custom domains)
-    // For WASM, we can't do DNS lookups directly, so we use a DNS-over-HTTPS service
-    let dns_url = format!(
-        "https://dns.google/resolve?name=_atproto.{}&type=TXT",
-        urlencoding::encode(handle.as_str())
-    );
-
-    log::debug!("Resolving handle {} via DNS", handle);
-
-    match reqwest::get(&dns_url).await {
-        Ok(response) if response.status().is_success() => {
-            if let Ok(dns_response) = response.json::<serde_json::Value>().await {
-                // Extract DID from TXT record
-                if let Some(answers) = dns_response.get("Answer").and_then(|a| a.as_array()) {
-                    for answer in answers {
-                        if let Some(data) = answer.get("data").and_then(|d| d.as_str()) {
-                            // Remove quotes and look for did= prefix
-                            let data_clean = data.trim_matches('"');
-                            if let Some(did_str) = data_clean.strip_prefix("did=") {
-                                let did = Did::new(did_str.to_string());
-                                if did.validate() {
-                                    log::info!("Resolved {} to {} via DNS", handle, did);
-                                    return Ok(did);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        Ok(response) => {
-            log::debug!(
-                "DNS resolution failed with status {}, trying HTTPS",
-                response.status()
-            );
-        }
-        Err(e) => {
-            log::debug!("DNS resolution failed: {}, trying HTTPS", e);
-        }
+    if let Some(did) = resolve_handle_dns(handle).await {
+        log::info!("Resolved {} to {} via DNS", handle, did);
+        return Ok(did);
     }
 
     // Fallback to Bluesky AppView API (CORS-friendly)
@@ -115,14 +83,116 @@ pub async fn resolve_handle(handle: &Handle) -> Result<Did> {
     Ok(did)
 }
 
+/// Extract the DID from an `_atproto` TXT record value (`did=...`)
+#[cfg(any(feature = "wasm", feature = "native"))]
+fn did_from_txt(data: &str) -> Option<Did> {
+    // Remove quotes and look for did= prefix
+    let did = Did::new(data.trim_matches('"').strip_prefix("did=")?.to_string());
+    did.validate().then_some(did)
+}
+
+/// The system resolver, built on first use from the system DNS configuration
+#[cfg(feature = "native")]
+fn system_resolver() -> Option<&'static hickory_resolver::TokioAsyncResolver> {
+    use hickory_resolver::TokioAsyncResolver;
+
+    static RESOLVER: OnceLock<Option<TokioAsyncResolver>> = OnceLock::new();
+    RESOLVER
+        .get_or_init(|| match TokioAsyncResolver::tokio_from_system_conf() {
+            Ok(resolver) => Some(resolver),
+            Err(e) => {
+                log::debug!("System DNS configuration unavailable: {}", e);
+                None
+            }
+        })
+        .as_ref()
+}
+
+/// Look up the handle's `_atproto` TXT record with the system resolver
+#[cfg(feature = "native")]
+async fn resolve_handle_dns(handle: &Handle) -> Option<Did> {
+    let resolver = system_resolver()?;
+
+    log::debug!("Resolving handle {} via system DNS", handle);
+
+    match resolver
+        .txt_lookup(format!("_atproto.{}.", handle.as_str()))
+        .await
+    {
+        Ok(records) => did_from_txt_records(records.iter()),
+        Err(e) => {
+            log::debug!("DNS resolution failed: {}, trying HTTPS", e);
+            None
+        }
+    }
+}
+
+/// The DID in the first `did=` record of a TXT lookup (a record's strings are joined)
+#[cfg(feature = "native")]
+fn did_from_txt_records<'a>(
+    records: impl IntoIterator<Item = &'a hickory_resolver::proto::rr::rdata::TXT>,
+) -> Option<Did> {
+    records
+        .into_iter()
+        .find_map(|record| did_from_txt(&record.to_string()))
+}
+
+/// Look up the handle's `_atproto` TXT record over DNS-over-HTTPS
+///
+/// Browsers can't do DNS lookups directly, so this goes through a DoH service.
+#[cfg(all(feature = "wasm", not(feature = "native")))]
+async fn resolve_handle_dns(handle: &Handle) -> Option<Did> {
+    let dns_url = format!(
+        "https://dns.google/resolve?name=_atproto.{}&type=TXT",
+        urlencoding::encode(handle.as_str())
+    );
+
+    log::debug!("Resolving handle {} via DNS-over-HTTPS", handle);
+
+    match reqwest::get(&dns_url).await {
+        Ok(response) if response.status().is_success() => {
+            let dns_response = response.json::<serde_json::Value>().await.ok()?;
+            did_from_doh(&dns_response)
+        }
+        Ok(response) => {
+            log::debug!(
+                "DNS resolution failed with status {}, trying HTTPS",
+                response.status()
+            );
+            None
+        }
+        Err(e) => {
+            log::debug!("DNS resolution failed: {}, trying HTTPS", e);
+            None
+        }
+    }
+}
+
+/// Extract the DID from a DNS-over-HTTPS JSON response's TXT answers
+#[cfg(all(feature = "wasm", not(feature = "native")))]
+fn did_from_doh(response: &serde_json::Value) -> Option<Did> {
+    response
+        .get("Answer")
+        .and_then(|a| a.as_array())?
+        .iter()
+        .filter_map(|answer| answer.get("data").and_then(|d| d.as_str()))
+        .find_map(did_from_txt)
+}
+
+/// Without `native` or `wasm` there is no DNS lookup; handles resolve over HTTPS only
+#[cfg(not(any(feature = "wasm", feature = "native")))]
+async fn resolve_handle_dns(_handle: &Handle) -> Option<Did> {
+    None
+}
+
 mod urlencoding {
     pub fn encode(s: &str) -> String {
         url::form_urlencoded::byte_serialize(s.as_bytes()).collect()
     }
 }
 
-/// Resolve a DID to its PDS endpoint
-pub async fn resolve_did(did: &Did) -> Result<String> {
+/// Fetch the DID document for a DID
+pub async fn resolve_did_document(did: &Did) -> Result<serde_json::Value> {
     // For did:plc, use plc.directory
     if did.as_str().starts_with("did:plc:") {
         let url = format!("https://plc.directory/{}", did.as_str());
@@ -140,30 +210,10 @@ pub async fn resolve_did(did: &Did) -> Result<String> {
             )));
         }
 
-        let did_doc: serde_json::Value = response
+        response
             .json()
             .await
-            .map_err(|e| Error::HandleResolution(format!("Failed to parse DID document: {}", e)))?;
-
-        // Extract PDS endpoint from service array
-        if let Some(services) = did_doc.get("service").and_then(|s| s.as_array()) {
-            for service in services {
-                if let Some(service_type) = service.get("type").and_then(|t| t.as_str()) {
-                    if service_type == "AtprotoPersonalDataServer" {
-                        if let Some(endpoint) =
-                            service.get("serviceEndpoint").and_then(|e| e.as_str())
-                        {
-                            log::info!("Resolved {} to PDS: {}", did, endpoint);
-                            return Ok(endpoint.to_string());
-                        }
-                    }
-                }
-            }
-        }
-
-        Err(Error::HandleResolution(
-            "No PDS endpoint found in DID document".to_string(),
-        ))
+            .map_err(|e| Error::HandleResolution(format!("Failed to parse DID document: {}", e)))
     } else {
         Err(Error::HandleResolution(format!(
             "Unsupported DID method: {}",
@@ -171,3 +221,197 @@ pub async fn resolve_did(did: &Did) -> Result<String> {
         )))
     }
 }
+
+/// PDS endpoints resolved so far, keyed by DID
+///
+/// Accounts rarely move PDS, and a bulk analysis would otherwise fetch the same DID
+/// document for every page and post it touches.
+fn pds_cache() -> &'static Mutex<HashMap<String, String>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Resolve a DID to its PDS endpoint (memoized for the lifetime of the process)
+pub async fn resolve_did(did: &Did) -> Result<String> {
+    if let Some(endpoint) = pds_cache()
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(did.as_str()).cloned())
+    {
+        return Ok(endpoint);
+    }
+
+    let did_doc = resolve_did_document(did).await?;
+
+    let endpoint = service_endpoint(&did_doc, "AtprotoPersonalDataServer").ok_or_else(|| {
+        Error::HandleResolution("No PDS endpoint found in DID document".to_string())
+    })?;
+
+    log::info!("Resolved {} to PDS: {}", did, endpoint);
+
+    if let Ok(mut cache) = pds_cache().lock() {
+        cache.insert(did.as_str().to_string(), endpoint.clone());
+    }
+
+    Ok(endpoint)
+}
+
+/// Resolve a labeler DID to the endpoint serving its `com.atproto.label.queryLabels`
+pub async fn resolve_labeler_endpoint(did: &Did) -> Result<String> {
+    let did_doc = resolve_did_document(did).await?;
+
+    let endpoint = service_endpoint(&did_doc, "AtprotoLabeler").ok_or_else(|| {
+        Error::HandleResolution(format!("{} does not declare a labeler service", did))
+    })?;
+
+    log::info!("Resolved {} to labeler: {}", did, endpoint);
+    Ok(endpoint)
+}
+
+/// Find the endpoint of the first service of the given type in a DID document
+fn service_endpoint(did_doc: &serde_json::Value, service_type: &str) -> Option<String> {
+    did_doc
+        .get("service")
+        .and_then(|s| s.as_array())?
+        .iter()
+        .find(|service| service.get("type").and_then(|t| t.as_str()) == Some(service_type))
+        .and_then(|service| service.get("serviceEndpoint"))
+        .and_then(|e| e.as_str())
+        .map(|e| e.trim_end_matches('/').to_string())
+}
+
+/// Resolve the repository signing key (the `#atproto` verification method) for a DID
+pub async fn resolve_signing_key(did: &Did) -> Result<SigningKey> {
+    let did_doc = resolve_did_document(did).await?;
+
+    let multibase = signing_key_multibase(&did_doc, did).ok_or_else(|| {
+        Error::Verification(format!(
+            "No #atproto signing key in DID document for {}",
+            did
+        ))
+    })?;
+
+    SigningKey::from_multibase(multibase)
+}
+
+/// The `publicKeyMultibase` of a DID document's `#atproto` verification method
+fn signing_key_multibase<'a>(did_doc: &'a serde_json::Value, did: &Did) -> Option<&'a str> {
+    did_doc
+        .get("verificationMethod")
+        .and_then(|m| m.as_array())?
+        .iter()
+        .find(|method| {
+            method
+                .get("id")
+                .and_then(|id| id.as_str())
+                .is_some_and(|id| id == "#atproto" || id == format!("{}#atproto", did))
+        })
+        .and_then(|method| method.get("publicKeyMultibase"))
+        .and_then(|key| key.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DID: &str = "did:plc:4qqizocrnriintskkh6trnzv";
+
+    /// A recorded `plc.directory` response
+    fn did_document() -> serde_json::Value {
+        serde_json::from_str(include_str!("../tests/fixtures/did_document.json")).unwrap()
+    }
+
+    #[cfg(any(feature = "wasm", feature = "native"))]
+    #[test]
+    fn did_from_txt_reads_quoted_and_bare_records() {
+        let expected = Some(Did::new(DID.to_string()));
+        assert_eq!(did_from_txt(&format!("did={}", DID)), expected);
+        assert_eq!(did_from_txt(&format!("\"did={}\"", DID)), expected);
+    }
+
+    #[cfg(any(feature = "wasm", feature = "native"))]
+    #[test]
+    fn did_from_txt_rejects_other_records() {
+        assert_eq!(did_from_txt("v=spf1 include:_spf.example.com ~all"), None);
+        assert_eq!(did_from_txt("did=plc:4qqizocrnriintskkh6trnzv"), None);
+        assert_eq!(did_from_txt("did="), None);
+    }
+
+    #[test]
+    fn service_endpoint_finds_the_pds() {
+        assert_eq!(
+            service_endpoint(&did_document(), "AtprotoPersonalDataServer").as_deref(),
+            Some("https://morel.us-east.host.bsky.network")
+        );
+        assert_eq!(service_endpoint(&did_document(), "AtprotoLabeler"), None);
+        assert_eq!(
+            service_endpoint(&serde_json::json!({}), "AtprotoPersonalDataServer"),
+            None
+        );
+    }
+
+    #[test]
+    fn signing_key_comes_from_the_atproto_method() {
+        let did = Did::new(DID.to_string());
+        let doc = did_document();
+        let multibase = signing_key_multibase(&doc, &did).unwrap();
+        assert_eq!(
+            multibase,
+            "zQ3shXjHeiBuRCKmM36cuYnm7YEMzhGnCmCyW92sRJ9pribSF"
+        );
+        assert!(matches!(
+            SigningKey::from_multibase(multibase),
+            Ok(SigningKey::Secp256k1(_))
+        ));
+
+        // A relative id works too; a method for another DID doesn't
+        let mut relative = did_document();
+        relative["verificationMethod"][0]["id"] = "#atproto".into();
+        assert_eq!(signing_key_multibase(&relative, &did), Some(multibase));
+        let mut other = did_document();
+        other["verificationMethod"][0]["id"] = "did:plc:someoneelse#atproto".into();
+        assert_eq!(signing_key_multibase(&other, &did), None);
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn did_from_txt_records_takes_the_first_did_record() {
+        use hickory_resolver::proto::rr::rdata::TXT;
+
+        let records = [
+            TXT::new(vec!["v=spf1 -all".to_string()]),
+            // Long records arrive split into strings of up to 255 bytes
+            TXT::new(vec![
+                "did=did:plc:4qqiz".to_string(),
+                "ocrnriintskkh6trnzv".to_string(),
+            ]),
+            TXT::new(vec!["did=did:plc:someoneelse".to_string()]),
+        ];
+        assert_eq!(
+            did_from_txt_records(&records),
+            Some(Did::new(DID.to_string()))
+        );
+        assert_eq!(did_from_txt_records(&records[..1]), None);
+        assert_eq!(did_from_txt_records(&[] as &[TXT]), None);
+    }
+
+    #[cfg(all(feature = "wasm", not(feature = "native")))]
+    #[test]
+    fn did_from_doh_reads_the_txt_answer() {
+        // A recorded dns.google response for _atproto.alice.test
+        let response = serde_json::json!({
+            "Status": 0,
+            "TC": false,
+            "Question": [{ "name": "_atproto.alice.test.", "type": 16 }],
+            "Answer": [
+                { "name": "_atproto.alice.test.", "type": 16, "TTL": 300, "data": "v=spf1 -all" },
+                { "name": "_atproto.alice.test.", "type": 16, "TTL": 300, "data": format!("\"did={}\"", DID) },
+                { "name": "_atproto.alice.test.", "type": 16, "TTL": 300, "data": "did=did:plc:someoneelse" }
+            ]
+        });
+        assert_eq!(did_from_doh(&response), Some(Did::new(DID.to_string())));
+
+        let nxdomain = serde_json::json!({ "Status": 3, "TC": false });
+        assert_eq!(did_from_doh(&nxdomain), None);
+    }
+}
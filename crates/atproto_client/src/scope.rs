@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Which of an account's records a bulk analysis covers
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::AtRecord;
+
+/// Record collections a bulk analysis can include
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScopeCollection {
+    Posts,
+    /// Posts the account reposted (labels are checked on the reposted post)
+    Reposts,
+    /// Posts the account liked (labels are checked on the liked post)
+    Likes,
+    Lists,
+    Profile,
+}
+
+impl ScopeCollection {
+    pub const ALL: [ScopeCollection; 5] = [
+        ScopeCollection::Posts,
+        ScopeCollection::Reposts,
+        ScopeCollection::Likes,
+        ScopeCollection::Lists,
+        ScopeCollection::Profile,
+    ];
+
+    /// Lexicon NSID of the collection's records
+    pub fn nsid(&self) -> &'static str {
+        match self {
+            ScopeCollection::Posts => "app.bsky.feed.post",
+            ScopeCollection::Reposts => "app.bsky.feed.repost",
+            ScopeCollection::Likes => "app.bsky.feed.like",
+            ScopeCollection::Lists => "app.bsky.graph.list",
+            ScopeCollection::Profile => "app.bsky.actor.profile",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScopeCollection::Posts => "Posts",
+            ScopeCollection::Reposts => "Reposted posts",
+            ScopeCollection::Likes => "Liked posts",
+            ScopeCollection::Lists => "Lists",
+            ScopeCollection::Profile => "Profile",
+        }
+    }
+
+    /// Whether labels are checked on the record's subject rather than the record itself
+    fn targets_subject(&self) -> bool {
+        matches!(self, ScopeCollection::Reposts | ScopeCollection::Likes)
+    }
+}
+
+impl fmt::Display for ScopeCollection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ScopeCollection::Posts => "posts",
+            ScopeCollection::Reposts => "reposts",
+            ScopeCollection::Likes => "likes",
+            ScopeCollection::Lists => "lists",
+            ScopeCollection::Profile => "profile",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for ScopeCollection {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        ScopeCollection::ALL
+            .into_iter()
+            .find(|collection| collection.to_string() == s || collection.nsid() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown collection '{}' (expected posts, reposts, likes, lists or profile)",
+                    s
+                )
+            })
+    }
+}
+
+/// Which records a bulk analysis covers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisScope {
+    /// Most recent matching records to analyze; `None` analyzes all of them
+    pub max_records: Option<usize>,
+    /// Only records created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only records created before this time
+    pub until: Option<DateTime<Utc>>,
+    pub collections: Vec<ScopeCollection>,
+    /// Include replies, not just top-level posts
+    pub include_replies: bool,
+    /// Count labels whose `exp` has passed; by default only labels in effect count
+    #[serde(default)]
+    pub include_expired: bool,
+}
+
+impl Default for AnalysisScope {
+    fn default() -> Self {
+        Self {
+            max_records: Some(crate::analysis::DEFAULT_MAX_POSTS),
+            since: None,
+            until: None,
+            collections: vec![ScopeCollection::Posts],
+            include_replies: true,
+            include_expired: false,
+        }
+    }
+}
+
+impl AnalysisScope {
+    /// Whether a record of `collection` passes the date window and reply filter
+    ///
+    /// Records without a readable `createdAt` only match when there is no date window.
+    pub fn matches(&self, collection: ScopeCollection, record: &AtRecord) -> bool {
+        if !self.include_replies
+            && collection == ScopeCollection::Posts
+            && record.value.get("reply").is_some()
+        {
+            return false;
+        }
+
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+
+        match created_at(record) {
+            Some(created) => {
+                self.since.is_none_or(|since| created >= since)
+                    && self.until.is_none_or(|until| created < until)
+            }
+            None => false,
+        }
+    }
+
+    /// Whether a record is older than the window, so older records can be skipped
+    pub fn is_before_window(&self, record: &AtRecord) -> bool {
+        match (self.since, created_at(record)) {
+            (Some(since), Some(created)) => created < since,
+            _ => false,
+        }
+    }
+
+    /// Records in scope, newest first, as the subjects to check for labels
+    ///
+    /// Likes and reposts are replaced by the post they point at; a subject reached through
+    /// several records (e.g. a post that was also liked) is only kept once.
+    pub fn select(&self, records: Vec<(ScopeCollection, AtRecord)>) -> Vec<AtRecord> {
+        let mut subjects: Vec<AtRecord> = records
+            .into_iter()
+            .filter(|(collection, record)| self.matches(*collection, record))
+            .filter_map(|(collection, record)| subject(collection, record))
+            .collect();
+
+        subjects.sort_by_key(|record| std::cmp::Reverse(created_at(record)));
+
+        let mut seen = HashSet::new();
+        subjects.retain(|record| seen.insert(record.uri.clone()));
+
+        if let Some(max) = self.max_records {
+            subjects.truncate(max);
+        }
+        subjects
+    }
+}
+
+fn created_at(record: &AtRecord) -> Option<DateTime<Utc>> {
+    let created = record.value.get("createdAt")?.as_str()?;
+    DateTime::parse_from_rfc3339(created)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// The record whose labels matter: the liked or reposted post, or the record itself
+///
+/// Subjects stand in for posts we don't have; they keep the like or repost's `$type` and
+/// `createdAt` so they can be told apart and sorted.
+fn subject(collection: ScopeCollection, record: AtRecord) -> Option<AtRecord> {
+    if !collection.targets_subject() {
+        return Some(record);
+    }
+
+    let target = record.value.get("subject")?;
+    Some(AtRecord {
+        uri: target.get("uri")?.as_str()?.to_string(),
+        cid: target
+            .get("cid")
+            .and_then(|cid| cid.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        value: serde_json::json!({
+            "$type": collection.nsid(),
+            "createdAt": record.value.get("createdAt"),
+        }),
+    })
+}
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Extraction of author-applied self-labels from record values
+
+use crate::{AtRecord, Label, LabelProvenance};
+
+/// `$type` of the self-label union member in `app.bsky.*` records
+const SELF_LABELS_TYPE: &str = "com.atproto.label.defs#selfLabels";
+
+/// Extract self-labels from a record (post, profile, list, ...)
+///
+/// Self-labels are stored in the record as `labels: { $type: selfLabels, values: [{ val }] }`.
+/// They are returned as regular [`Label`]s with the author DID as `src`, the record's
+/// `createdAt` as `cts` and `self_applied` set, so they can be shown next to labeler output.
+pub fn extract_self_labels(record: &AtRecord) -> Vec<Label> {
+    let Some(labels) = record.value.get("labels") else {
+        return Vec::new();
+    };
+
+    // `labels` is an open union: without the selfLabels $type it isn't self-labels
+    let kind = labels.get("$type").and_then(|t| t.as_str());
+    if kind != Some(SELF_LABELS_TYPE) {
+        log::debug!(
+            "Ignoring labels of type {} on {}",
+            kind.unwrap_or("(none)"),
+            record.uri
+        );
+        return Vec::new();
+    }
+
+    let Some(author) = record.author_did() else {
+        log::warn!("Cannot attribute self-labels, no DID in {}", record.uri);
+        return Vec::new();
+    };
+
+    let created_at = record
+        .value
+        .get("createdAt")
+        .and_then(|t| t.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    labels
+        .get("values")
+        .and_then(|v| v.as_array())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.get("val").and_then(|val| val.as_str()))
+                .filter(|val| !val.is_empty())
+                .map(|val| Label {
+                    val: val.to_string(),
+                    uri: record.uri.clone(),
+                    cid: Some(record.cid.clone()),
+                    src: author.as_str().to_string(),
+                    cts: created_at.clone(),
+                    exp: None,
+                    neg: false,
+                    self_applied: true,
+                    provenance: Some(LabelProvenance {
+                        endpoint: record.uri.clone(),
+                        authenticated: false,
+                        queried_at: chrono::Utc::now(),
+                    }),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(labels: serde_json::Value) -> AtRecord {
+        AtRecord {
+            uri: "at://did:plc:4qqizocrnriintskkh6trnzv/app.bsky.feed.post/3lbxp7tlw4c2b"
+                .to_string(),
+            cid: "bafyreih4wqhzhqbjmbk7o5lbuxy5g2sbtqclrtx5dhvdkd2mj6ykkcwhau".to_string(),
+            value: serde_json::json!({
+                "$type": "app.bsky.feed.post",
+                "text": "beach day",
+                "createdAt": "2026-03-01T12:00:00Z",
+                "labels": labels,
+            }),
+        }
+    }
+
+    #[test]
+    fn reads_typed_self_labels() {
+        let labels = extract_self_labels(&record(serde_json::json!({
+            "$type": SELF_LABELS_TYPE,
+            "values": [{ "val": "nudity" }, { "val": "" }],
+        })));
+
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].val, "nudity");
+        assert_eq!(labels[0].src, "did:plc:4qqizocrnriintskkh6trnzv");
+        assert_eq!(labels[0].cts, "2026-03-01T12:00:00Z");
+        assert!(labels[0].self_applied);
+    }
+
+    #[test]
+    fn ignores_labels_without_the_self_labels_type() {
+        let untyped = serde_json::json!({ "values": [{ "val": "nudity" }] });
+        assert!(extract_self_labels(&record(untyped)).is_empty());
+
+        let other = serde_json::json!({
+            "$type": "com.example.labels#other",
+            "values": [{ "val": "nudity" }],
+        });
+        assert!(extract_self_labels(&record(other)).is_empty());
+    }
+}
@@ -0,0 +1,351 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Saved inspection results, and what changed between two of them
+//!
+//! A snapshot keeps just enough of a Single Check or Bulk Analysis to compare it with a
+//! later one: the labels in effect, the negations seen alongside them, and for bulk runs
+//! the scope and which records were checked.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{AnalysisScope, BulkAnalysis, Label, LabelCollection, LabelDiff};
+
+/// Which kind of inspection a snapshot was taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotKind {
+    Check,
+    Bulk,
+}
+
+impl SnapshotKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SnapshotKind::Check => "Single Check",
+            SnapshotKind::Bulk => "Bulk Analysis",
+        }
+    }
+}
+
+/// One inspection result of a subject, frozen in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// DID or AT-URI (resolved, so a handle change doesn't split the history)
+    pub subject: String,
+    pub kind: SnapshotKind,
+    pub taken_at: DateTime<Utc>,
+    /// Labels on the subject itself (the account, for bulk analyses)
+    pub labels: Vec<Label>,
+    /// Negations returned alongside `labels`, to tell negated labels from removed ones
+    #[serde(default)]
+    pub negations: Vec<Label>,
+    /// Bulk analyses only: the records checked and the labels on them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub records: Option<RecordSnapshot>,
+    /// Bulk analyses only: which records the run covered (missing from older snapshots)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<AnalysisScope>,
+}
+
+/// Records covered by a bulk analysis
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordSnapshot {
+    pub checked_uris: Vec<String>,
+    pub labels: Vec<Label>,
+}
+
+impl Snapshot {
+    /// Snapshot of a Single Check of `subject`
+    pub fn from_check(
+        subject: &str,
+        collection: &LabelCollection,
+        taken_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            subject: subject.to_string(),
+            kind: SnapshotKind::Check,
+            taken_at,
+            labels: collection.labels.clone(),
+            negations: collection
+                .raw_labels()
+                .into_iter()
+                .filter(|label| label.neg)
+                .collect(),
+            records: None,
+            scope: None,
+        }
+    }
+
+    /// Snapshot of a bulk analysis, keyed by the analyzed account's DID
+    pub fn from_analysis(analysis: &BulkAnalysis, taken_at: DateTime<Utc>) -> Self {
+        Self {
+            subject: analysis.checkpoint.did.clone(),
+            kind: SnapshotKind::Bulk,
+            taken_at,
+            labels: analysis.stats.account_labels.clone(),
+            negations: Vec::new(),
+            records: Some(RecordSnapshot {
+                checked_uris: analysis.checkpoint.queried_uris.clone(),
+                labels: analysis
+                    .labeled_posts
+                    .iter()
+                    .flat_map(|post| post.labels.iter().cloned())
+                    .collect(),
+            }),
+            scope: Some(analysis.checkpoint.scope.clone()),
+        }
+    }
+
+    /// Labels in effect when the snapshot was taken
+    pub fn active_labels(&self) -> impl Iterator<Item = &Label> {
+        self.labels
+            .iter()
+            .filter(|label| !label.neg && !label.is_expired_at(self.taken_at))
+    }
+}
+
+/// What changed from an older snapshot of a subject to a newer one
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    /// Labels on the subject itself
+    pub labels: LabelDiff,
+    /// Labels on records checked both times
+    pub record_labels: LabelDiff,
+    /// Records with labels in effect now that had none before
+    pub newly_labeled: Vec<String>,
+    /// Records checked before that are missing now, as far back as the newer run went
+    pub deleted: Vec<String>,
+    /// The runs had different scopes (or the older one's is unknown), so `newly_labeled`
+    /// and `deleted` are left empty: they would mostly reflect the scope change
+    pub scope_changed: bool,
+}
+
+impl SnapshotDiff {
+    pub fn between(older: &Snapshot, newer: &Snapshot) -> Self {
+        let labels = LabelDiff::between(
+            &older.labels,
+            older.taken_at,
+            &newer.labels,
+            &newer.negations,
+            newer.taken_at,
+        );
+
+        let (Some(before), Some(after)) = (&older.records, &newer.records) else {
+            return Self {
+                labels,
+                ..Self::default()
+            };
+        };
+        let scope_changed = older.scope.is_none() || older.scope != newer.scope;
+
+        let checked_before: HashSet<&str> =
+            before.checked_uris.iter().map(String::as_str).collect();
+        let checked_after: HashSet<&str> = after.checked_uris.iter().map(String::as_str).collect();
+        let on_both = |label: &&Label| {
+            checked_before.contains(label.uri.as_str())
+                && checked_after.contains(label.uri.as_str())
+        };
+        let labels_before: Vec<Label> = before.labels.iter().filter(on_both).cloned().collect();
+        let labels_after: Vec<Label> = after.labels.iter().filter(on_both).cloned().collect();
+        let record_labels = LabelDiff::between(
+            &labels_before,
+            older.taken_at,
+            &labels_after,
+            &[],
+            newer.taken_at,
+        );
+
+        if scope_changed {
+            return Self {
+                labels,
+                record_labels,
+                scope_changed,
+                ..Self::default()
+            };
+        }
+
+        let labeled_before = labeled_uris(&before.labels, older.taken_at);
+        let mut newly_labeled: Vec<String> = labeled_uris(&after.labels, newer.taken_at)
+            .into_iter()
+            .filter(|uri| !labeled_before.contains(uri))
+            .map(str::to_string)
+            .collect();
+        newly_labeled.sort();
+
+        Self {
+            labels,
+            record_labels,
+            newly_labeled,
+            deleted: deleted_records(&newer.subject, before, after),
+            scope_changed,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+            && self.record_labels.is_empty()
+            && self.newly_labeled.is_empty()
+            && self.deleted.is_empty()
+            && !self.scope_changed
+    }
+}
+
+fn labeled_uris(labels: &[Label], at: DateTime<Utc>) -> HashSet<&str> {
+    labels
+        .iter()
+        .filter(|label| !label.neg && !label.is_expired_at(at))
+        .map(|label| label.uri.as_str())
+        .collect()
+}
+
+/// Records of `did`'s repository in `before` but not `after`, per collection no older than
+/// the oldest record `after` checked
+///
+/// Record keys are TIDs, which sort by creation time, so a run limited to the most recent
+/// records only vouches for records at least as new as the oldest one it saw. (Runs with
+/// an `until` date can still report newer records as deleted.) Liked and reposted posts
+/// are checked too, but their keys were minted by other accounts and say nothing about
+/// how far back this account's records were read, so they are left out.
+fn deleted_records(did: &str, before: &RecordSnapshot, after: &RecordSnapshot) -> Vec<String> {
+    let checked_after: HashSet<&str> = after.checked_uris.iter().map(String::as_str).collect();
+    let mut oldest: HashMap<&str, &str> = HashMap::new();
+    for (_, collection, rkey) in own_records(did, &after.checked_uris) {
+        oldest
+            .entry(collection)
+            .and_modify(|first| *first = (*first).min(rkey))
+            .or_insert(rkey);
+    }
+
+    let mut deleted: Vec<String> = own_records(did, &before.checked_uris)
+        .filter(|(uri, _, _)| !checked_after.contains(uri))
+        .filter(|(_, collection, rkey)| oldest.get(collection).is_some_and(|first| first <= rkey))
+        .map(|(uri, _, _)| uri.to_string())
+        .collect();
+    deleted.sort();
+    deleted
+}
+
+/// (URI, collection, rkey) of the records in `did`'s repository
+fn own_records<'a>(
+    did: &'a str,
+    uris: &'a [String],
+) -> impl Iterator<Item = (&'a str, &'a str, &'a str)> {
+    uris.iter().filter_map(move |uri| {
+        let (repo, collection, rkey) = split_uri(uri)?;
+        (repo == did).then_some((uri.as_str(), collection, rkey))
+    })
+}
+
+/// (repository DID, collection, rkey) of a record AT-URI
+fn split_uri(uri: &str) -> Option<(&str, &str, &str)> {
+    let mut parts = uri.strip_prefix("at://")?.splitn(3, '/');
+    Some((parts.next()?, parts.next()?, parts.next()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DID: &str = "did:plc:4qqizocrnriintskkh6trnzv";
+
+    fn post(rkey: &str) -> String {
+        format!("at://{}/app.bsky.feed.post/{}", DID, rkey)
+    }
+
+    fn label(uri: &str) -> Label {
+        Label {
+            val: "spam".to_string(),
+            uri: uri.to_string(),
+            cid: None,
+            src: "did:plc:ar7c4by46qjdydhdevvrndac".to_string(),
+            cts: "2026-03-01T13:00:00Z".to_string(),
+            exp: None,
+            neg: false,
+            self_applied: false,
+            provenance: None,
+        }
+    }
+
+    fn bulk(checked: &[String], labeled: &[&str], scope: Option<AnalysisScope>) -> Snapshot {
+        Snapshot {
+            subject: DID.to_string(),
+            kind: SnapshotKind::Bulk,
+            taken_at: "2026-03-10T00:00:00Z".parse().unwrap(),
+            labels: Vec::new(),
+            negations: Vec::new(),
+            records: Some(RecordSnapshot {
+                checked_uris: checked.to_vec(),
+                labels: labeled.iter().map(|uri| label(uri)).collect(),
+            }),
+            scope,
+        }
+    }
+
+    #[test]
+    fn same_scope_reports_deleted_and_newly_labeled() {
+        let scope = Some(AnalysisScope::default());
+        let older = bulk(
+            &[post("3lbxq"), post("3lbxs"), post("3lbxa")],
+            &[],
+            scope.clone(),
+        );
+        let newer = bulk(&[post("3lbxt"), post("3lbxq")], &[&post("3lbxt")], scope);
+
+        let diff = SnapshotDiff::between(&older, &newer);
+        assert!(!diff.scope_changed);
+        // 3lbxa is older than anything the newer run read, so it may just be out of range
+        assert_eq!(diff.deleted, vec![post("3lbxs")]);
+        assert_eq!(diff.newly_labeled, vec![post("3lbxt")]);
+    }
+
+    #[test]
+    fn scope_change_is_not_compared() {
+        let older = bulk(
+            &[post("3lbxq"), post("3lbxp")],
+            &[],
+            Some(AnalysisScope::default()),
+        );
+        let wider = AnalysisScope {
+            max_records: None,
+            ..AnalysisScope::default()
+        };
+        let newer = bulk(&[post("3lbxq")], &[&post("3lbxq")], Some(wider));
+
+        let diff = SnapshotDiff::between(&older, &newer);
+        assert!(diff.scope_changed);
+        assert!(diff.deleted.is_empty());
+        assert!(diff.newly_labeled.is_empty());
+        // Records checked both times still compare
+        assert_eq!(diff.record_labels.changes.len(), 1);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn unknown_older_scope_is_not_compared() {
+        let older = bulk(&[post("3lbxq"), post("3lbxp")], &[], None);
+        let newer = bulk(&[post("3lbxq")], &[], Some(AnalysisScope::default()));
+
+        let diff = SnapshotDiff::between(&older, &newer);
+        assert!(diff.scope_changed);
+        assert!(diff.deleted.is_empty());
+    }
+
+    #[test]
+    fn liked_posts_stay_out_of_deleted_records() {
+        let liked_old = "at://did:plc:bob/app.bsky.feed.post/2aaaa".to_string();
+        let liked_new = "at://did:plc:carol/app.bsky.feed.post/3zzzz".to_string();
+        let scope = Some(AnalysisScope::default());
+        let older = bulk(
+            &[post("3lbxq"), post("3lbxp"), liked_new.clone()],
+            &[],
+            scope.clone(),
+        );
+        // An old liked post's key must not stretch how far back the newer run counts
+        let newer = bulk(&[post("3lbxq"), liked_old], &[], scope);
+
+        let diff = SnapshotDiff::between(&older, &newer);
+        assert!(diff.deleted.is_empty());
+    }
+}
@@ -0,0 +1,424 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Turning what people paste (handles, DIDs, AT-URIs, client web URLs) into a subject
+//!
+//! Web URLs are recognized by their path, so bsky.app and the clients that copy its
+//! routes (deer.social, main.bsky.dev, ...) all work, as do links that embed an AT-URI
+//! (pdsls.dev) or another link in a `url` query parameter (skyview.social).
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{resolve_handle, Did, Error, Handle, Result};
+
+/// An account or record to inspect, before its handle (if any) is resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subject {
+    /// Handle or DID of the account
+    pub actor: String,
+    /// Collection NSID and record key, for record subjects
+    pub record: Option<(String, String)>,
+}
+
+impl Subject {
+    /// Normalize a handle (`@` optional), DID, AT-URI or web URL
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(Error::UnrecognizedInput(
+                "enter a handle, DID, AT-URI or Bluesky link".to_string(),
+            ));
+        }
+
+        if let Some(rest) = input.strip_prefix("at://") {
+            return parse_at_uri(rest);
+        }
+        if input.starts_with("did:") {
+            return account(input);
+        }
+        if input.starts_with("https://") || input.starts_with("http://") {
+            return parse_url(input);
+        }
+        // A link pasted without its scheme, e.g. bsky.app/profile/alice.bsky.social
+        if input.contains('/') {
+            return parse_url(&format!("https://{}", input));
+        }
+
+        account(input.trim_start_matches('@'))
+    }
+
+    /// Split a pasted list or text/CSV file into subjects, in order and without repeats
+    ///
+    /// Entries are separated by newlines, commas, semicolons or tabs. On a line with
+    /// several cells only those that look like subjects are kept, so extra CSV columns
+    /// (report reasons, dates) and a header row drop out. Any later line with none keeps its
+    /// first cell, to be reported as unrecognized rather than silently skipped.
+    pub fn split_list(text: &str) -> Vec<String> {
+        let mut seen = std::collections::HashSet::new();
+        let mut subjects = Vec::new();
+
+        for (index, line) in text.lines().filter(|l| !l.trim().is_empty()).enumerate() {
+            let cells: Vec<&str> = line
+                .split([',', ';', '\t'])
+                .map(|cell| cell.trim().trim_matches(['"', '\'']).trim())
+                .filter(|cell| !cell.is_empty())
+                .collect();
+            let mut found: Vec<&str> = cells
+                .iter()
+                .copied()
+                .filter(|cell| Subject::parse(cell).is_ok())
+                .collect();
+            // Nothing recognizable: a header if first, otherwise worth an error row
+            if found.is_empty() && index > 0 {
+                found.extend(cells.first());
+            }
+
+            for cell in found {
+                if seen.insert(cell.to_string()) {
+                    subjects.push(cell.to_string());
+                }
+            }
+        }
+        subjects
+    }
+
+    /// Whether this is an account rather than one of its records
+    pub fn is_account(&self) -> bool {
+        self.record.is_none()
+    }
+
+    /// Whether this is an `app.bsky.feed.post` record
+    pub fn is_post(&self) -> bool {
+        matches!(&self.record, Some((collection, _)) if collection == "app.bsky.feed.post")
+    }
+
+    /// Whether this is an `app.bsky.graph.list` or a starter pack (whose members are a list)
+    pub fn is_list(&self) -> bool {
+        matches!(
+            &self.record,
+            Some((collection, _))
+                if collection == "app.bsky.graph.list" || collection == "app.bsky.graph.starterpack"
+        )
+    }
+
+    /// Resolve the actor to a DID, giving the label subject (DID or AT-URI) and the DID
+    pub async fn resolve(&self) -> Result<(String, Did)> {
+        let did = if self.actor.starts_with("did:") {
+            Did::new(self.actor.clone())
+        } else {
+            resolve_handle(&Handle::new(self.actor.clone())).await?
+        };
+
+        let subject = match &self.record {
+            Some((collection, rkey)) => format!("at://{}/{}/{}", did, collection, rkey),
+            None => did.as_str().to_string(),
+        };
+        Ok((subject, did))
+    }
+}
+
+/// The actor, or an AT-URI whose authority may still be a handle
+impl fmt::Display for Subject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.record {
+            Some((collection, rkey)) => write!(f, "at://{}/{}/{}", self.actor, collection, rkey),
+            None => f.write_str(&self.actor),
+        }
+    }
+}
+
+impl FromStr for Subject {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+fn account(actor: &str) -> Result<Subject> {
+    Ok(Subject {
+        actor: actor_name(actor)?,
+        record: None,
+    })
+}
+
+fn record(actor: &str, collection: &str, rkey: &str) -> Result<Subject> {
+    Ok(Subject {
+        actor: actor_name(actor)?,
+        record: Some((collection.to_string(), rkey.to_string())),
+    })
+}
+
+/// Check that `actor` is a DID or handle, lowercasing handles
+fn actor_name(actor: &str) -> Result<String> {
+    if actor.starts_with("did:") {
+        let did = Did::new(actor.to_string());
+        return if did.validate() {
+            Ok(actor.to_string())
+        } else {
+            Err(Error::InvalidDid(actor.to_string()))
+        };
+    }
+
+    let handle = Handle::new(actor.to_ascii_lowercase());
+    if handle.validate() && !handle.as_str().contains(['/', ' ', '@']) {
+        Ok(handle.0)
+    } else {
+        Err(Error::UnrecognizedInput(format!(
+            "'{}' is not a handle, DID, AT-URI or Bluesky link",
+            actor
+        )))
+    }
+}
+
+/// `at://` already stripped; accepts an account (`at://did`) or a record
+fn parse_at_uri(rest: &str) -> Result<Subject> {
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    match path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()[..]
+    {
+        [actor] => account(actor),
+        [actor, collection, rkey] => record(actor, collection, rkey),
+        _ => Err(Error::InvalidAtUri(format!(
+            "at://{} (expected at://<did or handle>[/<collection>/<rkey>])",
+            rest
+        ))),
+    }
+}
+
+fn parse_url(input: &str) -> Result<Subject> {
+    // pdsls.dev and other record browsers put the AT-URI straight in the path
+    if let Some(start) = input.find("at://") {
+        return parse_at_uri(&input[start + "at://".len()..]);
+    }
+
+    let url = url::Url::parse(input)
+        .map_err(|e| Error::UnrecognizedInput(format!("{} is not a valid link: {}", input, e)))?;
+
+    // Viewers that wrap a Bluesky link, e.g. skyview.social/?url=https://bsky.app/...
+    if let Some((_, inner)) = url
+        .query_pairs()
+        .find(|(key, _)| key == "url" || key == "uri")
+    {
+        return Subject::parse(&inner);
+    }
+
+    let segments: Vec<String> = url
+        .path_segments()
+        .map(|segments| {
+            segments
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    s.replace("%3A", ":")
+                        .replace("%3a", ":")
+                        .replace("%40", "@")
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    match segments[..] {
+        ["profile", actor, "post", rkey, ..] => record(actor, "app.bsky.feed.post", rkey),
+        ["profile", actor, "feed", rkey, ..] => record(actor, "app.bsky.feed.generator", rkey),
+        ["profile", actor, "lists", rkey, ..] => record(actor, "app.bsky.graph.list", rkey),
+        ["profile", _, "post" | "feed" | "lists"] => Err(Error::UnrecognizedInput(format!(
+            "{} is missing the record key after /{}",
+            input, segments[2]
+        ))),
+        // Profile tabs (followers, likes, ...) still point at the account
+        ["profile", actor, ..] => account(actor),
+        ["starter-pack", actor, rkey, ..] => record(actor, "app.bsky.graph.starterpack", rkey),
+        // Clients with Mastodon-style profile links
+        [actor] if actor.starts_with('@') => account(&actor[1..]),
+        _ => Err(Error::UnrecognizedInput(format!(
+            "{} is not a profile, post, feed, list or starter pack link",
+            input
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DID: &str = "did:plc:4qqizocrnriintskkh6trnzv";
+
+    fn subject(actor: &str, record: Option<(&str, &str)>) -> Subject {
+        Subject {
+            actor: actor.to_string(),
+            record: record.map(|(collection, rkey)| (collection.to_string(), rkey.to_string())),
+        }
+    }
+
+    #[test]
+    fn parses_every_accepted_shape() {
+        let post = Some(("app.bsky.feed.post", "3lbxq2zmv7k2a"));
+        let cases = [
+            ("alice.bsky.social", subject("alice.bsky.social", None)),
+            ("  @Alice.Bsky.Social ", subject("alice.bsky.social", None)),
+            (DID, subject(DID, None)),
+            ("at://alice.bsky.social", subject("alice.bsky.social", None)),
+            (
+                "at://did:plc:4qqizocrnriintskkh6trnzv/app.bsky.feed.post/3lbxq2zmv7k2a",
+                subject(DID, post),
+            ),
+            (
+                "https://bsky.app/profile/alice.bsky.social",
+                subject("alice.bsky.social", None),
+            ),
+            (
+                "https://bsky.app/profile/alice.bsky.social/followers",
+                subject("alice.bsky.social", None),
+            ),
+            (
+                "https://bsky.app/profile/did:plc:4qqizocrnriintskkh6trnzv/post/3lbxq2zmv7k2a",
+                subject(DID, post),
+            ),
+            (
+                "https://bsky.app/profile/did%3Aplc%3A4qqizocrnriintskkh6trnzv/post/3lbxq2zmv7k2a",
+                subject(DID, post),
+            ),
+            (
+                "https://deer.social/profile/alice.bsky.social/post/3lbxq2zmv7k2a?ref=share",
+                subject("alice.bsky.social", post),
+            ),
+            (
+                "https://bsky.app/profile/alice.bsky.social/feed/whats-hot",
+                subject("alice.bsky.social", Some(("app.bsky.feed.generator", "whats-hot"))),
+            ),
+            (
+                "https://bsky.app/profile/alice.bsky.social/lists/3kxyz2abc3d2e",
+                subject("alice.bsky.social", Some(("app.bsky.graph.list", "3kxyz2abc3d2e"))),
+            ),
+            (
+                "https://bsky.app/starter-pack/alice.bsky.social/3lsp4abc5d2e",
+                subject(
+                    "alice.bsky.social",
+                    Some(("app.bsky.graph.starterpack", "3lsp4abc5d2e")),
+                ),
+            ),
+            (
+                "https://pdsls.dev/at://did:plc:4qqizocrnriintskkh6trnzv/app.bsky.feed.post/3lbxq2zmv7k2a",
+                subject(DID, post),
+            ),
+            (
+                "https://skyview.social/?url=https://bsky.app/profile/alice.bsky.social/post/3lbxq2zmv7k2a",
+                subject("alice.bsky.social", post),
+            ),
+            (
+                "bsky.app/profile/alice.bsky.social/post/3lbxq2zmv7k2a",
+                subject("alice.bsky.social", post),
+            ),
+            (
+                "https://mastodon.example/@alice.bsky.social",
+                subject("alice.bsky.social", None),
+            ),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(Subject::parse(input).unwrap(), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_unrecognized_input() {
+        let cases = [
+            "",
+            "   ",
+            "alice",
+            "did:",
+            "at://did:plc:4qqizocrnriintskkh6trnzv/app.bsky.feed.post",
+            "https://bsky.app/profile/alice.bsky.social/post",
+            "https://bsky.app/search?q=spam",
+            "https://bsky.app/",
+            "https://[not a host/profile/alice.bsky.social",
+        ];
+
+        for input in cases {
+            assert!(
+                Subject::parse(input).is_err(),
+                "{:?} should be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn errors_name_the_problem() {
+        assert!(matches!(
+            Subject::parse("at://alice.bsky.social/app.bsky.feed.post"),
+            Err(Error::InvalidAtUri(_))
+        ));
+        assert!(matches!(Subject::parse("did:"), Err(Error::InvalidDid(_))));
+        match Subject::parse("https://[not a host/profile/alice.bsky.social") {
+            Err(Error::UnrecognizedInput(message)) => {
+                assert!(message.contains("not a valid link"), "{}", message)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match Subject::parse("https://bsky.app/search?q=spam") {
+            Err(Error::UnrecognizedInput(message)) => {
+                assert!(message.contains("not a profile, post"), "{}", message)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn kinds_and_display() {
+        let post = Subject::parse("https://bsky.app/profile/alice.bsky.social/post/3lbx").unwrap();
+        assert!(post.is_post() && !post.is_account() && !post.is_list());
+        assert_eq!(
+            post.to_string(),
+            "at://alice.bsky.social/app.bsky.feed.post/3lbx"
+        );
+
+        let pack = Subject::parse("https://bsky.app/starter-pack/alice.bsky.social/3lsp").unwrap();
+        assert!(pack.is_list());
+        assert!(Subject::parse(DID).unwrap().is_account());
+    }
+
+    #[test]
+    fn split_list_skips_a_header_row() {
+        let text = "handle,reason\nalice.bsky.social,spam\nbob.bsky.social,impersonation\n";
+        assert_eq!(
+            Subject::split_list(text),
+            vec!["alice.bsky.social", "bob.bsky.social"]
+        );
+    }
+
+    #[test]
+    fn split_list_keeps_subjects_from_any_column() {
+        let text = format!(
+            "\"alice.bsky.social\";2026-03-01\n2026-03-02\t{}\n\n  @carol.example.com , note\n",
+            DID
+        );
+        assert_eq!(
+            Subject::split_list(&text),
+            vec!["alice.bsky.social", DID, "@carol.example.com"]
+        );
+    }
+
+    #[test]
+    fn split_list_drops_repeats() {
+        let text = "alice.bsky.social\nbob.bsky.social, alice.bsky.social\nalice.bsky.social\n";
+        assert_eq!(
+            Subject::split_list(text),
+            vec!["alice.bsky.social", "bob.bsky.social"]
+        );
+    }
+
+    #[test]
+    fn split_list_keeps_the_first_cell_of_unrecognized_lines() {
+        let text = "alice.bsky.social\nnot a subject, still not\nalso-not-one\n";
+        let subjects = Subject::split_list(text);
+        assert_eq!(
+            subjects,
+            vec!["alice.bsky.social", "not a subject", "also-not-one"]
+        );
+        // Left in so that checking them reports the error
+        assert!(Subject::parse(&subjects[1]).is_err());
+    }
+}
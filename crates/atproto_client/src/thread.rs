@@ -0,0 +1,355 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! A post's whole thread (parents and replies) flattened for labeling and display
+//!
+//! Built from an `app.bsky.feed.getPostThread` response. Posts are kept in reading order:
+//! the root first, then each post followed by its replies, with a depth for indenting.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::count_label_values;
+use crate::{AtRecord, Label, UserInfo};
+
+/// One post in a thread, or a placeholder for one that can't be shown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadPost {
+    pub uri: String,
+    /// Author DID, taken from the URI so deleted and blocked posts have one too
+    pub did: String,
+    /// Missing for deleted and blocked posts
+    pub author: Option<UserInfo>,
+    pub text: String,
+    pub created_at: String,
+    /// Levels below the thread root (the root is 0)
+    pub depth: usize,
+    /// URI of the post this replies to, if it is in the thread
+    pub parent: Option<String>,
+    pub reply_count: usize,
+    pub like_count: usize,
+    pub repost_count: usize,
+    /// Why the post can't be shown ("not found", "blocked")
+    pub unavailable: Option<String>,
+    /// Labels on the post in effect, self-labels included
+    pub labels: Vec<Label>,
+}
+
+impl ThreadPost {
+    /// Handle of the author if known, otherwise the DID
+    pub fn author_name(&self) -> &str {
+        self.author
+            .as_ref()
+            .map_or(self.did.as_str(), |author| author.handle.as_str())
+    }
+}
+
+/// Labels on every post and author in a thread
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PostThread {
+    /// The post the thread was requested for
+    pub uri: String,
+    /// Root first, each post followed by its replies
+    pub posts: Vec<ThreadPost>,
+    /// Account labels per author DID, for authors that have any
+    pub author_labels: HashMap<String, Vec<Label>>,
+    /// Posts and authors whose label query failed
+    pub unchecked: usize,
+    /// Expired labels left out
+    pub expired_labels: usize,
+    /// Why labeling stopped before every post and author was checked
+    pub interruption: Option<String>,
+}
+
+impl PostThread {
+    /// Flatten a `getPostThread` response's `thread`, anchored at `uri`
+    pub fn from_view(uri: &str, thread: &serde_json::Value) -> Self {
+        // Parents are nested upwards from the anchor; collect them root first
+        let mut ancestors = Vec::new();
+        let mut parent = thread.get("parent").filter(|view| view.is_object());
+        while let Some(view) = parent {
+            ancestors.push(view);
+            parent = view.get("parent").filter(|view| view.is_object());
+        }
+        ancestors.reverse();
+
+        let mut posts = Vec::new();
+        let mut parent_uri = None;
+        for (depth, view) in ancestors.iter().enumerate() {
+            let post = thread_post(view, depth, parent_uri.take());
+            parent_uri = Some(post.uri.clone());
+            posts.push(post);
+        }
+        add_with_replies(&mut posts, thread, ancestors.len(), parent_uri);
+
+        Self {
+            uri: uri.to_string(),
+            posts,
+            ..Self::default()
+        }
+    }
+
+    /// DIDs of everyone who posted in the thread, in order of first appearance
+    pub fn authors(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.posts
+            .iter()
+            .filter(|post| seen.insert(post.did.as_str()))
+            .map(|post| post.did.clone())
+            .collect()
+    }
+
+    /// Account labels on a post's author
+    pub fn labels_on_author(&self, post: &ThreadPost) -> &[Label] {
+        self.author_labels
+            .get(&post.did)
+            .map_or(&[], |labels| labels.as_slice())
+    }
+
+    /// Posts that are labeled themselves or by a labeled author
+    pub fn labeled_posts(&self) -> impl Iterator<Item = &ThreadPost> {
+        self.posts
+            .iter()
+            .filter(|post| !post.labels.is_empty() || !self.labels_on_author(post).is_empty())
+    }
+
+    /// Replies below the anchor post and how many of them come from labeled authors
+    pub fn labeled_replies(&self) -> (usize, usize) {
+        let Some(anchor) = self.posts.iter().position(|post| post.uri == self.uri) else {
+            return (0, 0);
+        };
+        let depth = self.posts[anchor].depth;
+        let replies: Vec<&ThreadPost> = self.posts[anchor + 1..]
+            .iter()
+            .take_while(|post| post.depth > depth)
+            .collect();
+        let labeled = replies
+            .iter()
+            .filter(|post| !self.labels_on_author(post).is_empty())
+            .count();
+        (replies.len(), labeled)
+    }
+
+    /// Label values with the number of posts and authors carrying each, most common first
+    pub fn label_counts(&self) -> Vec<(String, usize)> {
+        let on_posts = self.posts.iter().map(|post| post.labels.as_slice());
+        count_label_values(on_posts.chain(self.author_labels.values().map(Vec::as_slice)))
+    }
+}
+
+/// Add `view` and, depth first, every reply below it
+fn add_with_replies(
+    posts: &mut Vec<ThreadPost>,
+    view: &serde_json::Value,
+    depth: usize,
+    parent: Option<String>,
+) {
+    let post = thread_post(view, depth, parent);
+    let uri = post.uri.clone();
+    posts.push(post);
+
+    for reply in view
+        .get("replies")
+        .and_then(|r| r.as_array())
+        .into_iter()
+        .flatten()
+    {
+        add_with_replies(posts, reply, depth + 1, Some(uri.clone()));
+    }
+}
+
+/// Read a `threadViewPost`, `notFoundPost` or `blockedPost`
+fn thread_post(view: &serde_json::Value, depth: usize, parent: Option<String>) -> ThreadPost {
+    let post = view.get("post");
+    let uri = post
+        .and_then(|p| p.get("uri"))
+        .or_else(|| view.get("uri"))
+        .and_then(|u| u.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let did = uri
+        .strip_prefix("at://")
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or_default()
+        .to_string();
+
+    let unavailable = if view.get("blocked").and_then(|b| b.as_bool()) == Some(true) {
+        Some("blocked".to_string())
+    } else if post.is_none() {
+        Some("not found".to_string())
+    } else {
+        None
+    };
+
+    let record = post.and_then(|p| p.get("record"));
+    let text_field = |field: &str| {
+        record
+            .and_then(|r| r.get(field))
+            .and_then(|t| t.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let count = |field: &str| {
+        post.and_then(|p| p.get(field))
+            .and_then(|c| c.as_u64())
+            .unwrap_or(0) as usize
+    };
+    let labels = match (post, record) {
+        (Some(post), Some(record)) => AtRecord {
+            uri: uri.clone(),
+            cid: post
+                .get("cid")
+                .and_then(|c| c.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            value: record.clone(),
+        }
+        .self_labels(),
+        _ => Vec::new(),
+    };
+
+    ThreadPost {
+        author: post
+            .and_then(|p| p.get("author"))
+            .and_then(UserInfo::from_profile),
+        text: text_field("text"),
+        created_at: text_field("createdAt"),
+        depth,
+        parent,
+        reply_count: count("replyCount"),
+        like_count: count("likeCount"),
+        repost_count: count("repostCount"),
+        unavailable,
+        labels,
+        uri,
+        did,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "did:plc:alicealicealicealicealic";
+    const ANCHOR: &str = "at://did:plc:bobbobbobbobbobbobbobbob/app.bsky.feed.post/3lbxq2zmv7k2b";
+
+    /// A recorded `getPostThread` response for a reply, with its parent and replies
+    fn thread() -> PostThread {
+        let response: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/get_post_thread.json")).unwrap();
+        PostThread::from_view(ANCHOR, &response["thread"])
+    }
+
+    fn account_label(did: &str, val: &str) -> Label {
+        Label {
+            val: val.to_string(),
+            uri: did.to_string(),
+            cid: None,
+            src: "did:plc:ar7c4by46qjdydhdevvrndac".to_string(),
+            cts: "2026-03-01T00:00:00Z".to_string(),
+            exp: None,
+            neg: false,
+            self_applied: false,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn from_view_flattens_parents_then_replies() {
+        let thread = thread();
+        let rkeys: Vec<(&str, usize)> = thread
+            .posts
+            .iter()
+            .map(|post| (post.uri.rsplit('/').next().unwrap(), post.depth))
+            .collect();
+        assert_eq!(
+            rkeys,
+            [
+                ("3lbxq2zmv7k2a", 0),
+                ("3lbxq2zmv7k2b", 1),
+                ("3lbxq2zmv7k2c", 2),
+                ("3lbxq2zmv7k2d", 3),
+                ("3lbxq2zmv7k2e", 2),
+                ("3lbxq2zmv7k2f", 2),
+            ]
+        );
+
+        let [root, anchor, spam, ..] = &thread.posts[..] else {
+            unreachable!()
+        };
+        assert_eq!(root.parent, None);
+        assert_eq!(root.author_name(), "alice.bsky.social");
+        assert_eq!((root.like_count, root.repost_count), (10, 2));
+        assert_eq!(anchor.parent.as_ref(), Some(&root.uri));
+        assert_eq!(anchor.text, "Agreed, and here's why");
+        assert_eq!(spam.parent.as_deref(), Some(ANCHOR));
+    }
+
+    #[test]
+    fn from_view_keeps_unavailable_posts() {
+        let thread = thread();
+        let not_found = &thread.posts[3];
+        assert_eq!(not_found.unavailable.as_deref(), Some("not found"));
+        assert_eq!(not_found.did, "did:plc:davedavedavedavedavedave");
+        assert_eq!(not_found.parent, Some(thread.posts[2].uri.clone()));
+        assert_eq!(not_found.author_name(), not_found.did);
+
+        let blocked = &thread.posts[4];
+        assert_eq!(blocked.unavailable.as_deref(), Some("blocked"));
+        assert_eq!(blocked.did, "did:plc:eveeveeveeveeveeveeveeve");
+        assert!(blocked.author.is_none());
+        assert!(blocked.text.is_empty());
+    }
+
+    #[test]
+    fn from_view_reads_self_labels() {
+        let thread = thread();
+        let self_labeled = &thread.posts[5];
+        assert_eq!(self_labeled.labels.len(), 1);
+        assert_eq!(self_labeled.labels[0].val, "porn");
+        assert!(self_labeled.labels[0].self_applied);
+        assert!(thread.posts[..5].iter().all(|post| post.labels.is_empty()));
+    }
+
+    #[test]
+    fn authors_are_listed_once_in_order() {
+        assert_eq!(
+            thread().authors(),
+            [
+                ALICE,
+                "did:plc:bobbobbobbobbobbobbobbob",
+                "did:plc:carolcarolcarolcarolcaro",
+                "did:plc:davedavedavedavedavedave",
+                "did:plc:eveeveeveeveeveeveeveeve",
+            ]
+        );
+    }
+
+    #[test]
+    fn labeled_replies_count_below_the_anchor() {
+        let mut thread = thread();
+        assert_eq!(thread.labeled_replies(), (4, 0));
+
+        // Alice wrote the root too, which is not a reply
+        thread
+            .author_labels
+            .insert(ALICE.to_string(), vec![account_label(ALICE, "spam")]);
+        assert_eq!(thread.labeled_replies(), (4, 1));
+        let labeled: Vec<&str> = thread
+            .labeled_posts()
+            .map(|post| post.uri.rsplit('/').next().unwrap())
+            .collect();
+        assert_eq!(labeled, ["3lbxq2zmv7k2a", "3lbxq2zmv7k2f"]);
+
+        thread.uri = "at://did:plc:someoneelse/app.bsky.feed.post/3lbxq2zmv7k2z".to_string();
+        assert_eq!(thread.labeled_replies(), (0, 0));
+    }
+
+    #[test]
+    fn label_counts_include_author_labels() {
+        let mut thread = thread();
+        thread
+            .author_labels
+            .insert(ALICE.to_string(), vec![account_label(ALICE, "porn")]);
+        assert_eq!(thread.label_counts(), [("porn".to_string(), 2)]);
+    }
+}
@@ -0,0 +1,358 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Label history for a subject: applications, negations and expiries in order
+//!
+//! Built from the raw labeler responses rather than `LabelCollection::labels`, which
+//! drops negations.
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Label, LabelCollection};
+
+/// (src, uri, val): one labeler's label on one subject, across re-applications
+type LabelKey = (String, String, String);
+
+/// What happened to a label at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelineEventKind {
+    Applied,
+    Negated,
+    Expired,
+}
+
+impl TimelineEventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimelineEventKind::Applied => "Applied",
+            TimelineEventKind::Negated => "Negated",
+            TimelineEventKind::Expired => "Expired",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            TimelineEventKind::Applied => "➕",
+            TimelineEventKind::Negated => "↩️",
+            TimelineEventKind::Expired => "⌛",
+        }
+    }
+}
+
+/// One entry on the timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    pub at: DateTime<Utc>,
+    pub kind: TimelineEventKind,
+    /// The label as emitted (for expiries, the application that expired)
+    pub label: Label,
+}
+
+/// A stretch of time during which one labeler's label was in effect on a subject
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelSpan {
+    pub val: String,
+    pub src: String,
+    pub uri: String,
+    pub self_applied: bool,
+    pub applied_at: DateTime<Utc>,
+    /// When the label stopped being in effect; `None` while it still is
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Why it ended: `Negated` or `Expired`
+    pub ended_by: Option<TimelineEventKind>,
+}
+
+impl LabelSpan {
+    pub fn is_active(&self) -> bool {
+        self.ended_at.is_none()
+    }
+}
+
+/// Chronological history of every label seen for a subject
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LabelTimeline {
+    /// Oldest first
+    pub events: Vec<TimelineEvent>,
+    /// Ordered by when they started
+    pub spans: Vec<LabelSpan>,
+}
+
+impl LabelTimeline {
+    /// History from a check's raw responses, plus its self-labels
+    pub fn from_collection(collection: &LabelCollection, now: DateTime<Utc>) -> Self {
+        let mut labels = collection.raw_labels();
+        labels.extend(
+            collection
+                .labels
+                .iter()
+                .filter(|label| label.self_applied)
+                .cloned(),
+        );
+
+        Self::from_labels(labels, now)
+    }
+
+    /// History of `labels`, negations included; labels with unreadable timestamps are skipped
+    pub fn from_labels(labels: impl IntoIterator<Item = Label>, now: DateTime<Utc>) -> Self {
+        // The same label can come back from several labelers' endpoints or pages
+        let mut seen = HashSet::new();
+        let mut by_label: BTreeMap<LabelKey, Vec<(DateTime<Utc>, Label)>> = BTreeMap::new();
+        for label in labels {
+            let Some(cts) = parse_time(&label.cts) else {
+                log::warn!(
+                    "Skipping label {} with unreadable cts {}",
+                    label.val,
+                    label.cts
+                );
+                continue;
+            };
+            let key = (label.src.clone(), label.uri.clone(), label.val.clone());
+            if seen.insert((key.clone(), label.cts.clone(), label.neg)) {
+                by_label.entry(key).or_default().push((cts, label));
+            }
+        }
+
+        let mut timeline = LabelTimeline::default();
+        for mut history in by_label.into_values() {
+            history.sort_by_key(|(cts, label)| (*cts, label.neg));
+
+            let mut open: Option<(LabelSpan, Option<DateTime<Utc>>, Label)> = None;
+            for (cts, label) in history {
+                // An application that lapsed before this entry ended by expiring
+                if let Some((span, Some(exp), applied)) = &open {
+                    if *exp <= cts {
+                        timeline.close(span.clone(), *exp, TimelineEventKind::Expired, applied);
+                        open = None;
+                    }
+                }
+
+                if label.neg {
+                    if let Some((span, _, _)) = open.take() {
+                        timeline.close(span, cts, TimelineEventKind::Negated, &label);
+                    }
+                    timeline.push(cts, TimelineEventKind::Negated, label);
+                    continue;
+                }
+
+                let exp = label.exp.as_deref().and_then(parse_time);
+                // A re-application while in effect only refreshes the expiry
+                let span = match open.take() {
+                    Some((span, _, _)) => span,
+                    None => LabelSpan {
+                        val: label.val.clone(),
+                        src: label.src.clone(),
+                        uri: label.uri.clone(),
+                        self_applied: label.self_applied,
+                        applied_at: cts,
+                        ended_at: None,
+                        ended_by: None,
+                    },
+                };
+                open = Some((span, exp, label.clone()));
+                timeline.push(cts, TimelineEventKind::Applied, label);
+            }
+
+            if let Some((span, exp, applied)) = open {
+                match exp {
+                    Some(exp) if exp <= now => {
+                        timeline.close(span, exp, TimelineEventKind::Expired, &applied)
+                    }
+                    _ => timeline.spans.push(span),
+                }
+            }
+        }
+
+        timeline.events.sort_by_key(|event| event.at);
+        timeline.spans.sort_by_key(|span| span.applied_at);
+        timeline
+    }
+
+    /// Spans still in effect
+    pub fn active(&self) -> impl Iterator<Item = &LabelSpan> {
+        self.spans.iter().filter(|span| span.is_active())
+    }
+
+    /// Whether any label was ever negated or expired
+    pub fn has_reversals(&self) -> bool {
+        self.spans.iter().any(|span| !span.is_active())
+    }
+
+    fn push(&mut self, at: DateTime<Utc>, kind: TimelineEventKind, label: Label) {
+        self.events.push(TimelineEvent { at, kind, label });
+    }
+
+    fn close(
+        &mut self,
+        mut span: LabelSpan,
+        at: DateTime<Utc>,
+        kind: TimelineEventKind,
+        label: &Label,
+    ) {
+        span.ended_at = Some(at);
+        span.ended_by = Some(kind);
+        self.spans.push(span);
+        if kind == TimelineEventKind::Expired {
+            self.push(at, kind, label.clone());
+        }
+    }
+}
+
+fn parse_time(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LabelProvenance;
+
+    const SRC: &str = "did:plc:ar7c4by46qjdydhdevvrndac";
+    const URI: &str = "at://did:plc:4qqizocrnriintskkh6trnzv/app.bsky.feed.post/3lbxkcjh5ek2d";
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        parse_time(timestamp).unwrap()
+    }
+
+    fn label(cts: &str, exp: Option<&str>, neg: bool) -> Label {
+        Label {
+            val: "spam".to_string(),
+            uri: URI.to_string(),
+            cid: None,
+            src: SRC.to_string(),
+            cts: cts.to_string(),
+            exp: exp.map(str::to_string),
+            neg,
+            self_applied: false,
+            provenance: None,
+        }
+    }
+
+    fn kinds(timeline: &LabelTimeline) -> Vec<TimelineEventKind> {
+        timeline.events.iter().map(|event| event.kind).collect()
+    }
+
+    #[test]
+    fn negation_closes_the_span() {
+        let timeline = LabelTimeline::from_labels(
+            [
+                label("2026-03-01T00:00:00Z", None, false),
+                label("2026-03-02T00:00:00Z", None, true),
+            ],
+            at("2026-03-10T00:00:00Z"),
+        );
+
+        assert_eq!(timeline.spans.len(), 1);
+        let span = &timeline.spans[0];
+        assert_eq!(span.applied_at, at("2026-03-01T00:00:00Z"));
+        assert_eq!(span.ended_at, Some(at("2026-03-02T00:00:00Z")));
+        assert_eq!(span.ended_by, Some(TimelineEventKind::Negated));
+        assert_eq!(
+            kinds(&timeline),
+            [TimelineEventKind::Applied, TimelineEventKind::Negated]
+        );
+        assert!(timeline.has_reversals());
+        assert_eq!(timeline.active().count(), 0);
+    }
+
+    #[test]
+    fn passed_expiry_closes_the_span_as_expired() {
+        let timeline = LabelTimeline::from_labels(
+            [label(
+                "2026-03-01T00:00:00Z",
+                Some("2026-03-05T00:00:00Z"),
+                false,
+            )],
+            at("2026-03-10T00:00:00Z"),
+        );
+
+        let span = &timeline.spans[0];
+        assert_eq!(span.ended_at, Some(at("2026-03-05T00:00:00Z")));
+        assert_eq!(span.ended_by, Some(TimelineEventKind::Expired));
+        assert_eq!(
+            kinds(&timeline),
+            [TimelineEventKind::Applied, TimelineEventKind::Expired]
+        );
+
+        // Before the expiry the label is still in effect
+        let earlier = LabelTimeline::from_labels(
+            [label(
+                "2026-03-01T00:00:00Z",
+                Some("2026-03-05T00:00:00Z"),
+                false,
+            )],
+            at("2026-03-04T00:00:00Z"),
+        );
+        assert_eq!(earlier.active().count(), 1);
+    }
+
+    #[test]
+    fn reapplication_refreshes_the_expiry() {
+        let timeline = LabelTimeline::from_labels(
+            [
+                label("2026-03-01T00:00:00Z", Some("2026-03-05T00:00:00Z"), false),
+                label("2026-03-04T00:00:00Z", Some("2026-03-20T00:00:00Z"), false),
+            ],
+            at("2026-03-10T00:00:00Z"),
+        );
+
+        assert_eq!(timeline.spans.len(), 1);
+        let span = &timeline.spans[0];
+        assert!(span.is_active());
+        assert_eq!(span.applied_at, at("2026-03-01T00:00:00Z"));
+        assert_eq!(
+            kinds(&timeline),
+            [TimelineEventKind::Applied, TimelineEventKind::Applied]
+        );
+
+        // Re-applied only after it lapsed: two spans, the first expired
+        let lapsed = LabelTimeline::from_labels(
+            [
+                label("2026-03-01T00:00:00Z", Some("2026-03-05T00:00:00Z"), false),
+                label("2026-03-06T00:00:00Z", None, false),
+            ],
+            at("2026-03-10T00:00:00Z"),
+        );
+        assert_eq!(lapsed.spans.len(), 2);
+        assert_eq!(lapsed.spans[0].ended_by, Some(TimelineEventKind::Expired));
+        assert!(lapsed.spans[1].is_active());
+    }
+
+    #[test]
+    fn duplicates_from_two_endpoints_collapse() {
+        let from = |endpoint: &str| Label {
+            provenance: Some(LabelProvenance {
+                endpoint: endpoint.to_string(),
+                authenticated: false,
+                queried_at: at("2026-03-10T00:00:00Z"),
+            }),
+            ..label("2026-03-01T00:00:00Z", None, false)
+        };
+        let timeline = LabelTimeline::from_labels(
+            [
+                from("https://mod.bsky.app"),
+                from("https://ozone.example.com"),
+            ],
+            at("2026-03-10T00:00:00Z"),
+        );
+
+        assert_eq!(timeline.spans.len(), 1);
+        assert_eq!(timeline.events.len(), 1);
+        assert!(timeline.spans[0].is_active());
+    }
+
+    #[test]
+    fn negation_without_an_application() {
+        let timeline = LabelTimeline::from_labels(
+            [label("2026-03-02T00:00:00Z", None, true)],
+            at("2026-03-10T00:00:00Z"),
+        );
+
+        assert!(timeline.spans.is_empty());
+        assert_eq!(kinds(&timeline), [TimelineEventKind::Negated]);
+        assert!(!timeline.has_reversals());
+    }
+}
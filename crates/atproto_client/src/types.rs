@@ -79,9 +79,57 @@ pub struct Label {
     /// Negation flag (if true, removes a previously applied label)
     #[serde(default)]
     pub neg: bool,
+
+    /// Set for labels the author declared in the record itself (self-labels)
+    /// rather than labels emitted by a labeler service
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub self_applied: bool,
+
+    /// Where this label was read from; not part of the label as signed by the labeler
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<LabelProvenance>,
+}
+
+/// Where a label was obtained, kept as evidence for exports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelProvenance {
+    /// Labeler service URL that answered, or the record's AT-URI for self-labels
+    pub endpoint: String,
+    /// Whether the query carried an access token
+    pub authenticated: bool,
+    pub queried_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A labeler's unmodified answer to one queryLabels request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelerResponse {
+    /// Full request URL, including the subjects queried
+    pub url: String,
+    pub authenticated: bool,
+    pub queried_at: chrono::DateTime<chrono::Utc>,
+    pub status: u16,
+    pub body: serde_json::Value,
 }
 
 impl Label {
+    /// When the label stops applying, if it has a readable `exp`
+    pub fn expires_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let exp = self.exp.as_deref()?;
+        chrono::DateTime::parse_from_rfc3339(exp)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+    }
+
+    /// Whether the label's `exp` had passed at `now`
+    pub fn is_expired_at(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.expires_at().is_some_and(|exp| exp <= now)
+    }
+
+    /// Whether the label's `exp` has passed
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(chrono::Utc::now())
+    }
+
     /// Get the category of this label
     pub fn category(&self) -> LabelCategory {
         LabelCategory::from_value(&self.val)
@@ -138,6 +186,20 @@ pub struct AtRecord {
     pub value: serde_json::Value,
 }
 
+impl AtRecord {
+    /// DID of the repository this record lives in, taken from the AT-URI authority
+    pub fn author_did(&self) -> Option<Did> {
+        let authority = self.uri.strip_prefix("at://")?.split('/').next()?;
+        let did = Did::new(authority.to_string());
+        did.validate().then_some(did)
+    }
+
+    /// Self-labels the author attached to this record
+    pub fn self_labels(&self) -> Vec<Label> {
+        crate::self_labels::extract_self_labels(self)
+    }
+}
+
 /// Response from listRecords endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListRecordsResponse {
@@ -147,16 +209,79 @@ pub struct ListRecordsResponse {
     pub cursor: Option<String>,
 }
 
+/// Basic account info as returned in AppView actor lists (likers, reposters)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserInfo {
+    pub did: String,
+    pub handle: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+}
+
+impl UserInfo {
+    /// Read an `app.bsky.actor.defs#profileView(Basic)` object
+    pub fn from_profile(profile: &serde_json::Value) -> Option<Self> {
+        Some(Self {
+            did: profile.get("did")?.as_str()?.to_string(),
+            handle: profile.get("handle")?.as_str()?.to_string(),
+            display_name: profile
+                .get("displayName")
+                .and_then(|n| n.as_str())
+                .map(str::to_string),
+        })
+    }
+}
+
 /// Collection of labels with metadata
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LabelCollection {
     pub labels: Vec<Label>,
+    /// Labeler queried (the first one, for merged collections); see each label's provenance
     pub labeler_did: String,
     pub query_timestamp: chrono::DateTime<chrono::Utc>,
+    /// Raw responses the labels were read from, in request order
+    #[serde(default)]
+    pub responses: Vec<LabelerResponse>,
+}
+
+impl LabelCollection {
+    /// Add the labels and responses of another query, e.g. to a different labeler
+    pub fn merge(&mut self, other: LabelCollection) {
+        self.labels.extend(other.labels);
+        self.responses.extend(other.responses);
+    }
+
+    /// Every label in the raw responses, negations included, stamped with where it came from
+    pub fn raw_labels(&self) -> Vec<Label> {
+        self.responses
+            .iter()
+            .flat_map(|response| {
+                let provenance = LabelProvenance {
+                    endpoint: response
+                        .url
+                        .split("/xrpc/")
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    authenticated: response.authenticated,
+                    queried_at: response.queried_at,
+                };
+                serde_json::from_value::<LabelsResponse>(response.body.clone())
+                    .map(|page| page.labels)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(move |mut label| {
+                        label.provenance = Some(provenance.clone());
+                        label
+                    })
+            })
+            .collect()
+    }
 }
 
 /// Label categories for grouping
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum LabelCategory {
     AdultContent,
     Violence,
@@ -0,0 +1,340 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Authenticity checks for repository data: commit signatures and record inclusion proofs
+
+use std::collections::HashMap;
+
+use crate::dagcbor::{self, Ipld};
+use crate::encoding::{base58_decode, read_varint};
+use crate::repo::{mst_entry, Commit};
+use crate::{AtRecord, CarFile, Cid, Did, Error, Result};
+
+/// Multicodec prefix of a compressed secp256k1 public key
+const SECP256K1_PUB: u64 = 0xe7;
+
+/// Multicodec prefix of a compressed NIST P-256 public key
+const P256_PUB: u64 = 0x1200;
+
+/// A repository signing key from a DID document (`#atproto` verification method)
+#[derive(Debug, Clone)]
+pub enum SigningKey {
+    Secp256k1(k256::ecdsa::VerifyingKey),
+    P256(p256::ecdsa::VerifyingKey),
+}
+
+impl SigningKey {
+    /// Parse a `publicKeyMultibase` value (`z` + base58btc multicodec-prefixed key)
+    pub fn from_multibase(multibase: &str) -> Result<Self> {
+        let encoded = multibase.strip_prefix('z').ok_or_else(|| {
+            Error::Verification(format!("Unsupported key multibase: {}", multibase))
+        })?;
+        let bytes = base58_decode(encoded)?;
+        let (codec, len) = read_varint(&bytes)?;
+        let key = &bytes[len..];
+
+        match codec {
+            SECP256K1_PUB => k256::ecdsa::VerifyingKey::from_sec1_bytes(key)
+                .map(Self::Secp256k1)
+                .map_err(|e| Error::Verification(format!("Invalid secp256k1 key: {}", e))),
+            P256_PUB => p256::ecdsa::VerifyingKey::from_sec1_bytes(key)
+                .map(Self::P256)
+                .map_err(|e| Error::Verification(format!("Invalid P-256 key: {}", e))),
+            other => Err(Error::Verification(format!(
+                "Unsupported key type 0x{:x}",
+                other
+            ))),
+        }
+    }
+
+    /// Verify a 64-byte compact (r||s) ECDSA signature over SHA-256 of `message`
+    ///
+    /// ATproto requires low-S signatures, so high-S variants are rejected even if they
+    /// would otherwise verify.
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<()> {
+        use k256::ecdsa::signature::Verifier;
+
+        let invalid = |e: k256::ecdsa::Error| Error::Verification(format!("Bad signature: {}", e));
+
+        match self {
+            Self::Secp256k1(key) => {
+                let sig = k256::ecdsa::Signature::from_slice(signature).map_err(invalid)?;
+                if sig.normalize_s().is_some() {
+                    return Err(Error::Verification("High-S signature".to_string()));
+                }
+                key.verify(message, &sig).map_err(invalid)
+            }
+            Self::P256(key) => {
+                let sig = p256::ecdsa::Signature::from_slice(signature).map_err(invalid)?;
+                if sig.normalize_s().is_some() {
+                    return Err(Error::Verification("High-S signature".to_string()));
+                }
+                key.verify(message, &sig).map_err(invalid)
+            }
+        }
+    }
+}
+
+/// A record proven to be part of a signed repository commit
+#[derive(Debug, Clone)]
+pub struct VerifiedRecord {
+    pub record: AtRecord,
+
+    /// CID of the signed commit the proof was checked against
+    pub commit_cid: Cid,
+
+    /// Repository revision of that commit
+    pub rev: Option<String>,
+}
+
+/// Verify a `com.atproto.sync.getRecord` proof CAR for `collection/rkey` in `did`'s repo
+///
+/// Checks the commit signature, then walks the MST from the signed root down to the
+/// record, verifying the hash of every block on the path.
+pub fn verify_record_proof(
+    car: &CarFile,
+    did: &Did,
+    collection: &str,
+    rkey: &str,
+    key: &SigningKey,
+) -> Result<VerifiedRecord> {
+    let commit_cid = car
+        .roots
+        .first()
+        .ok_or_else(|| Error::Verification("Proof has no root commit".to_string()))?;
+    let commit_block = verified_block(&car.blocks, commit_cid)?;
+    let commit = Commit::from_ipld(&dagcbor::decode(commit_block)?)?;
+
+    if commit.did != did.as_str() {
+        return Err(Error::Verification(format!(
+            "Commit is for {}, expected {}",
+            commit.did, did
+        )));
+    }
+    commit.verify_signature(key)?;
+
+    let record_key = format!("{}/{}", collection, rkey);
+    let record_cid =
+        mst_lookup(&car.blocks, &commit.data, record_key.as_bytes())?.ok_or_else(|| {
+            Error::Verification(format!("{} is not in the signed repository", record_key))
+        })?;
+    let record_block = verified_block(&car.blocks, &record_cid)?;
+
+    Ok(VerifiedRecord {
+        record: AtRecord {
+            uri: format!("at://{}/{}", did, record_key),
+            cid: record_cid.to_string(),
+            value: dagcbor::decode(record_block)?.to_json(),
+        },
+        commit_cid: commit_cid.clone(),
+        rev: commit.rev,
+    })
+}
+
+fn verified_block<'a>(blocks: &'a HashMap<Cid, Vec<u8>>, cid: &Cid) -> Result<&'a [u8]> {
+    let block = blocks
+        .get(cid)
+        .ok_or_else(|| Error::Verification(format!("Proof is missing block {}", cid)))?;
+    cid.verify_block(block)?;
+    Ok(block)
+}
+
+/// Find the value CID for `key` by descending the MST, verifying each node on the path
+fn mst_lookup(blocks: &HashMap<Cid, Vec<u8>>, root: &Cid, key: &[u8]) -> Result<Option<Cid>> {
+    let mut node_cid = root.clone();
+
+    // Bounded like the full walk; each step goes one level down
+    for _ in 0..64 {
+        let node = dagcbor::decode(verified_block(blocks, &node_cid)?)?;
+        let mut subtree = node.get("l").and_then(Ipld::as_link).cloned();
+        let mut last_key = Vec::new();
+
+        for entry in node.get("e").and_then(Ipld::as_list).unwrap_or_default() {
+            let (entry_key, value) = mst_entry(entry, &last_key)?;
+
+            match key.cmp(entry_key.as_slice()) {
+                std::cmp::Ordering::Equal => return Ok(Some(value)),
+                // The key sorts before this entry, so it can only be in the subtree to its left
+                std::cmp::Ordering::Less => break,
+                std::cmp::Ordering::Greater => {
+                    subtree = entry.get("t").and_then(Ipld::as_link).cloned();
+                    last_key = entry_key;
+                }
+            }
+        }
+
+        match subtree {
+            Some(cid) => node_cid = cid,
+            None => return Ok(None),
+        }
+    }
+
+    Err(Error::Verification("MST is too deep".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::Digest;
+
+    use super::*;
+    use crate::Repository;
+
+    /// A repository export signed with [`test_key`]: commit, two MST nodes, three records
+    const REPO_CAR: &[u8] = include_bytes!("../tests/fixtures/repo.car");
+
+    const DID: &str = "did:plc:4qqizocrnriintskkh6trnzv";
+
+    /// The secp256k1 key the fixture was signed with
+    fn test_key() -> k256::ecdsa::SigningKey {
+        let secret = sha2::Sha256::digest(b"at-peek test repository key");
+        k256::ecdsa::SigningKey::from_slice(&secret).unwrap()
+    }
+
+    fn public_key() -> SigningKey {
+        SigningKey::Secp256k1(*test_key().verifying_key())
+    }
+
+    fn did() -> Did {
+        Did::new(DID.to_string())
+    }
+
+    #[test]
+    fn repository_signature_verifies() {
+        let repo = Repository::from_car(REPO_CAR).unwrap();
+        repo.verify(&public_key()).unwrap();
+    }
+
+    #[test]
+    fn other_key_is_rejected() {
+        let repo = Repository::from_car(REPO_CAR).unwrap();
+        let other = k256::ecdsa::SigningKey::from_slice(&[7; 32]).unwrap();
+        assert!(repo
+            .verify(&SigningKey::Secp256k1(*other.verifying_key()))
+            .is_err());
+    }
+
+    #[test]
+    fn high_s_signature_is_rejected() {
+        use k256::ecdsa::signature::Signer;
+
+        let message = b"commit bytes";
+        let low: k256::ecdsa::Signature = test_key().sign(message);
+        public_key().verify(message, &low.to_bytes()).unwrap();
+
+        // (r, n - s) verifies under plain ECDSA, but ATproto only accepts low-S
+        let (r, s) = low.split_scalars();
+        let high = k256::ecdsa::Signature::from_scalars(r, -s).unwrap();
+        assert!(high.normalize_s().is_some());
+        let err = public_key().verify(message, &high.to_bytes()).unwrap_err();
+        assert!(err.to_string().contains("High-S"));
+    }
+
+    #[test]
+    fn malformed_signatures_are_rejected() {
+        let key = public_key();
+        assert!(key.verify(b"commit bytes", &[]).is_err());
+        assert!(key.verify(b"commit bytes", &[0; 64]).is_err());
+        assert!(key.verify(b"commit bytes", &[1; 63]).is_err());
+    }
+
+    #[test]
+    fn tampered_blocks_are_rejected() {
+        let mut car = CarFile::parse(REPO_CAR).unwrap();
+        let repo = Repository::from_car_file(car.clone()).unwrap();
+        let (_, record) = repo.entries().unwrap().pop().unwrap();
+
+        let mut block = car.blocks[&record].clone();
+        *block.last_mut().unwrap() ^= 1;
+        car.blocks.insert(record, block);
+
+        let repo = Repository::from_car_file(car).unwrap();
+        assert!(repo.verify(&public_key()).is_err());
+    }
+
+    #[test]
+    fn record_proof_walks_the_mst() {
+        let car = CarFile::parse(REPO_CAR).unwrap();
+
+        // In the right subtree of the root entry
+        let verified = verify_record_proof(
+            &car,
+            &did(),
+            "app.bsky.feed.post",
+            "3lbxq2zmv7k2a",
+            &public_key(),
+        )
+        .unwrap();
+        assert_eq!(
+            verified.record.uri,
+            format!("at://{}/app.bsky.feed.post/3lbxq2zmv7k2a", DID)
+        );
+        assert_eq!(verified.record.value["text"], "Still posting, still plain");
+        assert_eq!(&verified.commit_cid, &car.roots[0]);
+        assert_eq!(verified.rev.as_deref(), Some("3lby2abcdek2e"));
+
+        // In the root node itself
+        let profile = verify_record_proof(
+            &car,
+            &did(),
+            "app.bsky.actor.profile",
+            "self",
+            &public_key(),
+        )
+        .unwrap();
+        assert_eq!(profile.record.value["displayName"], "Alice");
+    }
+
+    #[test]
+    fn record_proof_rejects_absent_keys_and_other_accounts() {
+        let car = CarFile::parse(REPO_CAR).unwrap();
+
+        for (collection, rkey) in [
+            ("app.bsky.feed.post", "3lbxkcjh5ek2c"),
+            ("app.bsky.feed.post", "3lbxzzzzzzzzz"),
+            ("app.bsky.actor.profile", "aaaa"),
+        ] {
+            let err =
+                verify_record_proof(&car, &did(), collection, rkey, &public_key()).unwrap_err();
+            assert!(err.to_string().contains("not in the signed repository"));
+        }
+
+        let other = Did::new("did:plc:someoneelse".to_string());
+        assert!(verify_record_proof(
+            &car,
+            &other,
+            "app.bsky.feed.post",
+            "3lbxq2zmv7k2a",
+            &public_key()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn record_proof_rejects_missing_or_tampered_nodes() {
+        let car = CarFile::parse(REPO_CAR).unwrap();
+        let repo = Repository::from_car_file(car.clone()).unwrap();
+        let root = repo.commit().data.clone();
+
+        let mut missing = car.clone();
+        missing.blocks.remove(&root);
+        assert!(verify_record_proof(
+            &missing,
+            &did(),
+            "app.bsky.feed.post",
+            "3lbxq2zmv7k2a",
+            &public_key()
+        )
+        .is_err());
+
+        let mut tampered = car;
+        let mut node = tampered.blocks[&root].clone();
+        *node.last_mut().unwrap() ^= 1;
+        tampered.blocks.insert(root, node);
+        assert!(verify_record_proof(
+            &tampered,
+            &did(),
+            "app.bsky.feed.post",
+            "3lbxq2zmv7k2a",
+            &public_key()
+        )
+        .is_err());
+    }
+}